@@ -1,22 +1,73 @@
 use serde_derive::Deserialize;
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config{
     pub mount_config: MountConfig,
     pub s3_config: S3Config,
     pub metaserver_config: MetaServerConfig,
+    #[serde(default)]
+    pub meta_cache_config: MetaCacheConfig,
     pub zone_config: ZoneConfig,
     pub segment_configs: Vec<SegmentConfig>,
     pub disk_cache_config: DiskCacheConfig,
     pub backend_store_config: BackendStoreConfig,
     pub heartbeat_config: HeartbeatConfig,
+    #[serde(default)]
+    pub leader_rpc_config: LeaderRpcConfig,
+    #[serde(default)]
+    pub follower_config: FollowerConfig,
+    #[serde(default)]
+    pub write_buffer_config: WriteBufferConfig,
     pub log_path_config: LogPathConfig,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct MountConfig {
     pub mnt: String,
+    // mount read-only, by default is false.
+    #[serde(default)]
+    pub read_only: bool,
+    // allow the other users to access the mount, by default is false.
+    #[serde(default)]
+    pub allow_other: bool,
+    // let the kernel check the permission by the file mode, by default is false.
+    #[serde(default)]
+    pub default_permissions: bool,
+    // the source shown in /proc/mounts.
+    #[serde(default = "default_fsname")]
+    pub fsname: String,
+    // the type shown as fuse.<subtype> in /proc/mounts.
+    #[serde(default = "default_fsname")]
+    pub subtype: String,
+    // max size of a read request in bytes, 0 means the kernel default.
+    #[serde(default)]
+    pub max_read: u32,
+    // max size of a write handed to the leader at once in bytes, 0 means no limit.
+    #[serde(default)]
+    pub max_write: u32,
+    // how long the kernel caches the file attributes in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    pub attr_timeout_ms: u64,
+    // how long the kernel caches the dir entries in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    pub entry_timeout_ms: u64,
+    // how long to wait for the dirty data to be flushed on unmount in milliseconds.
+    #[serde(default = "default_shutdown_timeout_ms")]
+    pub shutdown_timeout_ms: u64,
+}
+
+fn default_fsname() -> String {
+    String::from("yigfs")
+}
+
+fn default_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_shutdown_timeout_ms() -> u64 {
+    30000
 }
 #[derive(Deserialize, Debug, Clone)]
 pub struct S3Config {
@@ -32,6 +83,42 @@ pub struct MetaServerConfig {
     pub thread_num: u32,
 }
 
+// the ttls of the client side meta cache in milliseconds, 0 disables the cache of that kind.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MetaCacheConfig {
+    // the file attributes.
+    pub attr_ttl_ms: u64,
+    // the dir entries found by lookup.
+    pub entry_ttl_ms: u64,
+    // the names which don't exist.
+    pub negative_ttl_ms: u64,
+    // the dir listings of readdir.
+    pub dir_ttl_ms: u64,
+    // max number of the cached items of each kind, the expired ones are dropped once it is exceeded.
+    pub capacity: usize,
+}
+
+// the cache is off by default, the meta server doesn't push the invalidations of the changes
+// made by the other machines yet, so the cached items may be stale until their ttls expire.
+impl Default for MetaCacheConfig {
+    fn default() -> Self {
+        MetaCacheConfig{
+            attr_ttl_ms: 0,
+            entry_ttl_ms: 0,
+            negative_ttl_ms: 0,
+            dir_ttl_ms: 0,
+            capacity: 100000,
+        }
+    }
+}
+
+impl MetaCacheConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.attr_ttl_ms > 0 || self.entry_ttl_ms > 0 || self.negative_ttl_ms > 0 || self.dir_ttl_ms > 0
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct ZoneConfig {
     pub zone: String,
@@ -54,11 +141,96 @@ pub struct DiskCacheConfig{
 pub struct BackendStoreConfig {
     pub backend_type: u32,
     pub settings: HashMap<String, String>,
+    // whether fsync waits until the data is uploaded into backend store, by default is false.
+    #[serde(default)]
+    pub fsync_wait_upload: bool,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct HeartbeatConfig{
     pub timeout: u64,
+    // how long the leaderships of this machine last without heartbeat in seconds,
+    // the files are taken over by the other machines after that.
+    #[serde(default = "default_lease_timeout")]
+    pub lease_timeout: u64,
+}
+
+fn default_lease_timeout() -> u64 {
+    15
+}
+
+// the rpc between the clients, the files led by other machines are accessed through it.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LeaderRpcConfig {
+    // the ip address which the leader server listens on.
+    pub bind_addr: String,
+    // the port which the leader server listens on, the peers are reached by machine:port.
+    pub port: u16,
+    // shared by all the machines, the requests of the peers without it are rejected.
+    // the leader server is started only if both bind_addr and secret are set.
+    pub secret: String,
+    // how long to wait for the response of a peer in milliseconds.
+    pub timeout_ms: u64,
+    // how long to wait for a leader to move a file to another machine in milliseconds,
+    // it is longer than timeout_ms because the data of the file is uploaded first.
+    pub migrate_timeout_ms: u64,
+}
+
+impl Default for LeaderRpcConfig {
+    fn default() -> Self {
+        LeaderRpcConfig{
+            bind_addr: String::new(),
+            port: 9877,
+            secret: String::new(),
+            timeout_ms: 10000,
+            migrate_timeout_ms: 600000,
+        }
+    }
+}
+
+// the files led by other machines can be read from the backend store without contacting their leaders.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct FollowerConfig {
+    // the read-only opens of the files led by other machines read from the backend store, by default is false.
+    pub enable: bool,
+    // cache the segments read by the follower in the local cache store, by default is false.
+    pub cache: bool,
+}
+
+// the consecutive writes of a file are coalesced in memory before they are written into the cache store.
+// the write is replied once it is buffered, so the error of writing the buffer is returned only by
+// the later flush, fsync or close of the file.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WriteBufferConfig {
+    // the buffer of a file is written once it reaches size in bytes, 0 disables the buffer.
+    pub size: u64,
+    // the buffer of a file is written if it isn't written in delay_ms milliseconds,
+    // it is at least MIN_WRITE_BUFFER_DELAY_MS, so that the buffer threads don't spin.
+    pub delay_ms: u64,
+    // the number of the threads writing the buffers, the buffer of a file is always written by the same one.
+    pub threads: u64,
+}
+
+impl Default for WriteBufferConfig {
+    fn default() -> Self {
+        // the writes are not buffered by default.
+        WriteBufferConfig{
+            size: 0,
+            delay_ms: 100,
+            threads: 4,
+        }
+    }
+}
+
+pub const MIN_WRITE_BUFFER_DELAY_MS: u64 = 10;
+
+impl WriteBufferConfig {
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(std::cmp::max(self.delay_ms, MIN_WRITE_BUFFER_DELAY_MS))
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum Errno{
     // success
     Esucc = 0,
@@ -16,6 +16,8 @@ pub enum Errno{
     Enotf = 6,
     // not support
     Enotsupp = 7,
+    // try again, such as the lock is held by others.
+    Eagain = 8,
     // not space left
     Enospc = 28,
     // access denied
@@ -24,9 +26,85 @@ pub enum Errno{
     Erange = 10,
     // offset err
     Eoffset = 11,
+    // operation not permitted
+    Eperm = 12,
+    // file name too long
+    Enametoolong = 13,
+    // is a directory
+    Eisdir = 14,
+    // the meta server cannot be reached
+    Etimedout = 15,
+    // read-only file system
+    Erofs = 16,
+    // invalid argument
+    Einval = 17,
+    // not a directory
+    Enotdir = 18,
+    // directory not empty
+    Enotempty = 19,
+    // no such attribute
+    Enodata = 20,
 }
 
 impl Errno {
+    // convert to the libc errno which is replied to the kernel, 0 for success.
+    // the numbering of Errno is internal, this is the only mapping to libc.
+    pub fn to_libc(&self) -> i32 {
+        match *self {
+            Errno::Esucc => 0,
+            Errno::Eintr => libc::EIO,
+            Errno::Enoent => libc::ENOENT,
+            Errno::Eexists => libc::EEXIST,
+            Errno::Eseek => libc::ESPIPE,
+            Errno::Eeof => libc::EIO,
+            Errno::Enotf => libc::ENOENT,
+            Errno::Enotsupp => libc::EOPNOTSUPP,
+            Errno::Eagain => libc::EAGAIN,
+            Errno::Enospc => libc::ENOSPC,
+            Errno::Eaccess => libc::EACCES,
+            Errno::Erange => libc::ERANGE,
+            Errno::Eoffset => libc::ENXIO,
+            Errno::Eperm => libc::EPERM,
+            Errno::Enametoolong => libc::ENAMETOOLONG,
+            Errno::Eisdir => libc::EISDIR,
+            Errno::Etimedout => libc::ETIMEDOUT,
+            Errno::Erofs => libc::EROFS,
+            Errno::Einval => libc::EINVAL,
+            Errno::Enotdir => libc::ENOTDIR,
+            Errno::Enotempty => libc::ENOTEMPTY,
+            Errno::Enodata => libc::ENODATA,
+        }
+    }
+
+    // convert the code of `errno as i32` back to Errno, such as the one sent by a peer.
+    // the unknown codes are internal errors.
+    pub fn from_code(code: i32) -> Errno {
+        match code {
+            0 => Errno::Esucc,
+            2 => Errno::Enoent,
+            3 => Errno::Eexists,
+            4 => Errno::Eseek,
+            5 => Errno::Eeof,
+            6 => Errno::Enotf,
+            7 => Errno::Enotsupp,
+            8 => Errno::Eagain,
+            28 => Errno::Enospc,
+            9 => Errno::Eaccess,
+            10 => Errno::Erange,
+            11 => Errno::Eoffset,
+            12 => Errno::Eperm,
+            13 => Errno::Enametoolong,
+            14 => Errno::Eisdir,
+            15 => Errno::Etimedout,
+            16 => Errno::Erofs,
+            17 => Errno::Einval,
+            18 => Errno::Enotdir,
+            19 => Errno::Enotempty,
+            20 => Errno::Enodata,
+            _ => Errno::Eintr,
+        }
+    }
+
     pub fn is_exists(&self)->bool {
         match *self {
             Errno::Eexists => {
@@ -114,4 +192,24 @@ impl Errno {
             }
         }
     }
+
+    pub fn is_enotdir(&self) -> bool {
+        matches!(*self, Errno::Enotdir)
+    }
+
+    pub fn is_enotempty(&self) -> bool {
+        matches!(*self, Errno::Enotempty)
+    }
+
+    pub fn is_enodata(&self) -> bool {
+        matches!(*self, Errno::Enodata)
+    }
+
+    pub fn is_eagain(&self) -> bool {
+        matches!(*self, Errno::Eagain)
+    }
+
+    pub fn is_timedout(&self) -> bool {
+        matches!(*self, Errno::Etimedout)
+    }
 }
@@ -1,4 +1,6 @@
 use std::path::PathBuf;
+use std::time::Duration;
+use common::config::{WriteBufferConfig, MIN_WRITE_BUFFER_DELAY_MS};
 use log::{info, error};
 
 #[test]
@@ -29,4 +31,30 @@ fn test_parse_config()->Result<(), String>{
             Err(format!{"failed to parse with err: {}", error})
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_write_buffer_delay()->Result<(), String>{
+    let cases = vec![
+        ("", 100),
+        ("delay_ms = 50", 50),
+        // 0 would make the buffer threads spin.
+        ("delay_ms = 0", MIN_WRITE_BUFFER_DELAY_MS),
+    ];
+    for (content, expect) in cases {
+        let cfg: WriteBufferConfig;
+        let ret = toml::from_str::<WriteBufferConfig>(content);
+        match ret {
+            Ok(ret) => {
+                cfg = ret;
+            }
+            Err(error) => {
+                return Err(format!("failed to parse {:?}, err: {}", content, error));
+            }
+        }
+        if cfg.delay() != Duration::from_millis(expect) {
+            return Err(format!("got delay: {:?} for {:?}, expect: {}ms", cfg.delay(), content, expect));
+        }
+    }
+    Ok(())
+}
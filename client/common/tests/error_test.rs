@@ -0,0 +1,44 @@
+use common::error::Errno;
+
+#[test]
+fn test_errno_to_libc()->Result<(), String> {
+    let cases = vec![
+        (Errno::Esucc, 0),
+        (Errno::Eintr, libc::EIO),
+        (Errno::Enoent, libc::ENOENT),
+        (Errno::Eaccess, libc::EACCES),
+        (Errno::Eperm, libc::EPERM),
+        (Errno::Enametoolong, libc::ENAMETOOLONG),
+        (Errno::Enotdir, libc::ENOTDIR),
+        (Errno::Eisdir, libc::EISDIR),
+        (Errno::Enotempty, libc::ENOTEMPTY),
+        (Errno::Etimedout, libc::ETIMEDOUT),
+        (Errno::Eagain, libc::EAGAIN),
+        (Errno::Erofs, libc::EROFS),
+    ];
+    for (err, expect) in cases {
+        if err.to_libc() != expect {
+            return Err(format!("{:?} is converted to {}, expect: {}", err, err.to_libc(), expect));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_errno_from_code()->Result<(), String> {
+    let cases = vec![
+        Errno::Esucc, Errno::Enoent, Errno::Eexists, Errno::Enotsupp, Errno::Eagain,
+        Errno::Enospc, Errno::Eperm, Errno::Etimedout, Errno::Erofs, Errno::Einval, Errno::Enotdir, Errno::Enotempty, Errno::Enodata,
+    ];
+    for err in cases {
+        let code = err.to_libc();
+        let ret = Errno::from_code(err as i32);
+        if ret.to_libc() != code {
+            return Err(format!("code of {} is converted to {:?}", code, ret));
+        }
+    }
+    if Errno::from_code(1000).to_libc() != libc::EIO {
+        return Err(String::from("unknown code is not converted to Eintr"));
+    }
+    Ok(())
+}
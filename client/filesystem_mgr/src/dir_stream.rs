@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+use common::error::Errno;
+use metaservice_mgr::types::DirEntry;
+
+// the offsets of "." and ".." in readdir, the cursors of meta server are shifted after them.
+pub const DOT_OFFSET: i64 = 1;
+pub const DOTDOT_OFFSET: i64 = 2;
+const CURSOR_BASE: i64 = 3;
+
+// the offset handed to the kernel for the entry whose cursor is cursor.
+pub fn to_fuse_offset(cursor: i64) -> i64 {
+    cursor + CURSOR_BASE
+}
+
+// the cursor to list the entries after the fuse offset.
+pub fn to_cursor(offset: i64) -> i64 {
+    if offset < CURSOR_BASE {
+        return 0;
+    }
+    offset - CURSOR_BASE
+}
+
+// DirStream keeps the page fetched from meta server for an opened dir,
+// so that the following readdir calls continue from it instead of fetching the page again.
+pub struct DirStream {
+    // the cursor which the buffered entries start after, -1 if nothing is fetched.
+    cursor: i64,
+    entries: VecDeque<DirEntry>,
+    // no more entries after cursor.
+    eof: bool,
+}
+
+impl DirStream {
+    pub fn new() -> Self {
+        DirStream{
+            cursor: -1,
+            entries: VecDeque::new(),
+            eof: false,
+        }
+    }
+
+    // get the entry after cursor without consuming it, None if the end of dir is reached.
+    // fetch is called with the cursor to get the next page if it is not buffered.
+    pub fn peek<F>(&mut self, cursor: i64, mut fetch: F) -> Result<Option<DirEntry>, Errno>
+    where F: FnMut(i64) -> Result<Vec<DirEntry>, Errno> {
+        // seekdir or the buffered page is used up.
+        if self.cursor != cursor || (self.entries.is_empty() && !self.eof) {
+            let ret = fetch(cursor);
+            match ret {
+                Ok(ret) => {
+                    self.eof = ret.is_empty();
+                    self.entries = ret.into_iter().collect();
+                    self.cursor = cursor;
+                }
+                Err(err) => {
+                    return Err(err);
+                }
+            }
+        }
+        match self.entries.front() {
+            Some(e) => {
+                return Ok(Some(e.copy()));
+            }
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+
+    // consume the entry returned by peek.
+    pub fn consume(&mut self) {
+        if let Some(e) = self.entries.pop_front() {
+            self.cursor = e.offset;
+        }
+    }
+}
@@ -2,6 +2,7 @@
 extern crate crossbeam_channel;
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::thread;
 use std::thread::JoinHandle;
 use crossbeam_channel::{Sender, Receiver, bounded, select};
@@ -13,21 +14,72 @@ pub struct MsgGetHandleInfo{
     pub ino: u64,
     pub tx: Sender<Option<FileHandleInfo>>,
 }
+pub struct MsgGetFhInfo{
+    pub fh: u64,
+    pub tx: Sender<Option<FileHandleInfo>>,
+}
+pub struct MsgIsOpened{
+    pub ino: u64,
+    pub tx: Sender<bool>,
+}
+pub struct MsgSetLeader{
+    pub ino: u64,
+    pub leader: String,
+    // the number of the opens of ino is sent back.
+    pub tx: Sender<i64>,
+}
 pub enum FileHandleInfoOp {
     AddHandleInfo(FileHandleInfo),
+    // move all the opens of ino to the new leader.
+    SetLeader(MsgSetLeader),
+    // delete the handle by fh.
     DelHandleInfo(u64),
     GetHandleInfo(MsgGetHandleInfo),
+    GetFhInfo(MsgGetFhInfo),
+    // whether ino is opened by the leader opens or the follower opens.
+    IsOpened(MsgIsOpened),
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FileHandleInfo {
     pub ino: u64,
+    // unique for each open.
+    pub fh: u64,
     pub leader: String,
+    // flags of open, such as O_RDONLY, O_APPEND.
+    pub flags: u32,
+    // read from the backend store by the follower instead of the leader.
+    pub follower: bool,
+}
+
+impl FileHandleInfo {
+    pub fn can_read(&self) -> bool {
+        self.flags as i32 & libc::O_ACCMODE != libc::O_WRONLY
+    }
+
+    pub fn can_write(&self) -> bool {
+        self.flags as i32 & libc::O_ACCMODE != libc::O_RDONLY
+    }
+
+    pub fn is_append(&self) -> bool {
+        self.flags as i32 & libc::O_APPEND != 0
+    }
+
+    // O_SYNC contains O_DSYNC, check O_SYNC first.
+    pub fn is_sync(&self) -> bool {
+        self.flags as i32 & libc::O_SYNC == libc::O_SYNC
+    }
+
+    pub fn is_dsync(&self) -> bool {
+        self.flags as i32 & libc::O_DSYNC != 0
+    }
 }
 
 // FileHandleInfoMgr is accessed by multi-threads in a YigFs instance.
 pub struct FileHandleInfoMgr {
+    // the next fh to allocate, 0 is never used.
+    next_fh: AtomicU64,
     op_tx: Sender<FileHandleInfoOp>,
     stop_tx: Sender<u32>,
     impl_join_handle: Option<JoinHandle<()>>,
@@ -41,6 +93,7 @@ impl FileHandleInfoMgr {
         let mut cacher = HandleCacher::new(rx, stop_rx);
 
         FileHandleInfoMgr{
+            next_fh: AtomicU64::new(1),
             op_tx: tx,
             stop_tx: stop_tx,
             impl_join_handle: Some(thread::spawn(move || cacher.start())),
@@ -71,6 +124,11 @@ impl FileHandleInfoMgr {
         drop(self.stop_tx.clone());
     }
 
+    // allocate a unique fh for a new open.
+    pub fn new_fh(&self) -> u64 {
+        self.next_fh.fetch_add(1, Ordering::SeqCst)
+    }
+
     pub fn add_handle_info(&self, info: FileHandleInfo)->Errno {
         let ino = info.ino;
         let leader = info.leader.clone();
@@ -86,6 +144,41 @@ impl FileHandleInfoMgr {
         }
     }
 
+    // get the handle of fh.
+    pub fn get_fh_info(&self, fh: u64) -> Result<FileHandleInfo, Errno> {
+        let (tx, rx) = bounded::<Option<FileHandleInfo>>(1);
+        let msg = MsgGetFhInfo{
+            fh: fh,
+            tx: tx,
+        };
+        defer!{
+            let rxc = rx.clone();
+            drop(rxc);
+        }
+        let ret = self.op_tx.send(FileHandleInfoOp::GetFhInfo(msg));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_fh_info: failed to send fh: {}, err: {}", fh, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                if let Some(h) = ret {
+                    return Ok(h);
+                }
+                return Err(Errno::Enoent);
+            }
+            Err(err) => {
+                error!("get_fh_info: failed to get handle for fh: {}, err: {}", fh, err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
+    // get the leader of the opened ino, fh and flags are not set in the result.
     pub fn get_handle_info(&self, ino: u64) -> Result<FileHandleInfo, Errno> {
         let (tx, rx) = bounded::<Option<FileHandleInfo>>(1);
         let msg = MsgGetHandleInfo{
@@ -119,14 +212,69 @@ impl FileHandleInfoMgr {
         }
     }
 
-    pub fn del_handle_info(&self, ino: u64) -> Errno {
-        let ret = self.op_tx.send(FileHandleInfoOp::DelHandleInfo(ino));
+    // whether ino has any open, including the follower opens which get_handle_info doesn't count.
+    pub fn is_opened(&self, ino: u64) -> Result<bool, Errno> {
+        let (tx, rx) = bounded::<bool>(1);
+        let msg = MsgIsOpened{
+            ino: ino,
+            tx: tx,
+        };
+        let ret = self.op_tx.send(FileHandleInfoOp::IsOpened(msg));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("is_opened: failed to send ino: {}, err: {}", ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return Ok(ret);
+            }
+            Err(err) => {
+                error!("is_opened: failed to check the opens of ino: {}, err: {}", ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
+    // switch the opens of ino to leader after a failover, returns the number of the opens.
+    pub fn set_leader(&self, ino: u64, leader: &String) -> Result<i64, Errno> {
+        let (tx, rx) = bounded::<i64>(1);
+        let msg = MsgSetLeader{
+            ino: ino,
+            leader: leader.clone(),
+            tx: tx,
+        };
+        let ret = self.op_tx.send(FileHandleInfoOp::SetLeader(msg));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("set_leader: failed to send leader: {} for ino: {}, err: {}", leader, ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return Ok(ret);
+            }
+            Err(err) => {
+                error!("set_leader: failed to set leader: {} for ino: {}, err: {}", leader, ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
+    pub fn del_handle_info(&self, fh: u64) -> Errno {
+        let ret = self.op_tx.send(FileHandleInfoOp::DelHandleInfo(fh));
         match ret {
             Ok(_) => {
                 return Errno::Esucc;
             }
             Err(err) => {
-                error!("del_handle_info: failed to send ino: {}, err: {}", ino, err);
+                error!("del_handle_info: failed to send fh: {}, err: {}", fh, err);
                 return Errno::Eintr;
             }
         }
@@ -142,7 +290,10 @@ struct FileInfoCache {
 struct HandleCacher{
     op_rx: Receiver<FileHandleInfoOp>,
     stop_rx: Receiver<u32>,
+    // ino-->FileInfoCache, shared by all the opens of ino.
     handles: HashMap<u64, FileInfoCache>,
+    // fh-->FileHandleInfo
+    fhs: HashMap<u64, FileHandleInfo>,
 }
 
 impl HandleCacher {
@@ -151,6 +302,7 @@ impl HandleCacher {
             op_rx: op_rx,
             stop_rx: stop_rx,
             handles: HashMap::new(),
+            fhs: HashMap::new(),
         }
     }
 
@@ -175,8 +327,17 @@ impl HandleCacher {
                         FileHandleInfoOp::GetHandleInfo(msg) => {
                             self.get_handle_info(&msg);
                         }
-                        FileHandleInfoOp::DelHandleInfo(ino) => {
-                            self.del_handle_info(ino);
+                        FileHandleInfoOp::GetFhInfo(msg) => {
+                            self.get_fh_info(&msg);
+                        }
+                        FileHandleInfoOp::DelHandleInfo(fh) => {
+                            self.del_handle_info(fh);
+                        }
+                        FileHandleInfoOp::SetLeader(msg) => {
+                            self.set_leader(&msg);
+                        }
+                        FileHandleInfoOp::IsOpened(msg) => {
+                            self.is_opened(&msg);
                         }
                     }
                 },
@@ -201,15 +362,21 @@ impl HandleCacher {
     }
 
     fn add_handle_info(&mut self, h: FileHandleInfo) {
+        // the follower opens are not opened on the leader, so they aren't shared with the leader opens.
+        if h.follower {
+            self.fhs.insert(h.fh, h);
+            return;
+        }
         if let Some(info) = self.handles.get_mut(&h.ino){
             info.reference += 1;
-            return;
+        } else {
+            self.handles.insert(h.ino, FileInfoCache{
+                ino: h.ino,
+                leader: h.leader.clone(),
+                reference: 1,
+            });
         }
-        self.handles.insert(h.ino, FileInfoCache{
-            ino: h.ino,
-            leader: h.leader.clone(),
-            reference: 1,
-        });
+        self.fhs.insert(h.fh, h);
     }
 
     fn get_handle_info(&mut self, msg: &MsgGetHandleInfo){
@@ -221,7 +388,10 @@ impl HandleCacher {
         if let Some(h) = self.handles.get(&msg.ino){
             handle = Some(FileHandleInfo{
                 ino: h.ino,
+                fh: 0,
                 leader: h.leader.clone(),
+                flags: 0,
+                follower: false,
             });
         }
         let ret = msg.tx.send(handle);
@@ -233,7 +403,63 @@ impl HandleCacher {
         }
     }
 
-    fn del_handle_info(&mut self, ino: u64) {
+    fn get_fh_info(&mut self, msg: &MsgGetFhInfo){
+        defer!{
+            let txc = msg.tx.clone();
+            drop(txc);
+        }
+        let handle = self.fhs.get(&msg.fh).cloned();
+        let ret = msg.tx.send(handle);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_fh_info: failed to send handle, err: {}", err);
+            }
+        }
+    }
+
+    fn is_opened(&mut self, msg: &MsgIsOpened) {
+        // every open is in fhs, the follower ones are not counted in handles.
+        let opened = self.fhs.values().any(|h| h.ino == msg.ino);
+        let ret = msg.tx.send(opened);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("is_opened: failed to send the opened state of ino: {}, err: {}", msg.ino, err);
+            }
+        }
+    }
+
+    fn set_leader(&mut self, msg: &MsgSetLeader) {
+        let mut reference: i64 = 0;
+        if let Some(info) = self.handles.get_mut(&msg.ino) {
+            info.leader = msg.leader.clone();
+            reference = info.reference;
+            for h in self.fhs.values_mut() {
+                if h.ino == msg.ino && !h.follower {
+                    h.leader = msg.leader.clone();
+                }
+            }
+        }
+        let ret = msg.tx.send(reference);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("set_leader: failed to send the opens of ino: {}, err: {}", msg.ino, err);
+            }
+        }
+    }
+
+    fn del_handle_info(&mut self, fh: u64) {
+        let ino: u64;
+        if let Some(h) = self.fhs.remove(&fh) {
+            if h.follower {
+                return;
+            }
+            ino = h.ino;
+        } else {
+            return;
+        }
         if let Some(info) = self.handles.get_mut(&ino){
             info.reference -= 1;
             if info.reference <= 0 {
@@ -1,35 +1,116 @@
 pub mod yigfs;
+pub mod shutdown;
 mod handle;
+mod dir_stream;
+pub mod perm;
 
+use std::ffi::{OsStr, OsString};
 use std::sync::Arc;
 use yigfs::Yigfs;
+use common::config::MountConfig;
+use common::error::Errno;
 use metaservice_mgr::mgr::MetaServiceMgr;
-use segment_mgr::{heartbeat_mgr::HeartbeatMgr, leader_mgr::LeaderMgr};
+use segment_mgr::{heartbeat_mgr::HeartbeatMgr, leader_mgr::LeaderMgr, segment_mgr::SegmentMgr};
+use log::{info, error};
 
 pub struct MountOptions{
     // mount point
     pub mnt: String,
+    // reject all the mutating requests with EROFS.
+    pub read_only: bool,
+    pub allow_other: bool,
+    pub default_permissions: bool,
+    pub fsname: String,
+    pub subtype: String,
+    // 0 means the kernel default.
+    pub max_read: u32,
+    // fuse negotiates the max write size with the kernel by itself,
+    // so this only limits the size of a write handed to the leader at once, 0 means no limit.
+    pub max_write: u32,
+    pub attr_timeout_ms: u64,
+    pub entry_timeout_ms: u64,
+}
+
+impl MountOptions {
+    pub fn new(mount_config: &MountConfig) -> Self {
+        MountOptions{
+            mnt: mount_config.mnt.clone(),
+            read_only: mount_config.read_only,
+            allow_other: mount_config.allow_other,
+            default_permissions: mount_config.default_permissions,
+            fsname: mount_config.fsname.clone(),
+            subtype: mount_config.subtype.clone(),
+            max_read: mount_config.max_read,
+            max_write: mount_config.max_write,
+            attr_timeout_ms: mount_config.attr_timeout_ms,
+            entry_timeout_ms: mount_config.entry_timeout_ms,
+        }
+    }
+
+    // the options passed to fuse mount, such as "-o ro,allow_other,fsname=yigfs".
+    fn to_fuse_options(&self) -> Vec<OsString> {
+        let mut opts = Vec::<String>::new();
+        if self.read_only {
+            opts.push(String::from("ro"));
+        }
+        if self.allow_other {
+            opts.push(String::from("allow_other"));
+        }
+        if self.default_permissions {
+            opts.push(String::from("default_permissions"));
+        }
+        if !self.fsname.is_empty() {
+            opts.push(format!("fsname={}", self.fsname));
+        }
+        if !self.subtype.is_empty() {
+            opts.push(format!("subtype={}", self.subtype));
+        }
+        if self.max_read > 0 {
+            opts.push(format!("max_read={}", self.max_read));
+        }
+        if opts.is_empty() {
+            return Vec::new();
+        }
+        vec![OsString::from("-o"), OsString::from(opts.join(","))]
+    }
 }
 
 pub struct FilesystemMgr {
    meta_service_mgr: Arc<dyn MetaServiceMgr>,
    leader_mgr: Option<LeaderMgr>,
    heartbeat_mgr: Arc<HeartbeatMgr>,
+   segment_mgr: Arc<SegmentMgr>,
 }
 
 impl FilesystemMgr{
-    pub fn create(meta_service_mgr: Arc<dyn MetaServiceMgr>, leader_mgr: LeaderMgr, heartbeat_mgr: Arc<HeartbeatMgr>)->FilesystemMgr{
+    pub fn create(meta_service_mgr: Arc<dyn MetaServiceMgr>, leader_mgr: LeaderMgr, heartbeat_mgr: Arc<HeartbeatMgr>,
+        segment_mgr: Arc<SegmentMgr>)->FilesystemMgr{
         FilesystemMgr{
             meta_service_mgr: meta_service_mgr,
             leader_mgr: Some(leader_mgr),
             heartbeat_mgr: heartbeat_mgr,
+            segment_mgr: segment_mgr,
         }
     }
 
-    pub fn mount(&mut self, mount_options : MountOptions) {
+    // it returns after the mount is unmounted and the opened files are flushed.
+    pub fn mount(&mut self, mount_options : MountOptions) -> Errno {
         if let Some(leader_mgr) = self.leader_mgr.take() {
-            let yfs = Yigfs::create(self.meta_service_mgr.clone(), leader_mgr);
-            fuse::mount(yfs, &mount_options.mnt, &[]).unwrap();
+            let yfs = Yigfs::create(self.meta_service_mgr.clone(), leader_mgr, self.segment_mgr.clone(), &mount_options);
+            let fuse_options = mount_options.to_fuse_options();
+            let options: Vec<&OsStr> = fuse_options.iter().map(|o| o.as_os_str()).collect();
+            // unmount on SIGTERM, SIGINT or SIGHUP.
+            let ret = shutdown::start_signal_waiter(&mount_options.mnt);
+            if !ret.is_success() {
+                error!("mount: failed to start signal waiter for {}, err: {:?}", mount_options.mnt, ret);
+            }
+            let ret = fuse::mount(yfs, &mount_options.mnt, &options);
+            if let Err(err) = ret {
+                error!("mount: failed to mount {}, err: {}", mount_options.mnt, err);
+                return Errno::Eintr;
+            }
+            info!("mount: {} is unmounted", mount_options.mnt);
         }
+        Errno::Esucc
     }
 }
\ No newline at end of file
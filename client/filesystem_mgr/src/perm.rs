@@ -0,0 +1,105 @@
+use std::fs;
+use fuse::Request;
+use metaservice_mgr::types::{FileAttr, FileType};
+use log::error;
+
+// the access mask, same as R_OK, W_OK and X_OK.
+pub const MAY_READ: u32 = 4;
+pub const MAY_WRITE: u32 = 2;
+pub const MAY_EXEC: u32 = 1;
+
+pub const S_ISVTX: u16 = 0o1000;
+pub const S_ISGID: u16 = 0o2000;
+
+// Caller is the identity of the process which sends the fuse request.
+pub struct Caller {
+    pub uid: u32,
+    pub gid: u32,
+    pub pid: u32,
+}
+
+impl Caller {
+    pub fn new(req: &Request) -> Self {
+        Caller {
+            uid: req.uid(),
+            gid: req.gid(),
+            pid: req.pid(),
+        }
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.uid == 0
+    }
+
+    pub fn is_owner(&self, attr: &FileAttr) -> bool {
+        self.uid == attr.uid
+    }
+
+    // check the primary group first, then the supplementary groups.
+    pub fn in_group(&self, gid: u32) -> bool {
+        if self.gid == gid {
+            return true;
+        }
+        let groups = self.get_groups();
+        groups.contains(&gid)
+    }
+
+    // check whether the caller has the permission of mask on the file.
+    pub fn can_access(&self, attr: &FileAttr, mask: u32) -> bool {
+        let perm = attr.perm as u32;
+        if self.is_root() {
+            // root can execute the file only if any of the execute bits is set.
+            if mask & MAY_EXEC != 0 && perm & 0o111 == 0 {
+                if let FileType::DIR = attr.kind {
+                    return true;
+                }
+                return false;
+            }
+            return true;
+        }
+        let granted: u32;
+        if self.is_owner(attr) {
+            granted = (perm >> 6) & 0o7;
+        } else if self.in_group(attr.gid) {
+            granted = (perm >> 3) & 0o7;
+        } else {
+            granted = perm & 0o7;
+        }
+        granted & mask == mask
+    }
+
+    // if the sticky bit of dir is set, only root, the owner of dir or the owner of file
+    // can remove or rename the file in dir.
+    pub fn can_delete(&self, dir: &FileAttr, file: &FileAttr) -> bool {
+        if dir.perm & S_ISVTX == 0 {
+            return true;
+        }
+        self.is_root() || self.is_owner(dir) || self.is_owner(file)
+    }
+
+    // fuse request only carries the primary group, so read the supplementary groups from procfs.
+    fn get_groups(&self) -> Vec<u32> {
+        let mut groups = Vec::<u32>::new();
+        let path = format!("/proc/{}/status", self.pid);
+        let ret = fs::read_to_string(&path);
+        match ret {
+            Ok(ret) => {
+                for line in ret.lines() {
+                    if !line.starts_with("Groups:") {
+                        continue;
+                    }
+                    for g in line["Groups:".len()..].split_whitespace() {
+                        if let Ok(gid) = g.parse::<u32>() {
+                            groups.push(gid);
+                        }
+                    }
+                    break;
+                }
+            }
+            Err(err) => {
+                error!("get_groups: failed to read {} for uid: {}, err: {}", path, self.uid, err);
+            }
+        }
+        groups
+    }
+}
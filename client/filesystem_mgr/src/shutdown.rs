@@ -0,0 +1,116 @@
+use std::ffi::CString;
+use std::process::Command;
+use std::thread;
+use libc::c_int;
+use common::error::Errno;
+use log::{info, warn, error};
+
+// the signals which unmount yigfs.
+const SHUTDOWN_SIGNALS: [c_int; 3] = [libc::SIGTERM, libc::SIGINT, libc::SIGHUP];
+
+fn shutdown_sigset() -> libc::sigset_t {
+    let mut set: libc::sigset_t = unsafe { std::mem::zeroed() };
+    unsafe {
+        libc::sigemptyset(&mut set);
+        for sig in &SHUTDOWN_SIGNALS {
+            libc::sigaddset(&mut set, *sig);
+        }
+    }
+    set
+}
+
+// block the shutdown signals in the calling thread.
+// it must be called before any thread is spawned, so that all the threads inherit the mask
+// and the signals are only received by the signal waiter.
+pub fn block_signals() -> Errno {
+    let set = shutdown_sigset();
+    let ret = unsafe { libc::pthread_sigmask(libc::SIG_BLOCK, &set, std::ptr::null_mut()) };
+    if ret != 0 {
+        error!("block_signals: failed to block the shutdown signals, err: {}", ret);
+        return Errno::Eintr;
+    }
+    return Errno::Esucc;
+}
+
+// start a thread which unmounts mnt once a shutdown signal is received.
+// the fuse session ends after the unmount, then the dirty data is flushed.
+pub fn start_signal_waiter(mnt: &String) -> Errno {
+    let mnt = mnt.clone();
+    let ret = thread::Builder::new().name(String::from("signal_waiter")).spawn(move || {
+        wait_signals(&mnt);
+    });
+    match ret {
+        Ok(_) => {
+            return Errno::Esucc;
+        }
+        Err(err) => {
+            error!("start_signal_waiter: failed to spawn signal waiter, err: {}", err);
+            return Errno::Eintr;
+        }
+    }
+}
+
+fn wait_signals(mnt: &String) {
+    let set = shutdown_sigset();
+    loop {
+        let mut sig: c_int = 0;
+        let ret = unsafe { libc::sigwait(&set, &mut sig) };
+        if ret != 0 {
+            error!("wait_signals: failed to wait for the signals, err: {}", ret);
+            return;
+        }
+        warn!("wait_signals: got signal {}, unmounting {}...", sig, mnt);
+        let ret = unmount(mnt);
+        if !ret.is_success() {
+            error!("wait_signals: failed to unmount {}, err: {:?}", mnt, ret);
+        }
+    }
+}
+
+// unmount mnt, the kernel stops sending requests once it is unmounted.
+// if the mount is still in use, it is detached lazily and the session ends after the last file is closed.
+fn unmount(mnt: &String) -> Errno {
+    let path: CString;
+    let ret = CString::new(mnt.as_str());
+    match ret {
+        Ok(ret) => {
+            path = ret;
+        }
+        Err(err) => {
+            error!("unmount: got invalid mount point: {}, err: {}", mnt, err);
+            return Errno::Einval;
+        }
+    }
+    let ret = unsafe { libc::umount2(path.as_ptr(), 0) };
+    if ret == 0 {
+        info!("unmount: {} is unmounted", mnt);
+        return Errno::Esucc;
+    }
+    let err = std::io::Error::last_os_error().raw_os_error().unwrap_or(0);
+    if err == libc::EBUSY {
+        warn!("unmount: {} is busy, detach it lazily", mnt);
+        let ret = unsafe { libc::umount2(path.as_ptr(), libc::MNT_DETACH) };
+        if ret == 0 {
+            return Errno::Esucc;
+        }
+    } else if err != libc::EPERM {
+        error!("unmount: failed to unmount {}, err: {}", mnt, err);
+        return Errno::Eintr;
+    }
+    // non-root users have to unmount through the setuid fusermount.
+    let ret = Command::new("fusermount").arg("-u").arg("-z").arg(mnt).status();
+    match ret {
+        Ok(ret) => {
+            if ret.success() {
+                info!("unmount: {} is unmounted by fusermount", mnt);
+                return Errno::Esucc;
+            }
+            error!("unmount: fusermount failed to unmount {}, status: {}", mnt, ret);
+            return Errno::Eintr;
+        }
+        Err(err) => {
+            error!("unmount: failed to run fusermount for {}, err: {}", mnt, err);
+            return Errno::Eintr;
+        }
+    }
+}
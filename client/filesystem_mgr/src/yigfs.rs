@@ -2,27 +2,60 @@ extern crate fuse;
 extern crate libc;
 extern crate time;
 
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
+use std::path::Path;
 use std::sync::Arc;
 use libc::{ENOENT, c_int};
 use time::Timespec;
-use fuse::{FileAttr, Filesystem, Request, 
-    ReplyData, ReplyEntry, ReplyAttr, ReplyDirectory, ReplyCreate, ReplyOpen, ReplyWrite, ReplyEmpty};
-use metaservice_mgr::{mgr::MetaServiceMgr, types::{FileLeader, NewFileInfo, SetFileAttr, FileType}};
+use fuse::{FileAttr, Filesystem, Request, consts,
+    ReplyData, ReplyEntry, ReplyAttr, ReplyDirectory, ReplyCreate, ReplyOpen, ReplyWrite, ReplyEmpty, ReplyStatfs, ReplyXattr};
+use metaservice_mgr::{mgr::MetaServiceMgr, types::{FileLeader, NewFileInfo, SetFileAttr, FileType, FsUsage}};
+use segment_mgr::leader::Leader;
 use segment_mgr::leader_mgr::LeaderMgr;
+use segment_mgr::segment_mgr::SegmentMgr;
 use common::uuid;
+use common::error::Errno;
 use crate::handle::{FileHandleInfo, FileHandleInfoMgr};
+use crate::dir_stream::{self, DirStream};
+use crate::perm::{Caller, MAY_READ, MAY_WRITE, MAY_EXEC, S_ISGID};
+use crate::MountOptions;
 use log::{info, warn, error};
 
-const TTL: Timespec = Timespec { sec: 1, nsec: 0 };                     // 1 second
+const BLOCK_SIZE: u32 = 4096;
+const MAX_NAME_LEN: u32 = 255;
+// the max depth of the dir tree walked up to find the ancestors.
+const MAX_DIR_DEPTH: u32 = 4096;
+// the metaservice doesn't limit the number of inodes, report a large enough value.
+const MAX_FILES: u64 = u32::MAX as u64;
+// limits of the extended attributes, same as linux.
+const XATTR_NAME_MAX: usize = 255;
+const XATTR_SIZE_MAX: usize = 65536;
+const XATTR_LIST_MAX: usize = 65536;
+const XATTR_USER_PREFIX: &str = "user.";
+const XATTR_TRUSTED_PREFIX: &str = "trusted.";
 
 
 pub struct Yigfs{
     meta_service_mgr: Arc<dyn MetaServiceMgr>,
     leader_mgr: LeaderMgr,
+    segment_mgr: Arc<SegmentMgr>,
     handle_cacher: FileHandleInfoMgr,
+    // the unlinked inos which are still opened, they are deleted on the last release.
+    orphans: HashSet<u64>,
+    // fh of the opened dir -> the page of entries fetched for it.
+    dir_streams: HashMap<u64, DirStream>,
     // fsid for this mounted yigfs instance
     fsid: String,
+    // all the mutating requests are rejected with EROFS.
+    read_only: bool,
+    // how long the kernel caches the attrs and the dir entries.
+    attr_ttl: Timespec,
+    entry_ttl: Timespec,
+    // max size of a write handed to the leader at once, 0 means no limit.
+    max_write: u32,
+    // the leaders and the handles are already stopped.
+    stopped: bool,
 }
 
 impl Filesystem for Yigfs {
@@ -31,20 +64,36 @@ impl Filesystem for Yigfs {
         let ret = self.meta_service_mgr.mount(req.uid(), req.gid());
         match ret {
             Ok(_) => {
+                // nothing is opened now, the orphans were left by the crash of the former mount.
+                // they are kept until a writable mount.
+                if self.read_only {
+                    return Ok(());
+                }
+                let ret = self.meta_service_mgr.list_orphans();
+                match ret {
+                    Ok(inos) => {
+                        for ino in inos {
+                            info!("init: reclaim the orphan ino: {}", ino);
+                            self.reclaim_orphan(ino);
+                        }
+                    }
+                    Err(err) => {
+                        warn!("init: failed to list the orphans, err: {:?}", err);
+                    }
+                }
                 return Ok(());
             }
             Err(error) => {
                 error!("failed to mount with err: {:?}", error);
-                return Err(ENOENT);
+                return Err(error.to_libc());
             }
         }
     }
     fn destroy(&mut self, req: &Request) {
         warn!("destroy: uid: {}, gid: {}, fsid: {}", req.uid(), req.gid(), self.fsid);
-        self.leader_mgr.stop();
-        self.handle_cacher.stop();
+        self.shutdown();
     }
-    fn lookup(&mut self, _req: &Request, parent: u64, name: &OsStr, reply: ReplyEntry) {
+    fn lookup(&mut self, req: &Request, parent: u64, name: &OsStr, reply: ReplyEntry) {
         let name_str: String;
         let ret = name.to_str();
         match ret {
@@ -53,20 +102,35 @@ impl Filesystem for Yigfs {
             }
             None => {
                 error!("got invalid parent: {}, name: {:?}", parent, name);
+                reply.error(libc::EBADMSG);
                 return;
             }
         }
+        // the kernel looks up the name before creating it, so the check here covers the creation too.
+        if name_str.len() > MAX_NAME_LEN as usize {
+            error!("lookup: parent: {}, name: {} is too long", parent, name_str);
+            reply.error(libc::ENAMETOOLONG);
+            return;
+        }
         info!("lookup: parent: {}, name: {}", parent, name_str);
+        // search permission is required on the parent dir.
+        let ret = self.check_access(&Caller::new(req), parent, MAY_EXEC);
+        if let Err(err) = ret {
+            error!("lookup: uid: {}, parent: {}, name: {}, permission check failed, err: {}",
+            req.uid(), parent, name_str, err);
+            reply.error(err);
+            return;
+        }
         let ret = self.meta_service_mgr.read_dir_file_attr(parent, &name_str);
         match ret {
             Ok(ret) => {
                 let file_attr = self.to_usefs_attr(&ret);
                 info!("lookup: parent: {}, name: {}, attr: {:?}", parent, name_str, file_attr);
-                reply.entry(&TTL, &file_attr, ret.generation);
+                reply.entry(&self.entry_ttl, &file_attr, ret.generation);
             }
             Err(error) => {
                 error!("failed to lookup for parent: {}, name: {}, err: {:?}", parent, name_str, error);
-                reply.error(ENOENT);
+                reply.error(error.to_libc());
             }
         }
     }
@@ -77,16 +141,74 @@ impl Filesystem for Yigfs {
             Ok(ret) => {
                 let attr = self.to_usefs_attr(&ret);
                 info!("getattr: ino: {}, attr: {:?}", ino, attr);
-                reply.attr(&TTL, &attr);
+                reply.attr(&self.attr_ttl, &attr);
             }
             Err(error) => {
                 error!("failed to getattr for ino: {}, err: {:?}", ino, error);
-                reply.error(ENOENT);
+                reply.error(error.to_libc());
             }
         }
     }
 
-    fn setattr(&mut self, req: &Request, ino: u64, mode: Option<u32>, uid: Option<u32>, gid: Option<u32>, size: Option<u64>, atime: Option<Timespec>, mtime: Option<Timespec>, _fh: Option<u64>, _crtime: Option<Timespec>, _chgtime: Option<Timespec>, _bkuptime: Option<Timespec>, _flags: Option<u32>, reply: ReplyAttr){
+    fn setattr(&mut self, req: &Request, ino: u64, mode: Option<u32>, uid: Option<u32>, gid: Option<u32>, size: Option<u64>, atime: Option<Timespec>, mtime: Option<Timespec>, fh: Option<u64>, _crtime: Option<Timespec>, _chgtime: Option<Timespec>, _bkuptime: Option<Timespec>, _flags: Option<u32>, reply: ReplyAttr){
+        let ret = self.check_read_only("setattr", ino);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
+        let caller = Caller::new(req);
+        let origin_attr: metaservice_mgr::types::FileAttr;
+        let ret = self.meta_service_mgr.read_file_attr(ino);
+        match ret {
+            Ok(ret) => {
+                origin_attr = ret;
+            }
+            Err(err) => {
+                error!("setattr: failed to get attr for ino: {}, err: {:?}", ino, err);
+                reply.error(err.to_libc());
+                return;
+            }
+        }
+        let mut mode = mode;
+        if mode.is_some() && !caller.is_root() && !caller.is_owner(&origin_attr) {
+            error!("setattr: uid: {} is not the owner of ino: {}, cannot change mode", caller.uid, ino);
+            reply.error(libc::EPERM);
+            return;
+        }
+        if let Some(u) = uid {
+            if u != origin_attr.uid && !caller.is_root() {
+                error!("setattr: uid: {} cannot change the owner of ino: {} to {}", caller.uid, ino, u);
+                reply.error(libc::EPERM);
+                return;
+            }
+        }
+        if let Some(g) = gid {
+            if g != origin_attr.gid && !caller.is_root() &&
+                (!caller.is_owner(&origin_attr) || !caller.in_group(g)) {
+                error!("setattr: uid: {} cannot change the group of ino: {} to {}", caller.uid, ino, g);
+                reply.error(libc::EPERM);
+                return;
+            }
+        }
+        if let Some(m) = mode {
+            // clear the setgid bit if the caller is not in the group of the file.
+            let file_gid = gid.unwrap_or(origin_attr.gid);
+            if m as u16 & S_ISGID != 0 && !caller.is_root() && !caller.in_group(file_gid) {
+                mode = Some(m & !(S_ISGID as u32));
+            }
+        }
+        // truncate through an opened file handle was checked in open.
+        if size.is_some() && fh.is_none() && !caller.can_access(&origin_attr, MAY_WRITE) {
+            error!("setattr: uid: {} has no write permission to truncate ino: {}", caller.uid, ino);
+            reply.error(libc::EACCES);
+            return;
+        }
+        if (atime.is_some() || mtime.is_some()) && !caller.is_root() && !caller.is_owner(&origin_attr) &&
+            !caller.can_access(&origin_attr, MAY_WRITE) {
+            error!("setattr: uid: {} has no permission to change the times of ino: {}", caller.uid, ino);
+            reply.error(libc::EACCES);
+            return;
+        }
         let mut set_attr = SetFileAttr{
             ino: ino,
             size: size,
@@ -125,6 +247,16 @@ impl Filesystem for Yigfs {
         set_attr.size = size;
 
         info!("setattr: uid: {}, gid: {}, pid: {}, attr: {:?}", req.uid(), req.gid(), req.pid(), set_attr);
+        // cut the blocks beyond the new size before the size is changed, so that the size is kept if it fails.
+        // extending leaves a hole which reads as zeros.
+        if let Some(s) = size {
+            let ret = self.truncate_blocks(ino, s, origin_attr.size);
+            if !ret.is_success() {
+                error!("setattr: failed to truncate blocks of ino: {} to size: {}, err: {:?}", ino, s, ret);
+                reply.error(libc::EIO);
+                return;
+            }
+        }
         let file_attr : metaservice_mgr::types::FileAttr;
         let ret = self.meta_service_mgr.set_file_attr(&set_attr);
         match ret {
@@ -134,29 +266,42 @@ impl Filesystem for Yigfs {
             }
             Err(err) => {
                 error!("failed to set_file_attr for {:?}, err: {:?}", set_attr, err);
-                reply.error(libc::EIO);
+                reply.error(err.to_libc());
                 return;
             }
         }
-        reply.attr(&TTL, &self.to_usefs_attr(&file_attr));
+        reply.attr(&self.attr_ttl, &self.to_usefs_attr(&file_attr));
     }
 
-    fn read(&mut self, _req: &Request, ino: u64, _fh: u64, offset: i64, size: u32, reply: ReplyData) {
-        let leader: String;
-        let ret = self.handle_cacher.get_handle_info(ino);
+    fn read(&mut self, _req: &Request, ino: u64, fh: u64, offset: i64, size: u32, reply: ReplyData) {
+        let handle: FileHandleInfo;
+        let ret = self.get_fh_info(ino, fh);
         match ret {
             Ok(ret) => {
-                leader = ret.leader;
+                if !ret.can_read() {
+                    error!("read: fh: {} of ino: {} is not opened for reading, flags: {}", fh, ino, ret.flags);
+                    reply.error(libc::EBADF);
+                    return;
+                }
+                handle = ret;
             }
             Err(err) => {
-                error!("read: file ino: {} is not opened yet, err: {:?}.", ino, err);
-                reply.error(libc::EBADF);
+                error!("read: file ino: {}, fh: {} is not opened yet.", ino, fh);
+                reply.error(err);
                 return;
             }
         }
         // get the leader.
-        let leader_io = self.leader_mgr.get_leader(&leader);
-        let ret = leader_io.read(ino, offset as u64, size);
+        let leader_io = self.get_leader_io(&handle);
+        let mut ret = leader_io.read(ino, offset as u64, size);
+        // the follower reads from the backend store, it doesn't fail over with the leader.
+        if let Err(Errno::Etimedout) = ret {
+            if !handle.follower {
+                if let Some(l) = self.failover(ino, &handle.leader) {
+                    ret = l.read(ino, offset as u64, size);
+                }
+            }
+        }
         match ret {
             Ok(ret) => {
                 reply.data(ret.as_slice());
@@ -164,6 +309,11 @@ impl Filesystem for Yigfs {
             }
             Err(err) => {
                 error!("read: failed to read ino: {}, offset: {}, err: {:?}", ino, offset, err);
+                // the follower reads the data not uploaded by the leader yet, try again later.
+                if err.is_eagain() {
+                    reply.error(libc::EAGAIN);
+                    return;
+                }
                 reply.error(libc::EIO);
                 return;
             }
@@ -172,6 +322,11 @@ impl Filesystem for Yigfs {
 
     /// Create a directory.
     fn mkdir(&mut self, req: &Request, parent: u64, name: &OsStr, mode: u32, reply: ReplyEntry) {
+        let ret = self.check_read_only("mkdir", parent);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
         let uid = req.uid();
         let gid = req.gid();
         let name_str: String;
@@ -185,9 +340,16 @@ impl Filesystem for Yigfs {
         }
         info!("mkdir: uid: {}, gid: {}, parent: {}, name: {}, mode: {}",
         uid, gid, parent, name_str, mode);
+        let ret = self.check_access(&Caller::new(req), parent, MAY_WRITE | MAY_EXEC);
+        if let Err(err) = ret {
+            error!("mkdir: uid: {}, parent: {}, name: {}, permission check failed, err: {}",
+            uid, parent, name_str, err);
+            reply.error(err);
+            return;
+        }
         let file_info: NewFileInfo;
         let ret = self.meta_service_mgr.new_ino_leader(
-            parent, &name_str, uid, gid, mode, FileType::DIR.to());
+            parent, &name_str, uid, gid, mode, FileType::DIR.to(), 0);
         match ret {
             Ok(ret) => {
                 file_info = ret;
@@ -195,41 +357,181 @@ impl Filesystem for Yigfs {
             Err(err) => {
                 error!("mkdir: uid: {}, gid: {}, parent: {}, name: {}, mode: {}, failed to create, err: {:?}",
                 uid, gid, parent, name_str, mode, err);
-                reply.error(libc::EIO);
+                reply.error(err.to_libc());
                 return;
             }
         }
-        reply.entry(&TTL, &self.to_usefs_attr(&file_info.attr), file_info.attr.generation);
+        reply.entry(&self.entry_ttl, &self.to_usefs_attr(&file_info.attr), file_info.attr.generation);
     }
 
-    fn readdir(&mut self, _req: &Request, ino: u64, _fh: u64, offset: i64, mut reply: ReplyDirectory) {
-        // must authorize the request here by checking _req.
-        info!("readdir: ino: {}, offset: {}", ino, offset);
-        let entrys : Vec<metaservice_mgr::types::DirEntry>;
-        let ret = self.meta_service_mgr.read_dir(ino, offset);
+    /// Create a file node, such as fifo, socket, char or block device.
+    fn mknod(&mut self, req: &Request, parent: u64, name: &OsStr, mode: u32, rdev: u32, reply: ReplyEntry) {
+        let ret = self.check_read_only("mknod", parent);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
+        let uid = req.uid();
+        let gid = req.gid();
+        let name_str: String;
+        if let Some(n) = name.to_str() {
+            name_str = n.to_string();
+        } else {
+            error!("mknod: uid: {}, gid: {}, parent: {}, mode: {}, input invalid name",
+            uid, gid, parent, mode);
+            reply.error(libc::EBADMSG);
+            return;
+        }
+        info!("mknod: uid: {}, gid: {}, parent: {}, name: {}, mode: {:o}, rdev: {}",
+        uid, gid, parent, name_str, mode, rdev);
+        let caller = Caller::new(req);
+        let file_type: FileType;
+        match mode & libc::S_IFMT {
+            libc::S_IFREG | 0 => {
+                file_type = FileType::FILE;
+            }
+            libc::S_IFIFO => {
+                file_type = FileType::FIFO;
+            }
+            libc::S_IFSOCK => {
+                file_type = FileType::SOCKET;
+            }
+            libc::S_IFCHR => {
+                file_type = FileType::CHAR;
+            }
+            libc::S_IFBLK => {
+                file_type = FileType::BLOCK;
+            }
+            _ => {
+                error!("mknod: parent: {}, name: {}, got invalid mode: {:o}", parent, name_str, mode);
+                reply.error(libc::EINVAL);
+                return;
+            }
+        }
+        // only root can create the device, same as CAP_MKNOD.
+        let device_rdev: u32;
+        match file_type {
+            FileType::CHAR | FileType::BLOCK => {
+                if !caller.is_root() {
+                    error!("mknod: uid: {} cannot create device: {} under parent: {}", uid, name_str, parent);
+                    reply.error(libc::EPERM);
+                    return;
+                }
+                device_rdev = rdev;
+            }
+            _ => {
+                device_rdev = 0;
+            }
+        }
+        let ret = self.check_access(&caller, parent, MAY_WRITE | MAY_EXEC);
+        if let Err(err) = ret {
+            error!("mknod: uid: {}, parent: {}, name: {}, permission check failed, err: {}",
+            uid, parent, name_str, err);
+            reply.error(err);
+            return;
+        }
+        let file_info: NewFileInfo;
+        let ret = self.meta_service_mgr.new_ino_leader(
+            parent, &name_str, uid, gid, mode, file_type.to(), device_rdev);
         match ret {
             Ok(ret) => {
-                entrys = ret;
+                file_info = ret;
             }
-            Err(error) => {
-                error!("failed to readdir for ino: {}, offset: {}, err: {:?}", ino, offset, error);
-                reply.error(ENOENT);
+            Err(err) => {
+                error!("mknod: uid: {}, gid: {}, parent: {}, name: {}, mode: {:o}, failed to create, err: {:?}",
+                uid, gid, parent, name_str, mode, err);
+                reply.error(err.to_libc());
                 return;
             }
         }
-        // chech whether entrys is empty.
-        if entrys.is_empty(){
-            reply.error(ENOENT);
+        reply.entry(&self.entry_ttl, &self.to_usefs_attr(&file_info.attr), file_info.attr.generation);
+    }
+
+    fn opendir(&mut self, _req: &Request, ino: u64, flags: u32, reply: ReplyOpen) {
+        let fh = self.handle_cacher.new_fh();
+        info!("opendir: ino: {}, flags: {}, fh: {}", ino, flags, fh);
+        self.dir_streams.insert(fh, DirStream::new());
+        reply.opened(fh, 0);
+    }
+
+    fn releasedir(&mut self, _req: &Request, ino: u64, fh: u64, _flags: u32, reply: ReplyEmpty) {
+        info!("releasedir: ino: {}, fh: {}", ino, fh);
+        self.dir_streams.remove(&fh);
+        reply.ok();
+    }
+
+    // the offset is an opaque cursor, offset 0 lists from the beginning.
+    fn readdir(&mut self, req: &Request, ino: u64, fh: u64, offset: i64, mut reply: ReplyDirectory) {
+        info!("readdir: ino: {}, fh: {}, offset: {}", ino, fh, offset);
+        let ret = self.check_access(&Caller::new(req), ino, MAY_READ);
+        if let Err(err) = ret {
+            error!("readdir: uid: {}, ino: {}, permission check failed, err: {}", req.uid(), ino, err);
+            reply.error(err);
             return;
         }
-        
-        for entry in entrys {
-            reply.add(entry.ino, entry.ino as i64, self.ft_to_fuse_ft(&entry.file_type), entry.name);
+        if offset < dir_stream::DOT_OFFSET && reply.add(ino, dir_stream::DOT_OFFSET, fuse::FileType::Directory, ".") {
+            reply.ok();
+            return;
+        }
+        if offset < dir_stream::DOTDOT_OFFSET {
+            let parent = self.get_dir_parent(ino);
+            if reply.add(parent, dir_stream::DOTDOT_OFFSET, fuse::FileType::Directory, "..") {
+                reply.ok();
+                return;
+            }
+        }
+        // the readdir without opendir uses a temporary stream.
+        let mut stream = self.dir_streams.remove(&fh).unwrap_or(DirStream::new());
+        let meta = self.meta_service_mgr.clone();
+        let mut cursor = dir_stream::to_cursor(offset);
+        let mut added = 0;
+        loop {
+            let entry: metaservice_mgr::types::DirEntry;
+            let ret = stream.peek(cursor, |c| meta.read_dir(ino, c));
+            match ret {
+                Ok(ret) => {
+                    match ret {
+                        Some(ret) => {
+                            entry = ret;
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("readdir: failed to read dir: {}, cursor: {}, err: {:?}", ino, cursor, err);
+                    if added > 0 {
+                        // return what we got, the kernel will retry from the last entry.
+                        break;
+                    }
+                    if fh != 0 {
+                        self.dir_streams.insert(fh, stream);
+                    }
+                    reply.error(err.to_libc());
+                    return;
+                }
+            }
+            if reply.add(entry.ino, dir_stream::to_fuse_offset(entry.offset), self.ft_to_fuse_ft(&entry.file_type), &entry.name) {
+                // the buffer is full.
+                break;
+            }
+            stream.consume();
+            cursor = entry.offset;
+            added += 1;
+        }
+        if fh != 0 {
+            self.dir_streams.insert(fh, stream);
         }
         reply.ok();
     }
 
     fn create(&mut self, req: &Request, parent: u64, name: &OsStr, mode: u32, flags: u32, reply: ReplyCreate){
+        let ret = self.check_read_only("create", parent);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
         let str = name.to_str();
         let string : String;
         match str {
@@ -247,9 +549,16 @@ impl Filesystem for Yigfs {
 
         info!("create: uid: {}, gid: {}, parent: {}, name: {}, mod: {}, flags: {}",
         req.uid(), req.gid(), parent, name, mode, flags);
+        let ret = self.check_access(&Caller::new(req), parent, MAY_WRITE | MAY_EXEC);
+        if let Err(err) = ret {
+            error!("create: uid: {}, parent: {}, name: {}, permission check failed, err: {}",
+            req.uid(), parent, name, err);
+            reply.error(err);
+            return;
+        }
         let file_info: NewFileInfo;
         let ret = self.meta_service_mgr.new_ino_leader(
-            parent, &name, req.uid(), req.gid(), mode, FileType::FILE.to());
+            parent, &name, req.uid(), req.gid(), mode, FileType::FILE.to(), 0);
         match ret {
             Ok(ret ) => {
                 file_info = ret;
@@ -258,7 +567,7 @@ impl Filesystem for Yigfs {
                 if !err.is_exists() {
                     error!("failed to new_ino_leader: parent: {}, name: {}, err: {:?}",
                     parent, name, err);
-                    reply.error(libc::EIO);
+                    reply.error(err.to_libc());
                     return;
                 }
                 warn!("new_ino_leader: parent: {}, name: {} already exists", parent, name);
@@ -266,32 +575,71 @@ impl Filesystem for Yigfs {
                 return;
             }
         }
+        let leader_io = self.leader_mgr.get_leader(&file_info.leader_info.leader);
+        let ret = leader_io.open(file_info.attr.ino, file_info.attr.size, file_info.leader_info.token);
+        if !ret.is_success(){
+            error!("create: failed to open name: {}, ino: {}", name, file_info.attr.ino);
+            reply.error(libc::EIO);
+            return;
+        }
+        // cache ino->leader to reduce the net io, and record the flags for the following io.
+        let fh = self.handle_cacher.new_fh();
         let ret = self.handle_cacher.add_handle_info(FileHandleInfo{
             ino: file_info.attr.ino,
+            fh: fh,
             leader: file_info.leader_info.leader.clone(),
+            flags: flags,
+            follower: false,
         });
         if !ret.is_success(){
             error!("create: failed to add handle cache for name: {}, ino: {}", name, file_info.attr.ino);
+            leader_io.close(file_info.attr.ino);
             reply.error(libc::EIO);
             return;
         }
-        let leader_io = self.leader_mgr.get_leader(&file_info.leader_info.leader);
-        let ret = leader_io.open(file_info.attr.ino);
-        if !ret.is_success(){
-            error!("create: failed to open name: {}, ino: {}", name, file_info.attr.ino);
-            reply.error(libc::EIO);
-            return;
-        }
-        // will check flags and set this later.
-        // cache ino->leader to reduce the net io.
-        reply.created(&TTL, &self.to_usefs_attr(&file_info.attr), file_info.attr.generation, file_info.attr.ino, flags);
+        reply.created(&self.entry_ttl, &self.to_usefs_attr(&file_info.attr), file_info.attr.generation, fh, Yigfs::open_flags(flags));
     }
 
     fn open(&mut self, req: &Request, ino: u64, flags: u32, reply: ReplyOpen){
         let file_leader_info : FileLeader;
         info!("open: uid: {}, gid: {}, ino: {}, flags: {}",
         req.uid(), req.gid(), ino, flags);
-        let ret = self.meta_service_mgr.get_file_leader(ino);
+        let mut mask: u32;
+        match flags as i32 & libc::O_ACCMODE {
+            libc::O_RDONLY => {
+                mask = MAY_READ;
+            }
+            libc::O_WRONLY => {
+                mask = MAY_WRITE;
+            }
+            _ => {
+                mask = MAY_READ | MAY_WRITE;
+            }
+        }
+        if flags as i32 & libc::O_TRUNC != 0 {
+            mask |= MAY_WRITE;
+        }
+        if mask & MAY_WRITE != 0 {
+            let ret = self.check_read_only("open", ino);
+            if let Err(err) = ret {
+                reply.error(err);
+                return;
+            }
+        }
+        let file_size: u64;
+        let ret = self.check_access(&Caller::new(req), ino, mask);
+        match ret {
+            Ok(ret) => {
+                file_size = ret.size;
+            }
+            Err(err) => {
+                error!("open: uid: {}, ino: {}, flags: {}, permission check failed, err: {}",
+                req.uid(), ino, flags, err);
+                reply.error(err);
+                return;
+            }
+        }
+        let ret = self.get_file_leader(ino);
         match ret {
             Ok(ret) => {
                 file_leader_info = ret;
@@ -299,87 +647,240 @@ impl Filesystem for Yigfs {
             }
             Err(err) => {
                 error!("failed to get_file_leader for ino: {}, err: {:?}", ino, err);
-                reply.error(libc::EBADF);
+                reply.error(err.to_libc());
                 return;
             }
         }
-        let leader = self.leader_mgr.get_leader(&file_leader_info.leader);
+        let mut leader = self.leader_mgr.get_leader(&file_leader_info.leader);
+        let mut follower = false;
+        // the read-only opens of the files led by other machines read from the backend store if the follower is enabled.
+        if mask == MAY_READ && file_leader_info.leader != self.meta_service_mgr.get_machine_id() {
+            if let Some(f) = self.leader_mgr.get_follower() {
+                info!("open: ino: {} led by {} is read by the follower", ino, file_leader_info.leader);
+                leader = f;
+                follower = true;
+            }
+        }
+        let ret = leader.open(ino, file_size, file_leader_info.token);
+        if !ret.is_success() {
+            error!("open: failed to open ino: {}, err: {:?}", ino, ret);
+            reply.error(libc::EIO);
+            return;
+        }
+        let fh = self.handle_cacher.new_fh();
         let ret = self.handle_cacher.add_handle_info(FileHandleInfo{
             ino: ino,
+            fh: fh,
             leader: file_leader_info.leader.clone(),
+            flags: flags,
+            follower: follower,
         });
         if !ret.is_success() {
             error!("open: failed to add handle cache for ino: {}, leader: {}", ino, file_leader_info.leader);
+            leader.close(ino);
             reply.error(libc::EBADF);
             return;
         }
-        let ret = leader.open(ino);
-        if ret.is_success() {
-            reply.opened(ino, flags);
-            return;
-        }
-        error!("open: failed to open ino: {}, err: {:?}", ino, ret);
-        reply.error(libc::EIO);
-        return;        
+        reply.opened(fh, Yigfs::open_flags(flags));
     }
 
-    fn write(&mut self, _req: &Request, ino: u64, _fh: u64, offset: i64, data: &[u8], _flags: u32, reply: ReplyWrite){
+    fn write(&mut self, _req: &Request, ino: u64, fh: u64, offset: i64, data: &[u8], _flags: u32, reply: ReplyWrite){
+        let ret = self.check_read_only("write", ino);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
         //println!("write: uid: {}, gid: {}, ino: {}, fh: {}, offset: {}, data_size: {}, flags: {}",
         //req.uid(), req.gid(), ino, fh, offset, data.len(), flags);
         // get the file leader ip.
-        let leader: String;
-        let ret = self.handle_cacher.get_handle_info(ino);
+        let handle: FileHandleInfo;
+        let ret = self.get_fh_info(ino, fh);
         match ret {
             Ok(ret) => {
-                leader = ret.leader;
+                handle = ret;
             }
             Err(err) => {
-                error!("write: file ino: {} is not opened yet, err: {:?}.", ino, err);
-                reply.error(libc::EBADF);
+                error!("write: file ino: {}, fh: {} is not opened yet.", ino, fh);
+                reply.error(err);
                 return;
             }
         }
+        if !handle.can_write() {
+            error!("write: fh: {} of ino: {} is not opened for writing, flags: {}", fh, ino, handle.flags);
+            reply.error(libc::EBADF);
+            return;
+        }
         // get the leader.
-        let leader_io = self.leader_mgr.get_leader(&leader);
-        let ret = leader_io.write(ino, offset as u64, data);
-        match ret {
-            Ok(ret) => {
-                reply.written(ret.size);
-                return;
+        let mut leader_io = self.leader_mgr.get_leader(&handle.leader);
+        let mut offset = offset as u64;
+        // O_APPEND always writes at the end of file, the size in kernel may be stale.
+        if handle.is_append() {
+            let mut ret = leader_io.get_file_size(ino);
+            if let Err(Errno::Etimedout) = ret {
+                if let Some(l) = self.failover(ino, &handle.leader) {
+                    leader_io = l;
+                    ret = leader_io.get_file_size(ino);
+                }
             }
-            Err(err) => {
-                error!("write: failed to write ino: {}, offset: {}, err: {:?}",
-                ino, offset, err);
+            match ret {
+                Ok(ret) => {
+                    offset = ret;
+                }
+                Err(err) => {
+                    error!("write: failed to get file size of ino: {} for append, err: {:?}", ino, err);
+                    reply.error(libc::EIO);
+                    return;
+                }
+            }
+        }
+        // hand the data to the leader in pieces of at most max_write.
+        let mut chunk_size = self.max_write as usize;
+        if chunk_size == 0 {
+            chunk_size = std::cmp::max(data.len(), 1);
+        }
+        let mut written: u32 = 0;
+        for chunk in data.chunks(chunk_size) {
+            let mut ret = leader_io.write(ino, offset + written as u64, chunk);
+            if let Err(Errno::Etimedout) = ret {
+                if let Some(l) = self.failover(ino, &handle.leader) {
+                    leader_io = l;
+                    ret = leader_io.write(ino, offset + written as u64, chunk);
+                }
+            }
+            match ret {
+                Ok(ret) => {
+                    written += ret.size;
+                }
+                Err(err) => {
+                    error!("write: failed to write ino: {}, offset: {}, err: {:?}",
+                    ino, offset + written as u64, err);
+                    reply.error(libc::EIO);
+                    return;
+                }
+            }
+        }
+        if handle.is_sync() || handle.is_dsync() {
+            let err = leader_io.fsync(ino, !handle.is_sync());
+            if !err.is_success() {
+                error!("write: failed to sync ino: {}, fh: {} for flags: {}, err: {:?}",
+                ino, fh, handle.flags, err);
                 reply.error(libc::EIO);
                 return;
             }
         }
+        reply.written(written);
     }
 
     fn release(&mut self, req: &Request, ino: u64, fh: u64, flags: u32, lock_owner: u64, flush: bool, reply: ReplyEmpty) {
         info!("release: uid: {}, gid: {}, ino: {}, fh: {}, flags: {}, lock_owner: {}, flush: {}", 
         req.uid(), req.gid(), ino, fh, flags, lock_owner, flush);
-        let ret = self.handle_cacher.get_handle_info(ino);
+        // the handle is released even if close fails, such as losing the buffered data, the error is replied at last.
+        let close_ret: Errno;
+        let ret = self.get_fh_info(ino, fh);
         match ret {
             Ok(ret) => {
-                let leader = self.leader_mgr.get_leader(&ret.leader);
-                let err = leader.close(ino);
-                if !err.is_success(){
-                    error!("release: failed to close ino: {}, err: {:?}", ino, err);
+                let leader = self.get_leader_io(&ret);
+                close_ret = leader.close(ino);
+                if !close_ret.is_success(){
+                    error!("release: failed to close ino: {}, err: {:?}", ino, close_ret);
                 }
             }
             Err(err) => {
-                error!("release: failed to get handle for ino: {}, err: {:?}", ino, err);
+                error!("release: failed to get handle for ino: {}, fh: {}", ino, fh);
+                reply.error(err);
+                return;
             }
         }
-        let err = self.handle_cacher.del_handle_info(ino);
+        let err = self.handle_cacher.del_handle_info(fh);
         if !err.is_success() {
-            error!("release: failed to del handle for ino: {}, err: {:?}", ino, err);
+            error!("release: failed to del handle for ino: {}, fh: {}, err: {:?}", ino, fh, err);
+        }
+        // the last release of the unlinked file deletes it.
+        if self.orphans.contains(&ino) && !self.is_opened(ino) {
+            info!("release: delete the unlinked ino: {} on the last release", ino);
+            self.reclaim_orphan(ino);
+        }
+        if !close_ret.is_success() {
+            reply.error(libc::EIO);
+            return;
+        }
+        reply.ok();
+    }
+
+    /// Flush method.
+    /// called on each close() of the opened file, make the written data visible to other clients.
+    fn flush(&mut self, _req: &Request, ino: u64, fh: u64, lock_owner: u64, reply: ReplyEmpty) {
+        info!("flush: ino: {}, fh: {}, lock_owner: {}", ino, fh, lock_owner);
+        let handle: FileHandleInfo;
+        let ret = self.get_fh_info(ino, fh);
+        match ret {
+            Ok(ret) => {
+                handle = ret;
+            }
+            Err(err) => {
+                error!("flush: file ino: {}, fh: {} is not opened yet.", ino, fh);
+                reply.error(err);
+                return;
+            }
         }
+        let leader_io = self.get_leader_io(&handle);
+        let mut ret = leader_io.flush(ino);
+        if ret.is_timedout() {
+            if let Some(l) = self.failover(ino, &handle.leader) {
+                ret = l.flush(ino);
+            }
+        }
+        if !ret.is_success() {
+            error!("flush: failed to flush ino: {}, err: {:?}", ino, ret);
+            reply.error(libc::EIO);
+            return;
+        }
+        reply.ok();
+    }
+
+    /// Synchronize file contents.
+    fn fsync(&mut self, _req: &Request, ino: u64, fh: u64, datasync: bool, reply: ReplyEmpty) {
+        info!("fsync: ino: {}, fh: {}, datasync: {}", ino, fh, datasync);
+        let handle: FileHandleInfo;
+        let ret = self.get_fh_info(ino, fh);
+        match ret {
+            Ok(ret) => {
+                handle = ret;
+            }
+            Err(err) => {
+                error!("fsync: file ino: {}, fh: {} is not opened yet.", ino, fh);
+                reply.error(err);
+                return;
+            }
+        }
+        let leader_io = self.get_leader_io(&handle);
+        let mut ret = leader_io.fsync(ino, datasync);
+        if ret.is_timedout() {
+            if let Some(l) = self.failover(ino, &handle.leader) {
+                ret = l.fsync(ino, datasync);
+            }
+        }
+        if !ret.is_success() {
+            error!("fsync: failed to fsync ino: {}, err: {:?}", ino, ret);
+            reply.error(libc::EIO);
+            return;
+        }
+        reply.ok();
+    }
+
+    /// Synchronize directory contents.
+    /// the dir entries are updated into meta server synchronously, so nothing to do here.
+    fn fsyncdir(&mut self, _req: &Request, ino: u64, fh: u64, datasync: bool, reply: ReplyEmpty) {
+        info!("fsyncdir: ino: {}, fh: {}, datasync: {}", ino, fh, datasync);
         reply.ok();
     }
 
     fn unlink(&mut self, req: &Request, ino: u64, name: &OsStr, reply: ReplyEmpty) {
+        let ret = self.check_read_only("unlink", ino);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
         let file_name: String;
         if let Some(n) = name.to_str() {
             file_name = n.to_string();
@@ -389,72 +890,868 @@ impl Filesystem for Yigfs {
             reply.error(libc::EBADMSG);
             return;
         }
+        let caller = Caller::new(req);
+        let parent_attr: metaservice_mgr::types::FileAttr;
+        let ret = self.check_access(&caller, ino, MAY_WRITE | MAY_EXEC);
+        match ret {
+            Ok(ret) => {
+                parent_attr = ret;
+            }
+            Err(err) => {
+                error!("unlink: uid: {}, parent ino: {}, name: {}, permission check failed, err: {}",
+                req.uid(), ino, file_name, err);
+                reply.error(err);
+                return;
+            }
+        }
         let file_ino: u64;
         let ret = self.meta_service_mgr.read_dir_file_attr(ino, &file_name);
         match ret {
             Ok(attr) => {
+                if !caller.can_delete(&parent_attr, &attr) {
+                    error!("unlink: uid: {} cannot remove name: {} in sticky dir: {}", req.uid(), file_name, ino);
+                    reply.error(libc::EPERM);
+                    return;
+                }
                 file_ino = attr.ino;
             }
             Err(err) => {
                 error!("unlink: uid: {}, gid: {}, parent ino: {}, name: {}, failed to get file attr, err: {:?}",
                 req.uid(), req.gid(), ino, file_name, err);
-                reply.error(libc::EBADMSG);
+                reply.error(err.to_libc());
                 return;
             }
         }
         info!("unlink: uid: {}, gid: {}, parent ino: {}, name: {}, ino: {}", 
         req.uid(), req.gid(), ino, file_name, file_ino);
-        let ret = self.meta_service_mgr.delete_file(file_ino);
+        // hard links are not supported, so the name is the only link of the ino.
+        let ret = self.delete_unlinked(ino, &file_name, file_ino);
         if !ret.is_success(){
-            error!("unlink: failed to remove the file, ino: {}", ino);
-            reply.error(libc::EIO);
+            error!("unlink: failed to remove the file, parent ino: {}, name: {}, ino: {}, err: {:?}",
+            ino, file_name, file_ino, ret);
+            reply.error(ret.to_libc());
             return;
         }
         reply.ok();
     }
 
-    /// Rename a file.
-    fn rename(&mut self, req: &Request, parent: u64, name: &OsStr, newparent: u64, newname: &OsStr, reply: ReplyEmpty) {
-        let uid = req.uid();
-        let gid = req.gid();
-        let origin_name: String;
-        let new_name: String;
-        if let Some(n) = name.to_str() {
-            origin_name = n.to_string();
-        } else {
-            error!("rename: got invalid name for parent: {}", parent);
-            reply.error(libc::EBADMSG);
+    /// Remove a directory.
+    fn rmdir(&mut self, req: &Request, parent: u64, name: &OsStr, reply: ReplyEmpty) {
+        let ret = self.check_read_only("rmdir", parent);
+        if let Err(err) = ret {
+            reply.error(err);
             return;
         }
-        if let Some(n) = newname.to_str() {
-            new_name = n.to_string();
+        let dir_name: String;
+        if let Some(n) = name.to_str() {
+            dir_name = n.to_string();
         } else {
-            error!("rename: got invalid new name for parent: {}", parent);
+            error!("rmdir: uid: {}, gid: {}, parent ino: {}, got invalid name",
+            req.uid(), req.gid(), parent);
             reply.error(libc::EBADMSG);
             return;
         }
-        info!("rename: uid: {}, gid: {}, parent: {}, name: {}, new parent: {}, new name: {}", 
-        uid, gid, parent, origin_name, newparent, new_name);
-        let ret = self.meta_service_mgr.rename(parent, &origin_name, newparent, &new_name);
-        if ret.is_success() {
-            reply.ok();
-            return;
+        info!("rmdir: uid: {}, gid: {}, parent ino: {}, name: {}",
+        req.uid(), req.gid(), parent, dir_name);
+        let ret = self.check_delete(&Caller::new(req), parent, &dir_name);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("rmdir: uid: {}, parent ino: {}, name: {}, permission check failed, err: {}",
+                req.uid(), parent, dir_name, err);
+                reply.error(err);
+                return;
+            }
         }
-        error!("rename: failed to rename({}, {}) to ({}, {}), err: {:?}",
-        parent, origin_name, newparent, new_name, ret);
-        reply.error(libc::EIO);
-    }
-}
-
-impl Yigfs{
-    pub fn create(meta: Arc<dyn MetaServiceMgr>, leader_mgr: LeaderMgr)-> Yigfs{
-        Yigfs{
+        let ret = self.meta_service_mgr.delete_dir(parent, &dir_name);
+        match ret {
+            Ok(attr) => {
+                info!("rmdir: removed parent: {}, name: {}, parent attr: {:?}", parent, dir_name, attr);
+                reply.ok();
+            }
+            Err(err) => {
+                error!("rmdir: failed to remove parent: {}, name: {}, err: {:?}", parent, dir_name, err);
+                reply.error(err.to_libc());
+            }
+        }
+    }
+
+    /// Create a symbolic link.
+    fn symlink(&mut self, req: &Request, parent: u64, name: &OsStr, link: &Path, reply: ReplyEntry) {
+        let ret = self.check_read_only("symlink", parent);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
+        let uid = req.uid();
+        let gid = req.gid();
+        let link_name: String;
+        if let Some(n) = name.to_str() {
+            link_name = n.to_string();
+        } else {
+            error!("symlink: uid: {}, gid: {}, parent: {}, got invalid name", uid, gid, parent);
+            reply.error(libc::EBADMSG);
+            return;
+        }
+        let target: String;
+        if let Some(t) = link.to_str() {
+            target = t.to_string();
+        } else {
+            error!("symlink: uid: {}, gid: {}, parent: {}, name: {}, got invalid target",
+            uid, gid, parent, link_name);
+            reply.error(libc::EBADMSG);
+            return;
+        }
+        if target.is_empty() {
+            error!("symlink: uid: {}, gid: {}, parent: {}, name: {}, got empty target",
+            uid, gid, parent, link_name);
+            reply.error(ENOENT);
+            return;
+        }
+        if target.len() >= libc::PATH_MAX as usize {
+            error!("symlink: uid: {}, gid: {}, parent: {}, name: {}, target len: {} is too long",
+            uid, gid, parent, link_name, target.len());
+            reply.error(libc::ENAMETOOLONG);
+            return;
+        }
+        info!("symlink: uid: {}, gid: {}, parent: {}, name: {}, target: {}",
+        uid, gid, parent, link_name, target);
+        let ret = self.check_access(&Caller::new(req), parent, MAY_WRITE | MAY_EXEC);
+        if let Err(err) = ret {
+            error!("symlink: uid: {}, parent: {}, name: {}, permission check failed, err: {}",
+            uid, parent, link_name, err);
+            reply.error(err);
+            return;
+        }
+        let ret = self.meta_service_mgr.create_symlink(parent, &link_name, uid, gid, &target);
+        match ret {
+            Ok(attr) => {
+                reply.entry(&self.entry_ttl, &self.to_usefs_attr(&attr), attr.generation);
+            }
+            Err(err) => {
+                error!("symlink: uid: {}, gid: {}, parent: {}, name: {}, target: {}, failed to create, err: {:?}",
+                uid, gid, parent, link_name, target, err);
+                reply.error(err.to_libc());
+            }
+        }
+    }
+
+    /// Read symbolic link.
+    fn readlink(&mut self, _req: &Request, ino: u64, reply: ReplyData) {
+        info!("readlink: ino: {}", ino);
+        let ret = self.meta_service_mgr.read_symlink(ino);
+        match ret {
+            Ok(target) => {
+                reply.data(target.as_bytes());
+            }
+            Err(err) => {
+                error!("readlink: failed to read the target of ino: {}, err: {:?}", ino, err);
+                reply.error(err.to_libc());
+            }
+        }
+    }
+
+    /// Set an extended attribute.
+    fn setxattr(&mut self, req: &Request, ino: u64, name: &OsStr, value: &[u8], flags: u32, _position: u32, reply: ReplyEmpty) {
+        let ret = self.check_read_only("setxattr", ino);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
+        let attr_name: String;
+        let ret = self.check_xattr_name(req, name);
+        match ret {
+            Ok(ret) => {
+                attr_name = ret;
+            }
+            Err(err) => {
+                error!("setxattr: uid: {}, ino: {}, name: {:?}, invalid name, err: {}", req.uid(), ino, name, err);
+                reply.error(err);
+                return;
+            }
+        }
+        if value.len() > XATTR_SIZE_MAX {
+            error!("setxattr: ino: {}, name: {}, value size: {} is too large", ino, attr_name, value.len());
+            reply.error(libc::E2BIG);
+            return;
+        }
+        let flags = flags as i32;
+        if flags & !(libc::XATTR_CREATE | libc::XATTR_REPLACE) != 0 ||
+            flags == (libc::XATTR_CREATE | libc::XATTR_REPLACE) {
+            error!("setxattr: ino: {}, name: {}, got invalid flags: {}", ino, attr_name, flags);
+            reply.error(libc::EINVAL);
+            return;
+        }
+        info!("setxattr: uid: {}, ino: {}, name: {}, size: {}, flags: {}", req.uid(), ino, attr_name, value.len(), flags);
+        let ret = self.meta_service_mgr.set_xattr(ino, &attr_name, value, flags);
+        match ret {
+            Ok(_) => {
+                reply.ok();
+            }
+            Err(err) => {
+                error!("setxattr: failed to set xattr for ino: {}, name: {}, err: {:?}", ino, attr_name, err);
+                reply.error(err.to_libc());
+            }
+        }
+    }
+
+    /// Get an extended attribute.
+    /// if size is 0, the size of the value should be sent with reply.size().
+    fn getxattr(&mut self, req: &Request, ino: u64, name: &OsStr, size: u32, reply: ReplyXattr) {
+        let attr_name: String;
+        let ret = self.check_xattr_name(req, name);
+        match ret {
+            Ok(ret) => {
+                attr_name = ret;
+            }
+            Err(err) => {
+                error!("getxattr: uid: {}, ino: {}, name: {:?}, invalid name, err: {}", req.uid(), ino, name, err);
+                // the trusted attributes are invisible to the unprivileged users.
+                if err == libc::EPERM {
+                    reply.error(libc::ENODATA);
+                    return;
+                }
+                reply.error(err);
+                return;
+            }
+        }
+        info!("getxattr: uid: {}, ino: {}, name: {}, size: {}", req.uid(), ino, attr_name, size);
+        let value: Vec<u8>;
+        let ret = self.meta_service_mgr.get_xattr(ino, &attr_name);
+        match ret {
+            Ok(ret) => {
+                value = ret;
+            }
+            Err(err) => {
+                // missing attribute is common, don't log it as error.
+                if !err.is_enodata() {
+                    error!("getxattr: failed to get xattr for ino: {}, name: {}, err: {:?}", ino, attr_name, err);
+                }
+                reply.error(err.to_libc());
+                return;
+            }
+        }
+        if size == 0 {
+            reply.size(value.len() as u32);
+            return;
+        }
+        if value.len() > size as usize {
+            reply.error(libc::ERANGE);
+            return;
+        }
+        reply.data(&value);
+    }
+
+    /// List extended attribute names.
+    /// the names are returned as null-terminated strings one by one.
+    fn listxattr(&mut self, req: &Request, ino: u64, size: u32, reply: ReplyXattr) {
+        info!("listxattr: uid: {}, ino: {}, size: {}", req.uid(), ino, size);
+        let names: Vec<String>;
+        let ret = self.meta_service_mgr.list_xattr(ino);
+        match ret {
+            Ok(ret) => {
+                names = ret;
+            }
+            Err(err) => {
+                error!("listxattr: failed to list xattr for ino: {}, err: {:?}", ino, err);
+                reply.error(err.to_libc());
+                return;
+            }
+        }
+        let mut data = Vec::<u8>::new();
+        for n in &names {
+            if n.starts_with(XATTR_TRUSTED_PREFIX) && req.uid() != 0 {
+                continue;
+            }
+            data.extend_from_slice(n.as_bytes());
+            data.push(0);
+        }
+        if data.len() > XATTR_LIST_MAX {
+            error!("listxattr: ino: {}, the size of the names: {} exceeds the limit", ino, data.len());
+            reply.error(libc::E2BIG);
+            return;
+        }
+        if size == 0 {
+            reply.size(data.len() as u32);
+            return;
+        }
+        if data.len() > size as usize {
+            reply.error(libc::ERANGE);
+            return;
+        }
+        reply.data(&data);
+    }
+
+    /// Remove an extended attribute.
+    fn removexattr(&mut self, req: &Request, ino: u64, name: &OsStr, reply: ReplyEmpty) {
+        let ret = self.check_read_only("removexattr", ino);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
+        let attr_name: String;
+        let ret = self.check_xattr_name(req, name);
+        match ret {
+            Ok(ret) => {
+                attr_name = ret;
+            }
+            Err(err) => {
+                error!("removexattr: uid: {}, ino: {}, name: {:?}, invalid name, err: {}", req.uid(), ino, name, err);
+                reply.error(err);
+                return;
+            }
+        }
+        info!("removexattr: uid: {}, ino: {}, name: {}", req.uid(), ino, attr_name);
+        let ret = self.meta_service_mgr.remove_xattr(ino, &attr_name);
+        match ret {
+            Ok(_) => {
+                reply.ok();
+            }
+            Err(err) => {
+                error!("removexattr: failed to remove xattr for ino: {}, name: {}, err: {:?}", ino, attr_name, err);
+                reply.error(err.to_libc());
+            }
+        }
+    }
+
+    /// Rename a file.
+    fn rename(&mut self, req: &Request, parent: u64, name: &OsStr, newparent: u64, newname: &OsStr, reply: ReplyEmpty) {
+        let ret = self.check_read_only("rename", parent);
+        if let Err(err) = ret {
+            reply.error(err);
+            return;
+        }
+        let origin_name: String;
+        let new_name: String;
+        if let Some(n) = name.to_str() {
+            origin_name = n.to_string();
+        } else {
+            error!("rename: got invalid name for parent: {}", parent);
+            reply.error(libc::EBADMSG);
+            return;
+        }
+        if let Some(n) = newname.to_str() {
+            new_name = n.to_string();
+        } else {
+            error!("rename: got invalid new name for parent: {}", parent);
+            reply.error(libc::EBADMSG);
+            return;
+        }
+        let ret = self.rename_file(&Caller::new(req), parent, &origin_name, newparent, &new_name);
+        match ret {
+            Ok(_) => {
+                reply.ok();
+            }
+            Err(err) => {
+                reply.error(err);
+            }
+        }
+    }
+
+    /// Check file access permissions.
+    fn access(&mut self, req: &Request, ino: u64, mask: u32, reply: ReplyEmpty) {
+        info!("access: uid: {}, gid: {}, ino: {}, mask: {}", req.uid(), req.gid(), ino, mask);
+        if mask & MAY_WRITE != 0 {
+            let ret = self.check_read_only("access", ino);
+            if let Err(err) = ret {
+                reply.error(err);
+                return;
+            }
+        }
+        let ret = self.check_access(&Caller::new(req), ino, mask & (MAY_READ | MAY_WRITE | MAY_EXEC));
+        match ret {
+            Ok(_) => {
+                reply.ok();
+            }
+            Err(err) => {
+                reply.error(err);
+            }
+        }
+    }
+
+    /// Get file system statistics.
+    /// the free space is the free space of the local cache, because all the writes go to the cache first.
+    /// the total space is the used space of the namespace plus the free space.
+    fn statfs(&mut self, _req: &Request, ino: u64, reply: ReplyStatfs) {
+        let usage: FsUsage;
+        let ret = self.meta_service_mgr.get_fs_usage();
+        match ret {
+            Ok(ret) => {
+                usage = ret;
+            }
+            Err(err) => {
+                error!("statfs: ino: {}, failed to get fs usage, err: {:?}", ino, err);
+                reply.error(err.to_libc());
+                return;
+            }
+        }
+        let cap = self.segment_mgr.get_cache_capacity();
+        let bsize = BLOCK_SIZE as u64;
+        let used_blocks = (usage.used_size + bsize - 1) / bsize;
+        let free_blocks = cap.free / bsize;
+        let free_files = MAX_FILES.saturating_sub(usage.file_count);
+        info!("statfs: ino: {}, usage: {:?}, cache capacity: {:?}", ino, usage, cap);
+        reply.statfs(used_blocks + free_blocks, free_blocks, free_blocks,
+            usage.file_count + free_files, free_files, BLOCK_SIZE, MAX_NAME_LEN, BLOCK_SIZE);
+    }
+}
+
+impl Yigfs{
+    pub fn create(meta: Arc<dyn MetaServiceMgr>, leader_mgr: LeaderMgr, segment_mgr: Arc<SegmentMgr>, opts: &MountOptions)-> Yigfs{
+        Yigfs{
             meta_service_mgr: meta,
             leader_mgr: leader_mgr,
+            segment_mgr: segment_mgr,
             handle_cacher: FileHandleInfoMgr::new(),
+            orphans: HashSet::new(),
+            dir_streams: HashMap::new(),
             fsid: uuid::uuid_string(),
+            read_only: opts.read_only,
+            attr_ttl: Yigfs::ms_to_ts(opts.attr_timeout_ms),
+            entry_ttl: Yigfs::ms_to_ts(opts.entry_timeout_ms),
+            max_write: opts.max_write,
+            stopped: false,
+        }
+    }
+
+    // flush the opened files and stop the leaders, it is called on destroy or when the session ends.
+    // the unlinked files which are still opened are left as orphans and reclaimed by the next mount.
+    fn shutdown(&mut self) {
+        if self.stopped {
+            return;
+        }
+        self.stopped = true;
+        info!("shutdown: fsid: {}, {} orphans are left", self.fsid, self.orphans.len());
+        self.leader_mgr.stop();
+        self.handle_cacher.stop();
+    }
+
+    // the parent of dir ino recorded by meta server, the unknown one is listed as itself like the root.
+    fn get_dir_parent(&self, ino: u64) -> u64 {
+        let ret = self.meta_service_mgr.read_file_attr(ino);
+        match ret {
+            Ok(ret) => {
+                if ret.parent == 0 {
+                    return ino;
+                }
+                return ret.parent;
+            }
+            Err(err) => {
+                warn!("get_dir_parent: failed to read attr of dir: {}, err: {:?}", ino, err);
+                return ino;
+            }
+        }
+    }
+
+    fn ms_to_ts(ms: u64) -> Timespec {
+        Timespec::new((ms / 1000) as i64, ((ms % 1000) * 1_000_000) as i32)
+    }
+
+    // reject the mutating request if the mount is read-only.
+    fn check_read_only(&self, op: &str, ino: u64) -> Result<(), c_int> {
+        if self.read_only {
+            error!("{}: ino: {} cannot be changed on the read-only mount", op, ino);
+            return Err(libc::EROFS);
+        }
+        Ok(())
+    }
+    // read the attr of ino and check whether the caller has the permission of mask on it.
+    fn check_access(&self, caller: &Caller, ino: u64, mask: u32) -> Result<metaservice_mgr::types::FileAttr, c_int> {
+        let attr: metaservice_mgr::types::FileAttr;
+        let ret = self.meta_service_mgr.read_file_attr(ino);
+        match ret {
+            Ok(ret) => {
+                attr = ret;
+            }
+            Err(err) => {
+                error!("check_access: failed to get attr for ino: {}, err: {:?}", ino, err);
+                return Err(err.to_libc());
+            }
+        }
+        if !caller.can_access(&attr, mask) {
+            return Err(libc::EACCES);
+        }
+        Ok(attr)
+    }
+
+    // check whether the caller can remove name from parent, returns the attr of name.
+    fn check_delete(&self, caller: &Caller, parent: u64, name: &String) -> Result<metaservice_mgr::types::FileAttr, c_int> {
+        let parent_attr = self.check_access(caller, parent, MAY_WRITE | MAY_EXEC)?;
+        let attr: metaservice_mgr::types::FileAttr;
+        let ret = self.meta_service_mgr.read_dir_file_attr(parent, name);
+        match ret {
+            Ok(ret) => {
+                attr = ret;
+            }
+            Err(err) => {
+                error!("check_delete: failed to get attr for parent: {}, name: {}, err: {:?}", parent, name, err);
+                return Err(err.to_libc());
+            }
+        }
+        if !caller.can_delete(&parent_attr, &attr) {
+            return Err(libc::EPERM);
+        }
+        Ok(attr)
+    }
+
+    // rename (parent, name) to (newparent, new_name),
+    // the existing target is replaced atomically and freed if it has no links.
+    // fuse 0.3 doesn't pass the renameat2 flags, the kernel rejects them before calling rename.
+    fn rename_file(&mut self, caller: &Caller, parent: u64, name: &String, newparent: u64, new_name: &String) -> Result<(), c_int> {
+        info!("rename: uid: {}, gid: {}, parent: {}, name: {}, new parent: {}, new name: {}",
+        caller.uid, caller.gid, parent, name, newparent, new_name);
+        let origin_attr: metaservice_mgr::types::FileAttr;
+        let ret = self.check_delete(caller, parent, name);
+        match ret {
+            Ok(ret) => {
+                origin_attr = ret;
+            }
+            Err(err) => {
+                error!("rename: uid: {}, parent: {}, name: {}, permission check failed, err: {}",
+                caller.uid, parent, name, err);
+                return Err(err);
+            }
+        }
+        let target_attr: Option<metaservice_mgr::types::FileAttr>;
+        let ret = self.check_delete(caller, newparent, new_name);
+        match ret {
+            Ok(ret) => {
+                target_attr = Some(ret);
+            }
+            Err(err) => {
+                // the target doesn't exist is ok, but the new parent must be writable.
+                if err != ENOENT {
+                    error!("rename: uid: {}, new parent: {}, new name: {}, permission check failed, err: {}",
+                    caller.uid, newparent, new_name, err);
+                    return Err(err);
+                }
+                let ret = self.check_access(caller, newparent, MAY_WRITE | MAY_EXEC);
+                if let Err(err) = ret {
+                    error!("rename: uid: {}, new parent: {}, permission check failed, err: {}",
+                    caller.uid, newparent, err);
+                    return Err(err);
+                }
+                target_attr = None;
+            }
+        }
+        let origin_is_dir = matches!(origin_attr.kind, FileType::DIR);
+        // a dir cannot be moved into itself or any of its descendants.
+        if origin_is_dir && parent != newparent {
+            let ret = self.is_ancestor(origin_attr.ino, newparent);
+            match ret {
+                Ok(ret) => {
+                    if ret {
+                        error!("rename: cannot move dir: {} into its descendant: {}", origin_attr.ino, newparent);
+                        return Err(libc::EINVAL);
+                    }
+                }
+                Err(err) => {
+                    error!("rename: failed to check the ancestors of new parent: {}, err: {}", newparent, err);
+                    return Err(err);
+                }
+            }
+        }
+        if let Some(ref target) = target_attr {
+            // both names refer to the same ino, nothing to do.
+            if target.ino == origin_attr.ino {
+                return Ok(());
+            }
+            let target_is_dir = matches!(target.kind, FileType::DIR);
+            if origin_is_dir && !target_is_dir {
+                return Err(libc::ENOTDIR);
+            }
+            if !origin_is_dir && target_is_dir {
+                return Err(libc::EISDIR);
+            }
+        }
+        // moving a dir to another parent needs to update its "..", so it must be writable.
+        if origin_is_dir && parent != newparent && !caller.can_access(&origin_attr, MAY_WRITE) {
+            error!("rename: uid: {} has no write permission on dir: {}", caller.uid, origin_attr.ino);
+            return Err(libc::EACCES);
+        }
+        let replaced: Option<metaservice_mgr::types::FileAttr>;
+        let ret = self.meta_service_mgr.rename(parent, name, newparent, new_name);
+        match ret {
+            Ok(ret) => {
+                replaced = ret;
+            }
+            Err(err) => {
+                error!("rename: failed to rename({}, {}) to ({}, {}), err: {:?}",
+                parent, name, newparent, new_name, err);
+                return Err(err.to_libc());
+            }
+        }
+        // free the replaced target, the rename itself has succeeded.
+        if let Some(attr) = replaced {
+            let ret = self.delete_unlinked(newparent, new_name, attr.ino);
+            if !ret.is_success() {
+                error!("rename: failed to remove the replaced ino: {}, err: {:?}", attr.ino, ret);
+            }
+        }
+        Ok(())
+    }
+
+    // whether dir is ino or one of its ancestors, the ancestors are walked up to the root.
+    fn is_ancestor(&self, dir: u64, ino: u64) -> Result<bool, c_int> {
+        let mut cur = ino;
+        // the depth is limited in case the parents loop.
+        for _ in 0..MAX_DIR_DEPTH {
+            if cur == dir {
+                return Ok(true);
+            }
+            if cur == fuse::FUSE_ROOT_ID {
+                return Ok(false);
+            }
+            let ret = self.meta_service_mgr.read_file_attr(cur);
+            match ret {
+                Ok(ret) => {
+                    if ret.parent == 0 || ret.parent == cur {
+                        return Ok(false);
+                    }
+                    cur = ret.parent;
+                }
+                Err(err) => {
+                    error!("is_ancestor: failed to get attr for ino: {}, err: {:?}", cur, err);
+                    return Err(err.to_libc());
+                }
+            }
+        }
+        error!("is_ancestor: ino: {} is deeper than {} levels", ino, MAX_DIR_DEPTH);
+        Err(libc::ELOOP)
+    }
+
+    // delete the ino whose name under parent is removed, the opened one is deleted on its last release.
+    fn delete_unlinked(&mut self, parent: u64, name: &String, ino: u64) -> Errno {
+        if self.is_opened(ino) {
+            // the name is removed by the meta server, but the ino is kept as an orphan.
+            let ret = self.meta_service_mgr.add_orphan(parent, name, ino);
+            if let Err(err) = ret {
+                error!("delete_unlinked: failed to add orphan for ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+            info!("delete_unlinked: ino: {} is still opened, delete it on the last release", ino);
+            self.orphans.insert(ino);
+            return Errno::Esucc;
+        }
+        self.meta_service_mgr.unlink_file(parent, name, ino)
+    }
+
+    // whether ino is opened by the leader or the follower opens, it is treated as opened if unknown,
+    // so that the deletion is deferred rather than breaking the opens.
+    fn is_opened(&self, ino: u64) -> bool {
+        let ret = self.handle_cacher.is_opened(ino);
+        match ret {
+            Ok(ret) => {
+                return ret;
+            }
+            Err(err) => {
+                error!("is_opened: failed to check the opens of ino: {}, err: {:?}", ino, err);
+                return true;
+            }
+        }
+    }
+
+    // get the handle of fh which must be opened on ino, returns EBADF if not.
+    fn get_fh_info(&self, ino: u64, fh: u64) -> Result<FileHandleInfo, c_int> {
+        let ret = self.handle_cacher.get_fh_info(fh);
+        match ret {
+            Ok(ret) => {
+                if ret.ino != ino {
+                    error!("get_fh_info: fh: {} belongs to ino: {}, not ino: {}", fh, ret.ino, ino);
+                    return Err(libc::EBADF);
+                }
+                return Ok(ret);
+            }
+            Err(err) => {
+                if !err.is_enoent() {
+                    error!("get_fh_info: failed to get handle of fh: {}, ino: {}, err: {:?}", fh, ino, err);
+                    return Err(libc::EIO);
+                }
+                return Err(libc::EBADF);
+            }
+        }
+    }
+
+    // the fuse open flags in the reply, O_DIRECT bypasses the page cache.
+    fn open_flags(flags: u32) -> u32 {
+        if flags as i32 & libc::O_DIRECT != 0 {
+            return consts::FOPEN_DIRECT_IO;
+        }
+        0
+    }
+
+    // get the leader of ino, ino is taken over by this machine if the lease of its leader is expired.
+    fn get_file_leader(&self, ino: u64) -> Result<FileLeader, Errno> {
+        let info: FileLeader;
+        let ret = self.meta_service_mgr.get_file_leader(ino);
+        match ret {
+            Ok(ret) => {
+                info = ret;
+            }
+            Err(err) => {
+                return Err(err);
+            }
+        }
+        if !info.expired || info.leader == self.meta_service_mgr.get_machine_id() {
+            return Ok(info);
+        }
+        warn!("get_file_leader: lease of leader: {} for ino: {} is expired, take it over", info.leader, ino);
+        let ret = self.meta_service_mgr.transfer_leader(ino, info.token);
+        match ret {
+            Ok(ret) => {
+                info!("get_file_leader: leader of ino: {} is {} now, token: {}", ino, ret.leader, ret.token);
+                return Ok(ret);
+            }
+            Err(err) => {
+                error!("get_file_leader: failed to take over ino: {} from leader: {}, err: {:?}", ino, info.leader, err);
+                return Err(err);
+            }
+        }
+    }
+
+    // called when the leader of the opened ino cannot be reached, the other errors of the leader are returned as they are.
+    // reopen all the opens of ino on its current leader, which is this machine if the lease of the
+    // unreachable one is expired. the new leader rebuilds the file from the segments in meta server.
+    fn failover(&self, ino: u64, leader: &String) -> Option<Arc<dyn Leader>> {
+        let info: FileLeader;
+        let ret = self.get_file_leader(ino);
+        match ret {
+            Ok(ret) => {
+                info = ret;
+            }
+            Err(err) => {
+                error!("failover: failed to get leader of ino: {}, err: {:?}", ino, err);
+                return None;
+            }
         }
+        if info.leader == *leader {
+            error!("failover: leader: {} of ino: {} isn't changed, its lease isn't expired yet", leader, ino);
+            return None;
+        }
+        let size: u64;
+        let ret = self.meta_service_mgr.read_file_attr(ino);
+        match ret {
+            Ok(ret) => {
+                size = ret.size;
+            }
+            Err(err) => {
+                error!("failover: failed to read attr of ino: {}, err: {:?}", ino, err);
+                return None;
+            }
+        }
+        let opens: i64;
+        let ret = self.handle_cacher.set_leader(ino, &info.leader);
+        match ret {
+            Ok(ret) => {
+                opens = ret;
+            }
+            Err(err) => {
+                error!("failover: failed to set leader: {} for ino: {}, err: {:?}", info.leader, ino, err);
+                return None;
+            }
+        }
+        // each open is closed on the leader by its release.
+        let leader_io = self.leader_mgr.get_leader(&info.leader);
+        for _ in 0..opens {
+            let ret = leader_io.open(ino, size, info.token);
+            if !ret.is_success() {
+                error!("failover: failed to open ino: {} on leader: {}, err: {:?}", ino, info.leader, ret);
+                return None;
+            }
+        }
+        warn!("failover: ino: {} is moved from leader: {} to {}, token: {}", ino, leader, info.leader, info.token);
+        Some(leader_io)
     }
+
+    // the follower reads the follower opens, the others are handled by their leaders.
+    fn get_leader_io(&self, handle: &FileHandleInfo) -> Arc<dyn Leader> {
+        if handle.follower {
+            if let Some(f) = self.leader_mgr.get_follower() {
+                return f;
+            }
+        }
+        self.leader_mgr.get_leader(&handle.leader)
+    }
+
+    // cut the blocks of ino beyond size through its leader.
+    // if the file isn't opened on this machine, open it temporarily when it shrinks.
+    fn truncate_blocks(&self, ino: u64, size: u64, old_size: u64) -> Errno {
+        let ret = self.handle_cacher.get_handle_info(ino);
+        if let Ok(info) = ret {
+            let leader = self.leader_mgr.get_leader(&info.leader);
+            return leader.truncate(ino, size);
+        }
+        if size >= old_size {
+            return Errno::Esucc;
+        }
+        let file_leader_info: FileLeader;
+        let ret = self.get_file_leader(ino);
+        match ret {
+            Ok(ret) => {
+                file_leader_info = ret;
+            }
+            Err(err) => {
+                error!("truncate_blocks: failed to get_file_leader for ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+        }
+        let leader = self.leader_mgr.get_leader(&file_leader_info.leader);
+        let ret = leader.open(ino, old_size, file_leader_info.token);
+        if !ret.is_success() {
+            error!("truncate_blocks: failed to open ino: {}, err: {:?}", ino, ret);
+            return ret;
+        }
+        let ret = leader.truncate(ino, size);
+        let err = leader.close(ino);
+        if !err.is_success() {
+            error!("truncate_blocks: failed to close ino: {}, err: {:?}", ino, err);
+        }
+        return ret;
+    }
+
+    // delete the unlinked ino and its orphan record.
+    fn reclaim_orphan(&mut self, ino: u64) {
+        self.orphans.remove(&ino);
+        let ret = self.meta_service_mgr.delete_file(ino);
+        if !ret.is_success() {
+            error!("reclaim_orphan: failed to delete ino: {}, err: {:?}", ino, ret);
+            return;
+        }
+        let ret = self.meta_service_mgr.remove_orphan(ino);
+        if let Err(err) = ret {
+            error!("reclaim_orphan: failed to remove orphan record of ino: {}, err: {:?}", ino, err);
+        }
+    }
+
+    // only user.* and trusted.* are supported, and trusted.* is only for the privileged user.
+    fn check_xattr_name(&self, req: &Request, name: &OsStr) -> Result<String, c_int> {
+        let attr_name: String;
+        if let Some(n) = name.to_str() {
+            attr_name = n.to_string();
+        } else {
+            return Err(libc::EINVAL);
+        }
+        if attr_name.is_empty() || attr_name.len() > XATTR_NAME_MAX {
+            return Err(libc::ERANGE);
+        }
+        if attr_name.starts_with(XATTR_USER_PREFIX) {
+            if attr_name.len() == XATTR_USER_PREFIX.len() {
+                return Err(libc::EINVAL);
+            }
+            return Ok(attr_name);
+        }
+        if attr_name.starts_with(XATTR_TRUSTED_PREFIX) {
+            if attr_name.len() == XATTR_TRUSTED_PREFIX.len() {
+                return Err(libc::EINVAL);
+            }
+            if req.uid() != 0 {
+                return Err(libc::EPERM);
+            }
+            return Ok(attr_name);
+        }
+        Err(libc::EOPNOTSUPP)
+    }
+
     fn to_usefs_attr(&self, attr: &metaservice_mgr::types::FileAttr) -> FileAttr {
         FileAttr{
             ino: attr.ino,
@@ -482,9 +1779,27 @@ impl Yigfs{
             metaservice_mgr::types::FileType::LINK => {
                 fuse::FileType::Symlink
             }
+            metaservice_mgr::types::FileType::FIFO => {
+                fuse::FileType::NamedPipe
+            }
+            metaservice_mgr::types::FileType::SOCKET => {
+                fuse::FileType::Socket
+            }
+            metaservice_mgr::types::FileType::CHAR => {
+                fuse::FileType::CharDevice
+            }
+            metaservice_mgr::types::FileType::BLOCK => {
+                fuse::FileType::BlockDevice
+            }
             _ => {
                 fuse::FileType::RegularFile
             }
         }
     }
-}
\ No newline at end of file
+}
+impl Drop for Yigfs {
+    // the kernel doesn't send destroy for the fuse mount on linux, the session just ends on unmount.
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -0,0 +1,168 @@
+use std::fs;
+use filesystem_mgr::perm::{Caller, MAY_READ, MAY_WRITE, MAY_EXEC, S_ISVTX};
+use metaservice_mgr::types::{FileAttr, FileType};
+
+const OWNER: u32 = 1000;
+const GROUP: u32 = 1000;
+const OTHER: u32 = 2000;
+// no such process, so the caller has no supplementary groups.
+const NO_PID: u32 = u32::MAX;
+
+fn new_attr(kind: FileType, perm: u16, uid: u32, gid: u32) -> FileAttr {
+    FileAttr{
+        ino: 2,
+        generation: 0,
+        size: 0,
+        blocks: 0,
+        atime: 0,
+        mtime: 0,
+        ctime: 0,
+        kind: kind,
+        perm: perm,
+        nlink: 1,
+        uid: uid,
+        gid: gid,
+        rdev: 0,
+        flags: 0,
+        parent: 1,
+    }
+}
+
+fn new_caller(uid: u32, gid: u32, pid: u32) -> Caller {
+    Caller{
+        uid: uid,
+        gid: gid,
+        pid: pid,
+    }
+}
+
+// the supplementary groups of this process.
+fn self_groups() -> Vec<u32> {
+    let mut groups = Vec::<u32>::new();
+    let status = fs::read_to_string("/proc/self/status").unwrap();
+    for line in status.lines() {
+        if !line.starts_with("Groups:") {
+            continue;
+        }
+        for g in line["Groups:".len()..].split_whitespace() {
+            groups.push(g.parse::<u32>().unwrap());
+        }
+    }
+    groups
+}
+
+#[test]
+fn test_can_access_owner() -> Result<(), String> {
+    let attr = new_attr(FileType::FILE, 0o640, OWNER, GROUP);
+    let caller = new_caller(OWNER, OTHER, NO_PID);
+    if !caller.can_access(&attr, MAY_READ | MAY_WRITE) {
+        return Err(format!("owner cannot read & write the file with perm: {:o}", attr.perm));
+    }
+    if caller.can_access(&attr, MAY_EXEC) {
+        return Err(format!("owner can exec the file with perm: {:o}", attr.perm));
+    }
+    // the owner bits are used even if the group or other bits grant more.
+    let attr = new_attr(FileType::FILE, 0o077, OWNER, GROUP);
+    let caller = new_caller(OWNER, GROUP, NO_PID);
+    if caller.can_access(&attr, MAY_READ) {
+        return Err(format!("owner can read the file with perm: {:o}", attr.perm));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_can_access_group() -> Result<(), String> {
+    let attr = new_attr(FileType::FILE, 0o750, OWNER, GROUP);
+    let caller = new_caller(OTHER, GROUP, NO_PID);
+    if !caller.can_access(&attr, MAY_READ | MAY_EXEC) {
+        return Err(format!("group member cannot read & exec the file with perm: {:o}", attr.perm));
+    }
+    if caller.can_access(&attr, MAY_WRITE) {
+        return Err(format!("group member can write the file with perm: {:o}", attr.perm));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_can_access_other() -> Result<(), String> {
+    let attr = new_attr(FileType::FILE, 0o774, OWNER, GROUP);
+    let caller = new_caller(OTHER, OTHER, NO_PID);
+    if !caller.can_access(&attr, MAY_READ) {
+        return Err(format!("other cannot read the file with perm: {:o}", attr.perm));
+    }
+    if caller.can_access(&attr, MAY_WRITE) || caller.can_access(&attr, MAY_EXEC) {
+        return Err(format!("other can write or exec the file with perm: {:o}", attr.perm));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_can_access_root() -> Result<(), String> {
+    let root = new_caller(0, 0, NO_PID);
+    let attr = new_attr(FileType::FILE, 0o000, OWNER, GROUP);
+    if !root.can_access(&attr, MAY_READ | MAY_WRITE) {
+        return Err(format!("root cannot read & write the file with perm: {:o}", attr.perm));
+    }
+    // root can exec the file only if any of the exec bits is set.
+    if root.can_access(&attr, MAY_EXEC) {
+        return Err(format!("root can exec the file with perm: {:o}", attr.perm));
+    }
+    let attr = new_attr(FileType::FILE, 0o001, OWNER, GROUP);
+    if !root.can_access(&attr, MAY_EXEC) {
+        return Err(format!("root cannot exec the file with perm: {:o}", attr.perm));
+    }
+    // but root can always search the dir.
+    let attr = new_attr(FileType::DIR, 0o000, OWNER, GROUP);
+    if !root.can_access(&attr, MAY_EXEC) {
+        return Err(format!("root cannot search the dir with perm: {:o}", attr.perm));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_can_delete() -> Result<(), String> {
+    let file = new_attr(FileType::FILE, 0o644, OWNER, GROUP);
+    let other = new_caller(OTHER, OTHER, NO_PID);
+    // no sticky bit, the permission of dir is checked by the caller.
+    let dir = new_attr(FileType::DIR, 0o777, 0, 0);
+    if !other.can_delete(&dir, &file) {
+        return Err(format!("other cannot delete the file in dir with perm: {:o}", dir.perm));
+    }
+    let dir = new_attr(FileType::DIR, 0o777 | S_ISVTX, 0, 0);
+    if other.can_delete(&dir, &file) {
+        return Err(format!("other can delete the file in sticky dir with perm: {:o}", dir.perm));
+    }
+    let owner = new_caller(OWNER, OTHER, NO_PID);
+    if !owner.can_delete(&dir, &file) {
+        return Err(format!("the owner of file cannot delete it in sticky dir with perm: {:o}", dir.perm));
+    }
+    let dir = new_attr(FileType::DIR, 0o777 | S_ISVTX, OTHER, OTHER);
+    if !other.can_delete(&dir, &file) {
+        return Err(format!("the owner of dir cannot delete the file in it with perm: {:o}", dir.perm));
+    }
+    let root = new_caller(0, 0, NO_PID);
+    if !root.can_delete(&dir, &file) {
+        return Err(format!("root cannot delete the file in sticky dir with perm: {:o}", dir.perm));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_in_group() -> Result<(), String> {
+    let caller = new_caller(OWNER, GROUP, NO_PID);
+    if !caller.in_group(GROUP) {
+        return Err(format!("caller is not in its primary group: {}", GROUP));
+    }
+    if caller.in_group(OTHER) {
+        return Err(format!("caller without supplementary groups is in group: {}", OTHER));
+    }
+    // the supplementary groups are read from the process of the caller.
+    let groups = self_groups();
+    let caller = new_caller(OWNER, u32::MAX, std::process::id());
+    for g in &groups {
+        if !caller.in_group(*g) {
+            return Err(format!("caller is not in its supplementary group: {}, groups: {:?}", g, groups));
+        }
+    }
+    Ok(())
+}
@@ -90,6 +90,22 @@ impl<T: Clone> IntervalTree<T>{
             x = n.borrow().get_rchild().clone();
         }
 
+        if !v.is_empty() {
+            return v;
+        }
+        // start is in a hole, collect the intervals which start in (start, end).
+        let mut curr = self.ceiling(start);
+        loop {
+            if curr.as_ref().unwrap().borrow().is_nil() {
+                break;
+            }
+            if curr.as_ref().unwrap().borrow().get_intr().start >= end {
+                break;
+            }
+            v.push(curr.as_ref().unwrap().clone());
+            curr = self.successor(curr.as_ref().unwrap());
+        }
+
         return v;
     }
 
@@ -559,6 +575,22 @@ impl<T: Clone> IntervalTree<T>{
         x.borrow_mut().set_black();
     }
 
+    // get the node with the smallest key which is larger than key.
+    fn ceiling(&self, key: u64) -> Option<Rc<RefCell<TNode<T>>>> {
+        let mut x = self.root.clone();
+        let mut c: Option<Rc<RefCell<TNode<T>>>> = Some(self.nil.clone());
+        while x.as_ref().unwrap().borrow().is_not_nil() {
+            let n = x.as_ref().unwrap().clone();
+            if n.borrow().get_key() > key {
+                c = x.clone();
+                x = n.borrow().get_lchild().clone();
+                continue;
+            }
+            x = n.borrow().get_rchild().clone();
+        }
+        return c;
+    }
+
     fn successor(&self, n: &Rc<RefCell<TNode<T>>>) -> Option<Rc<RefCell<TNode<T>>>> {
         if n.borrow().get_rchild().as_ref().unwrap().borrow().is_not_nil() {
             return self.tree_node_minum(&n.borrow().get_rchild());
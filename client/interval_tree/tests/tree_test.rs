@@ -341,4 +341,42 @@ fn test_interval_tree_basic_get_traverse()->Result<(), String>{
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_interval_tree_get_from_hole()->Result<(), String>{
+    let mut tree = IntervalTree::new(Interval::new(0,0));
+    // [0, 10), [20, 30), [40, 50)...
+    let mut start = 0;
+    loop {
+        tree.insert_node(start, start + 10, Interval::new(start, start + 10));
+        start += 20;
+        if start >= 1000 {
+            break;
+        }
+    }
+
+    // the start in the hole, [10, 45) overlaps [20, 30) and [40, 50)
+    let nodes = tree.get(10, 45);
+    if nodes.len() != 2 {
+        return Err(format!("got {} intervals for [10, 45), intervals: {:?}", nodes.len(), nodes));
+    }
+    let intr = nodes[0].borrow().get_intr();
+    if intr.start != 20 || intr.end != 30 {
+        return Err(format!("got invalid first interval: {:?} for [10, 45)", intr));
+    }
+    let intr = nodes[1].borrow().get_intr();
+    if intr.start != 40 || intr.end != 50 {
+        return Err(format!("got invalid second interval: {:?} for [10, 45)", intr));
+    }
+    // no overlap at all.
+    let nodes = tree.get(10, 20);
+    if !nodes.is_empty() {
+        return Err(format!("got intervals for the hole [10, 20): {:?}", nodes));
+    }
+    // beyond the last interval.
+    let nodes = tree.get(1000, 2000);
+    if !nodes.is_empty() {
+        return Err(format!("got intervals beyond the last one: {:?}", nodes));
+    }
+    return Ok(());
+}
@@ -34,6 +34,8 @@ pub trait CacheStore: Send + Sync{
     fn read_async(&self, id0: u64, id1: u64, dir: &String, offset: u64, size: u32, read_resp: Sender<MsgFileOpResp>) -> Errno;
     fn stat(&self, id0: u64, id1: u64, dir: &String)-> Result<CacheStatResult, Errno>;
     fn close(&self, id0: u64, id1: u64) -> Errno;
+    // flush the segment file to disk, data_only works like fdatasync.
+    fn sync(&self, id0: u64, id1: u64, data_only: bool) -> Errno;
 }
 
 pub struct CacheStoreConfig{
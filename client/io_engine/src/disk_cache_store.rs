@@ -1,5 +1,5 @@
 
-use crate::{cache_store::CacheStatResult, io_thread_pool::IoThreadPool, types::{MsgFileStatOp, MsgFileStatResult}};
+use crate::{cache_store::CacheStatResult, io_thread_pool::IoThreadPool, types::{MsgFileStatOp, MsgFileStatResult, MsgFileSyncOp}};
 use crate::types::{MsgFileOp, MsgFileOpenOp, MsgFileWriteOp, MsgFileReadOp, MsgFileCloseOp, MsgFileOpResp};
 use crate::cache_store::{CacheStore, CacheStoreFactory, CacheStoreConfig, CacheWriteResult};
 use crate::disk_io_worker::DiskIoWorkerFactory;
@@ -262,6 +262,38 @@ impl CacheStore for DiskCache {
         }
         return Errno::Esucc;
     }
+
+    fn sync(&self, id0: u64, id1: u64, data_only: bool) -> Errno{
+        let worker = self.disk_pool.get_thread(id0, id1);
+        let (tx, rx) = bounded::<Errno>(1);
+        let msg = MsgFileSyncOp{
+            id0,
+            id1,
+            data_only,
+            resp_sender: tx,
+        };
+        let ret = worker.do_io(MsgFileOp::OpSync(msg));
+        if !ret.is_success(){
+            error!("disk_cache_store: sync: failed to send sync op for seg: id0: {}, id1: {}, err: {:?}",
+            id0, id1, ret);
+            return ret;
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                if !ret.is_success() {
+                    error!("disk_cache_store: sync: failed to sync seg: id0: {}, id1: {}, err: {:?}",
+                    id0, id1, ret);
+                }
+                ret
+            }
+            Err(err) => {
+                error!("disk_cache_store: sync: failed to recv sync result for seg: id0: {}, id1: {}, err: {}",
+                id0, id1, err);
+                Errno::Eintr
+            }
+        }
+    }
 }
 
 impl Drop for DiskCache {
@@ -1,6 +1,6 @@
 extern crate crossbeam_channel;
 
-use crate::{io_worker::{IoWorker, IoWorkerFactory}, types::{MsgFileStatOp, MsgFileStatResult}};
+use crate::{io_worker::{IoWorker, IoWorkerFactory}, types::{MsgFileStatOp, MsgFileStatResult, MsgFileSyncOp}};
 use std::{collections::HashMap, io::{Read, Seek, Write}};
 use std::io::SeekFrom;
 use std::fs::{File, OpenOptions};
@@ -79,6 +79,9 @@ impl DiskIoWorker {
             MsgFileOp::OpStat(msg) => {
                 self.do_stat(msg);
             }
+            MsgFileOp::OpSync(msg) => {
+                self.do_sync(msg);
+            }
         }
     }
 
@@ -377,6 +380,29 @@ impl DiskIoWorker {
         }
     }
 
+    fn do_sync(&mut self, msg: &MsgFileSyncOp){
+        let id = NumberOp::to_u128(msg.id0, msg.id1);
+        if let Some(f) = self.handles.get_mut(&id) {
+            let ret = if msg.data_only {
+                f.file.sync_data()
+            } else {
+                f.file.sync_all()
+            };
+            match ret {
+                Ok(_) => {
+                    msg.response(Errno::Esucc);
+                }
+                Err(err) => {
+                    error!("do_sync: failed to flush File(id0: {}, id1: {}), err: {}", msg.id0, msg.id1, err);
+                    msg.response(Errno::Eintr);
+                }
+            }
+            return;
+        }
+        // the seg is not opened, it is already flushed when it was closed.
+        msg.response(Errno::Esucc);
+    }
+
     fn do_stat(&mut self, msg: &MsgFileStatOp){
         let mut result = MsgFileStatResult{
             id0: msg.id0,
@@ -50,6 +50,28 @@ pub struct MsgFileCloseOp{
     pub id1: u64,
 }
 
+#[derive(Debug)]
+pub struct MsgFileSyncOp{
+    pub id0: u64,
+    pub id1: u64,
+    // only flush the data, like fdatasync.
+    pub data_only: bool,
+    pub resp_sender: Sender<Errno>,
+}
+
+impl MsgFileSyncOp{
+    pub fn response(&self, err: Errno){
+        let ret = self.resp_sender.send(err);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("failed to send response for sync(id0: {}, id1: {}), err: {}",
+                self.id0, self.id1, err);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct MsgFileWriteResp {
     pub id0: u64,
@@ -153,6 +175,7 @@ pub enum MsgFileOp {
     OpRead(MsgFileReadOp),
     OpClose(MsgFileCloseOp),
     OpStat(MsgFileStatOp),
+    OpSync(MsgFileSyncOp),
     //OpDel(MsgFileDelOp),
 }
 
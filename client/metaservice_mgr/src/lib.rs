@@ -3,6 +3,7 @@ pub mod mgr;
 pub mod mgr_impl;
 pub mod meta_store;
 pub mod meta_op;
+pub mod meta_cache;
 mod meta_thread;
 mod meta_worker;
 mod meta_thread_pool;
@@ -17,7 +18,10 @@ pub fn new_metaserver_mgr(cfg: &Config, exec: &Executor) -> Result<Arc<dyn mgr::
     let ret = mgr_impl::MetaServiceMgrImpl::new(cfg, exec);
     match ret {
         Ok(ret) => {
-            return Ok(Arc::new(ret));
+            if !cfg.meta_cache_config.is_enabled() {
+                return Ok(Arc::new(ret));
+            }
+            return Ok(Arc::new(meta_cache::CachedMetaServiceMgr::new(Arc::new(ret), &cfg.meta_cache_config)));
         }
         Err(error) => {
             return Err(format!("failed to new MetaServiceMgrImpl, err: {}", error));
@@ -1,7 +1,8 @@
 extern crate serde;
 
 use serde::{Serialize, Deserialize};
-use crate::types::{Segment, Block};
+use crate::types::Segment;
+use common::error::Errno;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ReqReadDir {
@@ -11,6 +12,37 @@ pub struct ReqReadDir {
     pub offset: i64,
 }
 
+// err_code values returned by the meta server in RespResult.
+pub const ERR_YIGFS_INVALID_PARAMS: i64 = 40001;
+pub const ERR_YIGFS_NO_SUCH_FILE: i64 = 40002;
+pub const ERR_YIGFS_NOT_FIND_TARGET_DIR_FILES: i64 = 40003;
+pub const ERR_YIGFS_MISSING_REQUIRED_PARAMS: i64 = 40004;
+pub const ERR_YIGFS_INVALID_TYPE: i64 = 40006;
+pub const ERR_YIGFS_FILE_ALREADY_EXIST: i64 = 40011;
+pub const ERR_YIGFS_MACHINE_NOT_MATCH_FILE_LEADER: i64 = 40015;
+pub const ERR_YIGFS_FILE_ALREADY_RENAMED: i64 = 40020;
+pub const ERR_YIGFS_DIR_NOT_EMPTY: i64 = 40021;
+pub const ERR_YIGFS_NOT_DIR: i64 = 40022;
+pub const ERR_YIGFS_NO_SUCH_XATTR: i64 = 40023;
+pub const ERR_YIGFS_XATTR_ALREADY_EXIST: i64 = 40024;
+
+// convert the err_code of the meta server to Errno, the unknown ones are internal errors.
+pub fn to_errno(err_code: i64) -> Errno {
+    match err_code {
+        0 => Errno::Esucc,
+        ERR_YIGFS_INVALID_PARAMS | ERR_YIGFS_MISSING_REQUIRED_PARAMS | ERR_YIGFS_INVALID_TYPE => Errno::Einval,
+        ERR_YIGFS_NO_SUCH_FILE | ERR_YIGFS_NOT_FIND_TARGET_DIR_FILES => Errno::Enoent,
+        // the source is renamed by others.
+        ERR_YIGFS_FILE_ALREADY_RENAMED => Errno::Enoent,
+        ERR_YIGFS_FILE_ALREADY_EXIST | ERR_YIGFS_XATTR_ALREADY_EXIST => Errno::Eexists,
+        ERR_YIGFS_MACHINE_NOT_MATCH_FILE_LEADER => Errno::Eperm,
+        ERR_YIGFS_DIR_NOT_EMPTY => Errno::Enotempty,
+        ERR_YIGFS_NOT_DIR => Errno::Enotdir,
+        ERR_YIGFS_NO_SUCH_XATTR => Errno::Enodata,
+        _ => Errno::Eintr,
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct RespResult {
     pub err_code: i64,
@@ -76,6 +108,8 @@ pub struct MsgFileAttr {
     /// Flags (macOS only, see chflags(2))
     #[serde(default)]
     pub flags: u32,
+    #[serde(rename(serialize = "parent_ino", deserialize = "parent_ino"), default)]
+    pub parent: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -127,6 +161,32 @@ pub struct ReqFileLeader {
 pub struct MsgLeaderInfo {
     pub zone: String,
     pub leader: String,
+    #[serde(default)]
+    pub token: u64,
+    #[serde(default)]
+    pub expired: bool,
+}
+
+// take over the leadership of ino, it is granted only if the lease of the leader holding token is expired.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqTransferLeader {
+    pub region: String,
+    pub bucket: String,
+    pub zone: String,
+    pub machine: String,
+    pub ino: u64,
+    pub token: u64,
+}
+
+// move the leadership of ino to machine, it is granted only if token is held by the current leader.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqMigrateLeader {
+    pub region: String,
+    pub bucket: String,
+    pub zone: String,
+    pub machine: String,
+    pub ino: u64,
+    pub token: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -150,6 +210,8 @@ pub struct ReqFileCreate {
     pub perm: u32,
     #[serde(rename(serialize = "type", deserialize = "type"))]
     pub file_type: u8,
+    // device number of the char & block device, 0 for the others.
+    pub rdev: u32,
 }
 
 
@@ -242,6 +304,8 @@ pub struct ReqUpdateSegments{
     pub generation: u64,
     pub segments: Vec<Segment>,
     pub remove_segments: Vec<Segment>,
+    // the update is rejected if the leadership of ino is taken over with a newer token.
+    pub leader_token: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -294,6 +358,8 @@ pub struct ReqHeartbeat{
     pub bucket: String,
     pub zone: String,
     pub machine: String,
+    // the leaderships of this machine are renewed by the heartbeat and last for lease_timeout seconds.
+    pub lease_timeout: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -314,6 +380,9 @@ pub struct RespHeartbeat {
     pub result: RespResult,
     pub upload_segments: Vec<MsgUploadSegment>,
     pub remove_segments: Vec<MsgRemoveSegment>,
+    // the inos changed by the other machines since the last heartbeat.
+    #[serde(default)]
+    pub invalid_inos: Vec<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -347,4 +416,200 @@ pub struct ReqFileRename{
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct RespFileRename{
     pub result: RespResult,
-}
\ No newline at end of file
+    // attr of the replaced target after its dir entry is removed, nlink is decreased.
+    #[serde(default)]
+    pub replaced: Option<MsgFileAttr>,
+}
+
+// the meta server checks the dir is empty and removes it in one transaction.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqDeleteDir {
+    pub region: String,
+    pub bucket: String,
+    pub zone: String,
+    pub machine: String,
+    #[serde(rename(serialize = "parent_ino", deserialize = "parent_ino"))]
+    pub parent: u64,
+    #[serde(rename(serialize = "file_name", deserialize = "file_name"))]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespDeleteDir {
+    pub result: RespResult,
+    // the attr of the parent dir after its nlink & ctime are updated.
+    #[serde(rename(serialize = "file", deserialize = "file"))]
+    pub parent_attr: MsgFileAttr,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqCreateSymlink {
+    pub zone: String,
+    pub machine: String,
+    pub region: String,
+    pub bucket: String,
+    #[serde(rename(serialize = "parent_ino", deserialize = "parent_ino"))]
+    pub parent: u64,
+    #[serde(rename(serialize = "file_name", deserialize = "file_name"))]
+    pub name: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub perm: u32,
+    #[serde(rename(serialize = "type", deserialize = "type"))]
+    pub file_type: u8,
+    // the path which the symlink points to.
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespCreateSymlink {
+    pub result: RespResult,
+    #[serde(rename(serialize = "file", deserialize = "file"))]
+    pub attr: MsgFileAttr,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqReadSymlink {
+    pub region: String,
+    pub bucket: String,
+    pub ino: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespReadSymlink {
+    pub result: RespResult,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqFsUsage {
+    pub region: String,
+    pub bucket: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespFsUsage {
+    pub result: RespResult,
+    pub used_size: u64,
+    pub file_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqSetXattr {
+    pub region: String,
+    pub bucket: String,
+    pub ino: u64,
+    pub name: String,
+    #[serde(with = "base64_bytes")]
+    pub value: Vec<u8>,
+    // XATTR_CREATE or XATTR_REPLACE, 0 means create or replace.
+    pub flags: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespSetXattr {
+    pub result: RespResult,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqGetXattr {
+    pub region: String,
+    pub bucket: String,
+    pub ino: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespGetXattr {
+    pub result: RespResult,
+    #[serde(with = "base64_bytes", default)]
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqListXattr {
+    pub region: String,
+    pub bucket: String,
+    pub ino: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespListXattr {
+    pub result: RespResult,
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqRemoveXattr {
+    pub region: String,
+    pub bucket: String,
+    pub ino: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespRemoveXattr {
+    pub result: RespResult,
+}
+
+// detach the name under parent_ino from ino, and record ino as the orphan of machine.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqAddOrphan {
+    pub region: String,
+    pub bucket: String,
+    pub zone: String,
+    pub machine: String,
+    pub parent_ino: u64,
+    pub file_name: String,
+    pub ino: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqOrphan {
+    pub region: String,
+    pub bucket: String,
+    pub zone: String,
+    pub machine: String,
+    pub ino: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespOrphan {
+    pub result: RespResult,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqListOrphans {
+    pub region: String,
+    pub bucket: String,
+    pub zone: String,
+    pub machine: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespListOrphans {
+    pub result: RespResult,
+    pub inos: Vec<u64>,
+}
+
+// the []byte of the meta server is encoded in base64 in json, and the empty one may be null.
+mod base64_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let ret = Option::<String>::deserialize(deserializer)?;
+        match ret {
+            Some(s) => {
+                base64::decode(&s).map_err(D::Error::custom)
+            }
+            None => {
+                Ok(Vec::new())
+            }
+        }
+    }
+}
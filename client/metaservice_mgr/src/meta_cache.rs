@@ -0,0 +1,709 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use common::config::MetaCacheConfig;
+use common::error::Errno;
+use common::thread::Thread;
+use crossbeam_channel::{Receiver, Sender, bounded, select, unbounded};
+use crate::mgr::MetaServiceMgr;
+use crate::types::{DirEntry, FileLeader, NewFileInfo, SetFileAttr,
+    Segment, HeartbeatResult, FsUsage, FileAttr};
+use log::{info, warn, error};
+
+// the result of looking up a name in the cache.
+enum CachedLookup {
+    // not cached or expired, with the epoch to put the entry fetched from meta server.
+    Miss(u64),
+    // the name doesn't exist.
+    Negative,
+    Found(FileAttr),
+}
+
+// the items fetched from meta server are put with the epoch got before the fetch,
+// they are dropped if the inos are invalidated after that epoch, so that a slow fetch
+// can't bring back the stale meta which has been invalidated.
+enum MetaCacheOp {
+    // the cached attr, and the epoch to put the one fetched from meta server if it is missed.
+    GetAttr(u64, Sender<(Option<FileAttr>, u64)>),
+    // attr, epoch
+    PutAttr(FileAttr, u64),
+    // parent, name
+    Lookup(u64, String, Sender<CachedLookup>),
+    // parent, name, ino, epoch. None for the name which doesn't exist.
+    PutEntry(u64, String, Option<u64>, u64),
+    // ino, offset
+    GetDir(u64, i64, Sender<(Option<Vec<DirEntry>>, u64)>),
+    // ino, offset, entries, epoch
+    PutDir(u64, i64, Vec<DirEntry>, u64),
+    GetEpoch(Sender<u64>),
+    // drop the attr of ino, and the entries & listings under it if it is a dir.
+    InvalidateIno(u64),
+    // drop the entry and the ino it points to, and the attr & listings of the parent.
+    InvalidateEntry(u64, String),
+    Clear,
+}
+
+// caching decorator of MetaServiceMgr, it keeps the attrs, the dir entries and the dir listings for a while,
+// so that lookup, getattr and readdir don't need the round trip to meta server every time.
+// the local changes drop the cached items, the changes of other machines are dropped by
+// the invalid inos in heartbeat or by invalidate_inos.
+pub struct CachedMetaServiceMgr {
+    mgr: Arc<dyn MetaServiceMgr>,
+    op_tx: Sender<MetaCacheOp>,
+    stop_tx: Sender<u8>,
+    thr: Thread,
+}
+
+impl MetaServiceMgr for CachedMetaServiceMgr {
+    fn mount(&self, uid: u32, gid: u32) -> Result<(), Errno> {
+        let ret = self.mgr.mount(uid, gid);
+        self.send_op(MetaCacheOp::Clear);
+        return ret;
+    }
+
+    fn read_dir(&self, ino: u64, offset: i64)->Result<Vec<DirEntry>, Errno> {
+        let mut epoch: u64 = 0;
+        let (tx, rx) = bounded::<(Option<Vec<DirEntry>>, u64)>(1);
+        self.send_op(MetaCacheOp::GetDir(ino, offset, tx));
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                if let Some(entries) = ret.0 {
+                    return Ok(entries);
+                }
+                epoch = ret.1;
+            }
+            Err(err) => {
+                error!("read_dir: failed to recv cached entries for ino: {}, offset: {}, err: {}", ino, offset, err);
+            }
+        }
+        let entries: Vec<DirEntry>;
+        let ret = self.mgr.read_dir(ino, offset);
+        match ret {
+            Ok(ret) => {
+                entries = ret;
+            }
+            Err(err) => {
+                return Err(err);
+            }
+        }
+        let cached: Vec<DirEntry> = entries.iter().map(|e| e.copy()).collect();
+        self.send_op(MetaCacheOp::PutDir(ino, offset, cached, epoch));
+        return Ok(entries);
+    }
+
+    fn read_dir_file_attr(&self, ino: u64, name: &String) -> Result<FileAttr, Errno> {
+        let mut epoch: u64 = 0;
+        let (tx, rx) = bounded::<CachedLookup>(1);
+        self.send_op(MetaCacheOp::Lookup(ino, name.clone(), tx));
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                match ret {
+                    CachedLookup::Found(attr) => {
+                        return Ok(attr);
+                    }
+                    CachedLookup::Negative => {
+                        return Err(Errno::Enoent);
+                    }
+                    CachedLookup::Miss(e) => {
+                        epoch = e;
+                    }
+                }
+            }
+            Err(err) => {
+                error!("read_dir_file_attr: failed to recv cached entry for parent: {}, name: {}, err: {}", ino, name, err);
+            }
+        }
+        let ret = self.mgr.read_dir_file_attr(ino, name);
+        match ret {
+            Ok(ret) => {
+                self.send_op(MetaCacheOp::PutAttr(ret.copy(), epoch));
+                self.send_op(MetaCacheOp::PutEntry(ino, name.clone(), Some(ret.ino), epoch));
+                return Ok(ret);
+            }
+            Err(err) => {
+                if err.is_enoent() {
+                    self.send_op(MetaCacheOp::PutEntry(ino, name.clone(), None, epoch));
+                }
+                return Err(err);
+            }
+        }
+    }
+
+    fn read_file_attr(&self, ino: u64) -> Result<FileAttr, Errno> {
+        let mut epoch: u64 = 0;
+        let (tx, rx) = bounded::<(Option<FileAttr>, u64)>(1);
+        self.send_op(MetaCacheOp::GetAttr(ino, tx));
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                if let Some(attr) = ret.0 {
+                    return Ok(attr);
+                }
+                epoch = ret.1;
+            }
+            Err(err) => {
+                error!("read_file_attr: failed to recv cached attr for ino: {}, err: {}", ino, err);
+            }
+        }
+        let ret = self.mgr.read_file_attr(ino);
+        match ret {
+            Ok(ret) => {
+                self.send_op(MetaCacheOp::PutAttr(ret.copy(), epoch));
+                return Ok(ret);
+            }
+            Err(err) => {
+                return Err(err);
+            }
+        }
+    }
+
+    fn set_file_attr(&self, attr: &SetFileAttr) -> Result<FileAttr, Errno> {
+        let epoch = self.get_epoch();
+        let ret = self.mgr.set_file_attr(attr);
+        match ret {
+            Ok(ret) => {
+                self.send_op(MetaCacheOp::PutAttr(ret.copy(), epoch));
+                return Ok(ret);
+            }
+            Err(err) => {
+                self.send_op(MetaCacheOp::InvalidateIno(attr.ino));
+                return Err(err);
+            }
+        }
+    }
+
+    fn new_ino_leader(&self, parent: u64, name: &String, uid: u32, gid: u32, perm: u32, file_type: u8, rdev: u32) -> Result<NewFileInfo, Errno> {
+        let ret = self.mgr.new_ino_leader(parent, name, uid, gid, perm, file_type, rdev);
+        // the entry is put by the next lookup, the epoch got before this change is older than
+        // the invalidation, so the result can't be put here.
+        self.send_op(MetaCacheOp::InvalidateEntry(parent, name.clone()));
+        return ret;
+    }
+
+    fn get_file_leader(&self, ino: u64) -> Result<FileLeader, Errno> {
+        self.mgr.get_file_leader(ino)
+    }
+
+    fn transfer_leader(&self, ino: u64, token: u64) -> Result<FileLeader, Errno> {
+        self.mgr.transfer_leader(ino, token)
+    }
+
+    fn migrate_leader(&self, ino: u64, machine: &String, token: u64) -> Result<FileLeader, Errno> {
+        self.mgr.migrate_leader(ino, machine, token)
+    }
+
+    fn get_file_segments(&self, ino: u64, offset: Option<u64>, size: Option<i64>) -> Result<Vec<Segment>, Errno> {
+        self.mgr.get_file_segments(ino, offset, size)
+    }
+
+    fn get_machine_id(&self) -> String {
+        self.mgr.get_machine_id()
+    }
+
+    fn add_file_block(&self, ino: u64, seg: &Segment) -> Errno {
+        let ret = self.mgr.add_file_block(ino, seg);
+        // the size of the file may be changed.
+        self.send_op(MetaCacheOp::InvalidateIno(ino));
+        return ret;
+    }
+
+    fn update_file_segments(&self, ino: u64, token: u64, segs: &Vec<Segment>, removed_segs: &Vec<Segment>) -> Errno {
+        let ret = self.mgr.update_file_segments(ino, token, segs, removed_segs);
+        self.send_op(MetaCacheOp::InvalidateIno(ino));
+        return ret;
+    }
+
+    fn upload_segment(&self, id0: u64, id1: u64, next_offset: u64) -> Errno {
+        self.mgr.upload_segment(id0, id1, next_offset)
+    }
+
+    fn heartbeat(&self)-> Result<HeartbeatResult, Errno> {
+        let ret = self.mgr.heartbeat();
+        match ret {
+            Ok(ret) => {
+                self.invalidate_inos(&ret.invalid_inos);
+                return Ok(ret);
+            }
+            Err(err) => {
+                return Err(err);
+            }
+        }
+    }
+
+    fn delete_file(&self, ino: u64) -> Errno {
+        let ret = self.mgr.delete_file(ino);
+        self.send_op(MetaCacheOp::InvalidateIno(ino));
+        return ret;
+    }
+
+    fn rename(&self, parent: u64, name: &String, new_parent: u64, new_name: &String) -> Result<Option<FileAttr>, Errno> {
+        let ret = self.mgr.rename(parent, name, new_parent, new_name);
+        self.send_op(MetaCacheOp::InvalidateEntry(parent, name.clone()));
+        self.send_op(MetaCacheOp::InvalidateEntry(new_parent, new_name.clone()));
+        if let Ok(Some(ref replaced)) = ret {
+            self.send_op(MetaCacheOp::InvalidateIno(replaced.ino));
+        }
+        return ret;
+    }
+
+    fn delete_dir(&self, parent: u64, name: &String) -> Result<FileAttr, Errno> {
+        let ret = self.mgr.delete_dir(parent, name);
+        // the entry is put by the next lookup, the epoch got before this change is older than
+        // the invalidation, so the result can't be put here.
+        self.send_op(MetaCacheOp::InvalidateEntry(parent, name.clone()));
+        return ret;
+    }
+
+    fn create_symlink(&self, parent: u64, name: &String, uid: u32, gid: u32, target: &String) -> Result<FileAttr, Errno> {
+        let ret = self.mgr.create_symlink(parent, name, uid, gid, target);
+        // the entry is put by the next lookup, the epoch got before this change is older than
+        // the invalidation, so the result can't be put here.
+        self.send_op(MetaCacheOp::InvalidateEntry(parent, name.clone()));
+        return ret;
+    }
+
+    fn read_symlink(&self, ino: u64) -> Result<String, Errno> {
+        self.mgr.read_symlink(ino)
+    }
+
+    fn unlink_file(&self, parent: u64, name: &String, ino: u64) -> Errno {
+        let ret = self.mgr.unlink_file(parent, name, ino);
+        self.send_op(MetaCacheOp::InvalidateEntry(parent, name.clone()));
+        self.send_op(MetaCacheOp::InvalidateIno(ino));
+        return ret;
+    }
+
+    fn get_fs_usage(&self) -> Result<FsUsage, Errno> {
+        self.mgr.get_fs_usage()
+    }
+
+    fn set_xattr(&self, ino: u64, name: &String, value: &[u8], flags: i32) -> Result<(), Errno> {
+        let ret = self.mgr.set_xattr(ino, name, value, flags);
+        // the ctime is changed.
+        self.send_op(MetaCacheOp::InvalidateIno(ino));
+        return ret;
+    }
+
+    fn get_xattr(&self, ino: u64, name: &String) -> Result<Vec<u8>, Errno> {
+        self.mgr.get_xattr(ino, name)
+    }
+
+    fn list_xattr(&self, ino: u64) -> Result<Vec<String>, Errno> {
+        self.mgr.list_xattr(ino)
+    }
+
+    fn remove_xattr(&self, ino: u64, name: &String) -> Result<(), Errno> {
+        let ret = self.mgr.remove_xattr(ino, name);
+        self.send_op(MetaCacheOp::InvalidateIno(ino));
+        return ret;
+    }
+
+    fn add_orphan(&self, parent: u64, name: &String, ino: u64) -> Result<(), Errno> {
+        let ret = self.mgr.add_orphan(parent, name, ino);
+        self.send_op(MetaCacheOp::InvalidateEntry(parent, name.clone()));
+        self.send_op(MetaCacheOp::InvalidateIno(ino));
+        return ret;
+    }
+
+    fn remove_orphan(&self, ino: u64) -> Result<(), Errno> {
+        self.mgr.remove_orphan(ino)
+    }
+
+    fn list_orphans(&self) -> Result<Vec<u64>, Errno> {
+        self.mgr.list_orphans()
+    }
+}
+
+impl CachedMetaServiceMgr {
+    pub fn new(mgr: Arc<dyn MetaServiceMgr>, cfg: &MetaCacheConfig) -> Self {
+        let (op_tx, op_rx) = unbounded::<MetaCacheOp>();
+        let (stop_tx, stop_rx) = bounded::<u8>(1);
+        let mut cache = MetaCache::new(cfg, op_rx, stop_rx);
+        let mut mgr = CachedMetaServiceMgr{
+            mgr: mgr,
+            op_tx: op_tx,
+            stop_tx: stop_tx,
+            thr: Thread::create(&String::from("meta_cache")),
+        };
+        mgr.thr.run(move || {
+            cache.start();
+        });
+        mgr
+    }
+
+    // drop the cached meta of the inos changed by the other machines, such as the ones pushed by meta server.
+    pub fn invalidate_inos(&self, inos: &Vec<u64>) {
+        for ino in inos {
+            self.send_op(MetaCacheOp::InvalidateIno(*ino));
+        }
+    }
+
+    // the epoch to put the items fetched from meta server after now, 0 is older than any epoch.
+    fn get_epoch(&self) -> u64 {
+        let (tx, rx) = bounded::<u64>(1);
+        self.send_op(MetaCacheOp::GetEpoch(tx));
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return ret;
+            }
+            Err(err) => {
+                error!("get_epoch: failed to recv epoch, err: {}", err);
+                return 0;
+            }
+        }
+    }
+
+    fn send_op(&self, op: MetaCacheOp) {
+        let ret = self.op_tx.send(op);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("CachedMetaServiceMgr: failed to send cache op, err: {}", err);
+            }
+        }
+    }
+}
+
+impl Drop for CachedMetaServiceMgr {
+    fn drop(&mut self) {
+        let ret = self.stop_tx.send(1);
+        match ret {
+            Ok(_) => {
+                self.thr.join();
+            }
+            Err(err) => {
+                error!("CachedMetaServiceMgr: failed to perform stop, err: {}", err);
+            }
+        }
+    }
+}
+
+struct CachedEntry {
+    ino: Option<u64>,
+    expire: Instant,
+}
+
+struct CachedAttr {
+    attr: FileAttr,
+    expire: Instant,
+}
+
+struct CachedDir {
+    entries: Vec<DirEntry>,
+    expire: Instant,
+}
+
+struct MetaCache {
+    attr_ttl: Duration,
+    entry_ttl: Duration,
+    negative_ttl: Duration,
+    dir_ttl: Duration,
+    capacity: usize,
+    attrs: HashMap<u64, CachedAttr>,
+    // parent -> name -> entry
+    entries: HashMap<u64, HashMap<String, CachedEntry>>,
+    // ino -> offset -> listing
+    dirs: HashMap<u64, HashMap<i64, CachedDir>>,
+    // increased by each invalidation.
+    epoch: u64,
+    // ino -> the epoch it is invalidated last time.
+    invalidated: HashMap<u64, u64>,
+    // the items with an epoch older than floor are dropped, it is moved forward once the
+    // invalidated inos are forgotten.
+    floor: u64,
+    op_rx: Receiver<MetaCacheOp>,
+    stop_rx: Receiver<u8>,
+}
+
+impl MetaCache {
+    fn new(cfg: &MetaCacheConfig, op_rx: Receiver<MetaCacheOp>, stop_rx: Receiver<u8>) -> Self {
+        MetaCache{
+            attr_ttl: Duration::from_millis(cfg.attr_ttl_ms),
+            entry_ttl: Duration::from_millis(cfg.entry_ttl_ms),
+            negative_ttl: Duration::from_millis(cfg.negative_ttl_ms),
+            dir_ttl: Duration::from_millis(cfg.dir_ttl_ms),
+            capacity: cfg.capacity,
+            attrs: HashMap::new(),
+            entries: HashMap::new(),
+            dirs: HashMap::new(),
+            epoch: 1,
+            invalidated: HashMap::new(),
+            floor: 1,
+            op_rx: op_rx,
+            stop_rx: stop_rx,
+        }
+    }
+
+    fn start(&mut self) {
+        loop {
+            select! {
+                recv(self.op_rx) -> msg => {
+                    match msg {
+                        Ok(msg) => {
+                            self.do_op(msg);
+                        }
+                        Err(err) => {
+                            error!("MetaCache::start: failed to recv op, err: {}", err);
+                            return;
+                        }
+                    }
+                }
+                recv(self.stop_rx) -> msg => {
+                    match msg {
+                        Ok(msg) => {
+                            warn!("MetaCache::start: got stop signal: {}, stopping...", msg);
+                        }
+                        Err(err) => {
+                            error!("MetaCache::start: failed to recv stop signal, err: {}", err);
+                        }
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    fn do_op(&mut self, op: MetaCacheOp) {
+        match op {
+            MetaCacheOp::GetAttr(ino, tx) => {
+                let attr = self.get_attr(ino);
+                let ret = tx.send((attr, self.epoch));
+                if let Err(err) = ret {
+                    error!("MetaCache: failed to send attr for ino: {}, err: {}", ino, err);
+                }
+            }
+            MetaCacheOp::PutAttr(attr, epoch) => {
+                if self.is_stale(attr.ino, epoch) {
+                    return;
+                }
+                self.put_attr(attr);
+            }
+            MetaCacheOp::Lookup(parent, name, tx) => {
+                let result = self.lookup(parent, &name);
+                let ret = tx.send(result);
+                if let Err(err) = ret {
+                    error!("MetaCache: failed to send entry for parent: {}, name: {}, err: {}", parent, name, err);
+                }
+            }
+            MetaCacheOp::PutEntry(parent, name, ino, epoch) => {
+                if self.is_stale(parent, epoch) {
+                    return;
+                }
+                if let Some(ino) = ino {
+                    if self.is_stale(ino, epoch) {
+                        return;
+                    }
+                }
+                self.put_entry(parent, name, ino);
+            }
+            MetaCacheOp::GetDir(ino, offset, tx) => {
+                let entries = self.get_dir(ino, offset);
+                let ret = tx.send((entries, self.epoch));
+                if let Err(err) = ret {
+                    error!("MetaCache: failed to send dir entries for ino: {}, offset: {}, err: {}", ino, offset, err);
+                }
+            }
+            MetaCacheOp::PutDir(ino, offset, entries, epoch) => {
+                if self.is_stale(ino, epoch) {
+                    return;
+                }
+                self.put_dir(ino, offset, entries);
+            }
+            MetaCacheOp::GetEpoch(tx) => {
+                let ret = tx.send(self.epoch);
+                if let Err(err) = ret {
+                    error!("MetaCache: failed to send epoch, err: {}", err);
+                }
+            }
+            MetaCacheOp::InvalidateIno(ino) => {
+                self.invalidate_ino(ino);
+            }
+            MetaCacheOp::InvalidateEntry(parent, name) => {
+                self.invalidate_entry(parent, &name);
+            }
+            MetaCacheOp::Clear => {
+                info!("MetaCache: clear {} attrs, {} dirs", self.attrs.len(), self.dirs.len());
+                self.attrs.clear();
+                self.entries.clear();
+                self.dirs.clear();
+                self.epoch += 1;
+                self.floor = self.epoch;
+                self.invalidated.clear();
+            }
+        }
+    }
+
+    // whether ino is invalidated after the item of it is fetched at epoch.
+    fn is_stale(&self, ino: u64, epoch: u64) -> bool {
+        if epoch < self.floor {
+            return true;
+        }
+        match self.invalidated.get(&ino) {
+            Some(e) => {
+                return *e > epoch;
+            }
+            None => {
+                return false;
+            }
+        }
+    }
+
+    fn mark_invalidated(&mut self, ino: u64) {
+        if self.invalidated.len() >= self.capacity {
+            // forget the invalidated inos, the items fetched before now are dropped instead.
+            self.invalidated.clear();
+            self.floor = self.epoch;
+        }
+        self.invalidated.insert(ino, self.epoch);
+    }
+
+    fn get_attr(&mut self, ino: u64) -> Option<FileAttr> {
+        if let Some(a) = self.attrs.get(&ino) {
+            if a.expire > Instant::now() {
+                return Some(a.attr.copy());
+            }
+            self.attrs.remove(&ino);
+        }
+        None
+    }
+
+    fn put_attr(&mut self, attr: FileAttr) {
+        if self.attr_ttl.as_millis() == 0 {
+            return;
+        }
+        if self.attrs.len() >= self.capacity {
+            let now = Instant::now();
+            self.attrs.retain(|_, a| a.expire > now);
+            if self.attrs.len() >= self.capacity {
+                self.attrs.clear();
+            }
+        }
+        self.attrs.insert(attr.ino, CachedAttr{
+            attr: attr,
+            expire: Instant::now() + self.attr_ttl,
+        });
+    }
+
+    fn lookup(&mut self, parent: u64, name: &String) -> CachedLookup {
+        let ino: Option<u64>;
+        match self.entries.get_mut(&parent) {
+            Some(names) => {
+                match names.get(name) {
+                    Some(e) => {
+                        if e.expire <= Instant::now() {
+                            names.remove(name);
+                            return CachedLookup::Miss(self.epoch);
+                        }
+                        ino = e.ino;
+                    }
+                    None => {
+                        return CachedLookup::Miss(self.epoch);
+                    }
+                }
+            }
+            None => {
+                return CachedLookup::Miss(self.epoch);
+            }
+        }
+        match ino {
+            Some(ino) => {
+                // the entry is useless without the attr.
+                match self.get_attr(ino) {
+                    Some(attr) => {
+                        return CachedLookup::Found(attr);
+                    }
+                    None => {
+                        return CachedLookup::Miss(self.epoch);
+                    }
+                }
+            }
+            None => {
+                return CachedLookup::Negative;
+            }
+        }
+    }
+
+    fn put_entry(&mut self, parent: u64, name: String, ino: Option<u64>) {
+        let ttl: Duration;
+        if ino.is_some() {
+            ttl = self.entry_ttl;
+        } else {
+            ttl = self.negative_ttl;
+        }
+        if ttl.as_millis() == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            let now = Instant::now();
+            for names in self.entries.values_mut() {
+                names.retain(|_, e| e.expire > now);
+            }
+            self.entries.retain(|_, names| !names.is_empty());
+            if self.entries.len() >= self.capacity {
+                self.entries.clear();
+            }
+        }
+        self.entries.entry(parent).or_insert(HashMap::new()).insert(name, CachedEntry{
+            ino: ino,
+            expire: Instant::now() + ttl,
+        });
+    }
+
+    fn get_dir(&mut self, ino: u64, offset: i64) -> Option<Vec<DirEntry>> {
+        if let Some(pages) = self.dirs.get_mut(&ino) {
+            if let Some(d) = pages.get(&offset) {
+                if d.expire > Instant::now() {
+                    return Some(d.entries.iter().map(|e| e.copy()).collect());
+                }
+                pages.remove(&offset);
+            }
+        }
+        None
+    }
+
+    fn put_dir(&mut self, ino: u64, offset: i64, entries: Vec<DirEntry>) {
+        if self.dir_ttl.as_millis() == 0 {
+            return;
+        }
+        if self.dirs.len() >= self.capacity {
+            let now = Instant::now();
+            for pages in self.dirs.values_mut() {
+                pages.retain(|_, d| d.expire > now);
+            }
+            self.dirs.retain(|_, pages| !pages.is_empty());
+            if self.dirs.len() >= self.capacity {
+                self.dirs.clear();
+            }
+        }
+        self.dirs.entry(ino).or_insert(HashMap::new()).insert(offset, CachedDir{
+            entries: entries,
+            expire: Instant::now() + self.dir_ttl,
+        });
+    }
+
+    fn invalidate_ino(&mut self, ino: u64) {
+        self.epoch += 1;
+        self.mark_invalidated(ino);
+        self.attrs.remove(&ino);
+        self.entries.remove(&ino);
+        self.dirs.remove(&ino);
+    }
+
+    fn invalidate_entry(&mut self, parent: u64, name: &String) {
+        if let Some(names) = self.entries.get_mut(&parent) {
+            if let Some(e) = names.remove(name) {
+                if let Some(ino) = e.ino {
+                    self.invalidate_ino(ino);
+                }
+            }
+        }
+        // the mtime and the listing of the parent are changed.
+        self.epoch += 1;
+        self.mark_invalidated(parent);
+        self.attrs.remove(&parent);
+        self.dirs.remove(&parent);
+    }
+}
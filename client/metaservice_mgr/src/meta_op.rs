@@ -44,6 +44,8 @@ impl MetaOpUploadSeg{
 
 pub struct MetaOpUpdateSegs{
     pub ino: u64,
+    // the fencing token of the leader of ino.
+    pub token: u64,
     pub segs: Vec<Segment>,
     pub garbages: Vec<Segment>,
     pub tx: Option<Sender<Errno>>,
@@ -52,6 +54,8 @@ pub struct MetaOpUpdateSegs{
 pub enum MetaOp{
     OpUploadSeg(MetaOpUploadSeg),
     OpUpdateChangedSegs(MetaOpUpdateSegs),
+    // the response is sent once all the former ops in the queue are handled.
+    OpDrain(Sender<Errno>),
 }
 
 pub enum MetaOpResp{
@@ -1,6 +1,7 @@
 
 use std::collections::HashMap;
-use crossbeam_channel::Sender;
+use std::time::{Duration, Instant};
+use crossbeam_channel::{Sender, RecvTimeoutError, bounded};
 
 use common::error::Errno;
 use crate::{meta_op::MetaOpResp, meta_thread_pool::MetaThreadPool, mgr::MetaServiceMgr};
@@ -32,7 +33,7 @@ impl MetaStore{
         return ret;
     }
 
-    pub fn update_changed_segments(&self, ino: u64, segs: &HashMap<u128, Segment>, garbages: &HashMap<u128, Segment>) -> Errno {
+    pub fn update_changed_segments(&self, ino: u64, token: u64, segs: &HashMap<u128, Segment>, garbages: &HashMap<u128, Segment>) -> Errno {
         let mut vsegs: Vec<Segment> = Vec::new();
         let mut vgarbages: Vec<Segment> = Vec::new();
 
@@ -45,12 +46,57 @@ impl MetaStore{
 
         //let thr = self.meta_pool.get_meta_thread_roundrobin();
         let thr = self.meta_pool.get_meta_thread_for_seg(ino, 0);
-        let ret = thr.update_changed_segments(ino, vsegs, vgarbages);
+        let ret = thr.update_changed_segments(ino, token, vsegs, vgarbages);
         if !ret.is_success(){
             error!("update_changed_segments: failed to upload changed segments for ino: {}, err: {:?}", ino, ret);
         }
         return ret;
     }
+
+    // the response is sent through tx once all the former changed segments of ino are handled.
+    pub fn sync_changed_segments(&self, ino: u64, tx: Sender<Errno>) -> Errno {
+        let thr = self.meta_pool.get_meta_thread_for_seg(ino, 0);
+        let ret = thr.sync_changed_segments(ino, tx);
+        if !ret.is_success(){
+            error!("sync_changed_segments: failed to sync changed segments for ino: {}, err: {:?}", ino, ret);
+        }
+        return ret;
+    }
+
+    // wait until all the queued meta ops are handled, used on shutdown.
+    // returns Etimedout if they are not handled in timeout.
+    pub fn drain(&self, timeout: Duration) -> Errno {
+        let threads = self.meta_pool.get_meta_threads();
+        let (tx, rx) = bounded::<Errno>(threads.len());
+        for t in threads {
+            let ret = t.drain(tx.clone());
+            if !ret.is_success() {
+                error!("drain: failed to drain meta thread, err: {:?}", ret);
+                return ret;
+            }
+        }
+        let deadline = Instant::now() + timeout;
+        let mut result = Errno::Esucc;
+        for _ in 0..threads.len() {
+            let ret = rx.recv_timeout(deadline.saturating_duration_since(Instant::now()));
+            match ret {
+                Ok(ret) => {
+                    if !ret.is_success() {
+                        result = ret;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    error!("drain: the meta ops are not handled in {:?}", timeout);
+                    return Errno::Etimedout;
+                }
+                Err(err) => {
+                    error!("drain: failed to recv drain result, err: {}", err);
+                    return Errno::Eintr;
+                }
+            }
+        }
+        return result;
+    }
 }
 
 impl Drop for MetaStore{
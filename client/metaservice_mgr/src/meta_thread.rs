@@ -21,7 +21,7 @@ impl MetaThread {
     pub fn new(name: &String, mgr: Arc<dyn MetaServiceMgr>) -> Self{
         let (op_tx, op_rx) = crossbeam_channel::unbounded::<MetaOp>();
         let (stop_tx, stop_rx) = crossbeam_channel::bounded::<u8>(1);
-        let meta_worker = MetaWorker::new(mgr, op_rx, stop_rx);
+        let mut meta_worker = MetaWorker::new(mgr, op_rx, stop_rx);
         let mut thr = MetaThread{
             thr: Thread::create(name),
             op_tx: op_tx,
@@ -65,10 +65,11 @@ impl MetaThread {
         }
     }
 
-    pub fn update_changed_segments(&self, ino: u64, segs: Vec<Segment>, garbages: Vec<Segment>) -> Errno {
+    pub fn update_changed_segments(&self, ino: u64, token: u64, segs: Vec<Segment>, garbages: Vec<Segment>) -> Errno {
         //let (tx, rx) = crossbeam_channel::bounded::<Errno>(1);
         let op = MetaOpUpdateSegs {
             ino: ino,
+            token: token,
             segs: segs,
             garbages: garbages,
             tx: None,
@@ -95,4 +96,39 @@ impl MetaThread {
             }
         }*/
     }
+
+    // the response is sent through tx once all the ops sent before are handled.
+    pub fn drain(&self, tx: Sender<Errno>) -> Errno {
+        let ret = self.op_tx.send(MetaOp::OpDrain(tx));
+        match ret {
+            Ok(_) => {
+                return Errno::Esucc;
+            }
+            Err(err) => {
+                error!("drain: failed to send drain op, err: {}", err);
+                return Errno::Eintr;
+            }
+        }
+    }
+
+    // wait until all the former changed segments of ino are updated into meta server.
+    pub fn sync_changed_segments(&self, ino: u64, tx: Sender<Errno>) -> Errno {
+        let op = MetaOpUpdateSegs {
+            ino: ino,
+            token: 0,
+            segs: Vec::new(),
+            garbages: Vec::new(),
+            tx: Some(tx),
+        };
+        let ret = self.op_tx.send(MetaOp::OpUpdateChangedSegs(op));
+        match ret {
+            Ok(_) => {
+                return Errno::Esucc;
+            }
+            Err(err) => {
+                error!("sync_changed_segments: failed to send op for ino: {}, err: {}", ino, err);
+                return Errno::Eintr;
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -33,6 +33,10 @@ impl MetaThreadPool {
         }
     }
 
+    pub fn get_meta_threads(&self) -> &Vec<MetaThread> {
+        &self.pool
+    }
+
     pub fn num(&self) -> u32 {
         self.pool.len() as u32
     }
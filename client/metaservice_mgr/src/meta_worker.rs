@@ -1,9 +1,11 @@
 use std::sync::Arc;
+use std::collections::HashMap;
 
-use crossbeam_channel::{Receiver, select};
+use crossbeam_channel::{Receiver, Sender, select};
 
 use crate::{meta_op::{MetaOpResp, MetaOpUpdateSegs, MetaOpUploadSeg, MetaOpUploadSegResp}, mgr::MetaServiceMgr};
 use crate::meta_op::MetaOp;
+use common::error::Errno;
 use log::{warn, error};
 
 
@@ -11,6 +13,8 @@ pub struct MetaWorker{
     meta_mgr: Arc<dyn MetaServiceMgr>,
     op_rx: Receiver<MetaOp>,
     stop_rx: Receiver<u8>,
+    // the errors of the failed async updates of the inos, reported at the next sync.
+    failed_inos: HashMap<u64, Errno>,
 }
 
 impl MetaWorker {
@@ -19,10 +23,11 @@ impl MetaWorker {
             meta_mgr: mgr,
             op_rx: op_rx,
             stop_rx: stop_rx,
+            failed_inos: HashMap::new(),
         }
     }
 
-    pub fn start(&self){
+    pub fn start(&mut self){
         loop {
             select! {
                 recv(self.op_rx)->msg => {
@@ -51,7 +56,7 @@ impl MetaWorker {
         }
     }
 
-    fn do_work(&self, msg: MetaOp) {
+    fn do_work(&mut self, msg: MetaOp) {
         match msg {
             MetaOp::OpUploadSeg(msg) => {
                 self.do_upload_seg(msg);
@@ -59,6 +64,26 @@ impl MetaWorker {
             MetaOp::OpUpdateChangedSegs(msg) => {
                 self.do_upload_changed_segs(msg);
             }
+            MetaOp::OpDrain(tx) => {
+                self.do_drain(tx);
+            }
+        }
+    }
+
+    fn do_drain(&mut self, tx: Sender<Errno>){
+        let mut ret = Errno::Esucc;
+        // no sync will come for these inos anymore, so report them here.
+        if !self.failed_inos.is_empty() {
+            error!("do_drain: the updates of inos: {:?} failed", self.failed_inos.keys());
+            self.failed_inos.clear();
+            ret = Errno::Eintr;
+        }
+        let cret = tx.send(ret);
+        match cret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("do_drain: failed to send response, err: {}", err);
+            }
         }
     }
 
@@ -80,10 +105,30 @@ impl MetaWorker {
         }
     }
 
-    fn do_upload_changed_segs(&self, op: MetaOpUpdateSegs){
-        let ret = self.meta_mgr.update_file_segments(op.ino, &op.segs, &op.garbages);
-        if !ret.is_success() {
-            error!("do_update_changed_segs: failed to upload changes segs for ino: {}, err: {:?}", op.ino, ret);
+    fn do_upload_changed_segs(&mut self, op: MetaOpUpdateSegs){
+        let ret: Errno;
+        if op.segs.is_empty() && op.garbages.is_empty() {
+            // this is a sync op, all the former updates of the ino are already processed because
+            // the updates of one ino are always handled by the same thread.
+            match self.failed_inos.remove(&op.ino) {
+                Some(err) => {
+                    error!("do_update_changed_segs: former update for ino: {} failed, err: {:?}", op.ino, err);
+                    ret = err;
+                }
+                None => {
+                    ret = Errno::Esucc;
+                }
+            }
+        } else {
+            ret = self.meta_mgr.update_file_segments(op.ino, op.token, &op.segs, &op.garbages);
+            if !ret.is_success() {
+                error!("do_update_changed_segs: failed to upload changes segs for ino: {}, err: {:?}", op.ino, ret);
+                if op.tx.is_none() {
+                    // keep the error for the next sync, such as Eperm of a leader which is fenced off.
+                    self.failed_inos.insert(op.ino, ret);
+                    return;
+                }
+            }
         }
 
         if let Some(tx) = op.tx {
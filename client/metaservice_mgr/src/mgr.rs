@@ -1,23 +1,60 @@
 use crate::types::{DirEntry, FileLeader, 
     NewFileInfo, SetFileAttr, 
-    Segment, HeartbeatResult};
+    Segment, HeartbeatResult, FsUsage};
 use crate::types::FileAttr;
 use common::error::Errno;
 
 pub trait MetaServiceMgr: Send + Sync {
     fn mount(&self, uid: u32, gid: u32) -> Result<(), Errno>;
+    // list a page of the entries after the cursor offset, 0 lists from the beginning.
+    // an empty page means the end of the dir.
     fn read_dir(&self, ino: u64, offset: i64)->Result<Vec<DirEntry>, Errno>;
     fn read_dir_file_attr(&self, ino: u64, name: &String) -> Result<FileAttr, Errno>;
     fn read_file_attr(&self, ino: u64) -> Result<FileAttr, Errno>;
     fn set_file_attr(&self, attr: &SetFileAttr) -> Result<FileAttr, Errno>;
-    fn new_ino_leader(&self, parent: u64, name: &String, uid: u32, gid: u32, perm: u32, file_type: u8) -> Result<NewFileInfo, Errno>;
+    // create the file name under parent, rdev is only used by the char & block device.
+    fn new_ino_leader(&self, parent: u64, name: &String, uid: u32, gid: u32, perm: u32, file_type: u8, rdev: u32) -> Result<NewFileInfo, Errno>;
     fn get_file_leader(&self, ino: u64) -> Result<FileLeader, Errno>;
+    // take over the leadership of ino whose leader holding token has an expired lease.
+    // returns the new leader, which is another machine if it has taken over ino first.
+    fn transfer_leader(&self, ino: u64, token: u64) -> Result<FileLeader, Errno>;
+    // hand the leadership of ino over to machine, token is the one held by the current leader.
+    // returns the new leader with a newer token.
+    fn migrate_leader(&self, ino: u64, machine: &String, token: u64) -> Result<FileLeader, Errno>;
     fn get_file_segments(&self, ino: u64, offset: Option<u64>, size: Option<i64>) -> Result<Vec<Segment>, Errno>;
     fn get_machine_id(&self) -> String;
     fn add_file_block(&self, ino: u64, seg: &Segment) -> Errno;
-    fn update_file_segments(&self, ino: u64, segs: &Vec<Segment>, removed_segs: &Vec<Segment>) -> Errno;
+    // token is the fencing token of the leader, Eperm is returned if the leadership is taken over.
+    fn update_file_segments(&self, ino: u64, token: u64, segs: &Vec<Segment>, removed_segs: &Vec<Segment>) -> Errno;
     fn upload_segment(&self, id0: u64, id1: u64, next_offset: u64) -> Errno;
     fn heartbeat(&self)-> Result<HeartbeatResult, Errno>;
     fn delete_file(&self, ino: u64) -> Errno;
-    fn rename(&self, parent: u64, name: &String, new_parent: u64, new_name: &String) -> Errno;
+    // rename atomically, the existing target is replaced.
+    // returns the attr of the replaced target whose dir entry is removed.
+    fn rename(&self, parent: u64, name: &String, new_parent: u64, new_name: &String) -> Result<Option<FileAttr>, Errno>;
+    // remove the empty dir specified by parent & name, returns the updated attr of the parent.
+    fn delete_dir(&self, parent: u64, name: &String) -> Result<FileAttr, Errno>;
+    // create the symlink name under parent which points to target.
+    fn create_symlink(&self, parent: u64, name: &String, uid: u32, gid: u32, target: &String) -> Result<FileAttr, Errno>;
+    // read the target of the symlink specified by ino.
+    fn read_symlink(&self, ino: u64) -> Result<String, Errno>;
+    // remove the name under parent and the ino it points to.
+    fn unlink_file(&self, parent: u64, name: &String, ino: u64) -> Errno;
+    // get the space & inode usage of the whole namespace.
+    fn get_fs_usage(&self) -> Result<FsUsage, Errno>;
+    // set the extended attribute name of ino, flags is XATTR_CREATE or XATTR_REPLACE or 0.
+    fn set_xattr(&self, ino: u64, name: &String, value: &[u8], flags: i32) -> Result<(), Errno>;
+    // get the value of extended attribute name, returns Enodata if it doesn't exist.
+    fn get_xattr(&self, ino: u64, name: &String) -> Result<Vec<u8>, Errno>;
+    // list the names of all the extended attributes of ino.
+    fn list_xattr(&self, ino: u64) -> Result<Vec<String>, Errno>;
+    // remove the extended attribute name of ino, returns Enodata if it doesn't exist.
+    fn remove_xattr(&self, ino: u64, name: &String) -> Result<(), Errno>;
+    // remove the name under parent, but keep ino which is still opened on this machine.
+    // ino is recorded as an orphan, so that it can be reclaimed after crash.
+    fn add_orphan(&self, parent: u64, name: &String, ino: u64) -> Result<(), Errno>;
+    // remove the orphan record of ino after it is deleted.
+    fn remove_orphan(&self, ino: u64) -> Result<(), Errno>;
+    // list the orphan inos recorded by this machine.
+    fn list_orphans(&self) -> Result<Vec<u64>, Errno>;
 }
\ No newline at end of file
@@ -1,10 +1,10 @@
 extern crate tokio;
 
 use std::{sync::Arc, time::Instant};
-use crate::{mgr, types::{Block, FileLeader, NewFileInfo, Segment, SetFileAttr}};
+use crate::{mgr, types::{Block, FileLeader, FileType, NewFileInfo, Segment, SetFileAttr}};
 use crate::types::DirEntry;
 use crate::types::FileAttr;
-use crate::types::{HeartbeatResult, HeartbeatUploadSeg};
+use crate::types::{HeartbeatResult, HeartbeatUploadSeg, FsUsage};
 use common::http_client;
 use common::http_client::RespText;
 use common::config::Config;
@@ -14,8 +14,12 @@ use common::http_client::HttpMethod;
 use common::runtime::Executor;
 use crate::message::{MsgFileAttr, MsgSetFileAttr, ReqAddBlock, ReqDirFileAttr, ReqFileAttr, ReqFileCreate, ReqFileLeader, 
     ReqGetSegments, ReqMount, ReqReadDir, ReqSetFileAttr, ReqUploadSegment, RespAddBock, RespDirFileAttr, RespFileAttr, RespFileCreate, 
-    RespFileLeader, RespGetSegments, RespHeartbeat, RespReadDir, RespSetFileAttr, RespUploadSegment, ReqDeleteFile, RespDeleteFile,
-    RespFileRename};
+    RespFileLeader, RespGetSegments, RespHeartbeat, RespReadDir, RespDirEntry, RespSetFileAttr, RespUploadSegment, ReqDeleteFile, RespDeleteFile,
+    RespFileRename, ReqDeleteDir, RespDeleteDir, ReqCreateSymlink, RespCreateSymlink, ReqReadSymlink, RespReadSymlink,
+    ReqFsUsage, RespFsUsage,
+    ReqSetXattr, RespSetXattr, ReqGetXattr, RespGetXattr, ReqListXattr, RespListXattr, ReqRemoveXattr, RespRemoveXattr,
+    ReqAddOrphan, ReqOrphan, RespOrphan, ReqListOrphans, RespListOrphans, ReqTransferLeader, ReqMigrateLeader};
+use crate::message::{ERR_YIGFS_NO_SUCH_FILE, ERR_YIGFS_NOT_FIND_TARGET_DIR_FILES, to_errno};
 
 use crate::message::{MsgSegmentOffset, ReqFileRename, ReqHeartbeat, ReqUpdateSegments, RespUpdateSegments};
 use log::{info, error};
@@ -28,6 +32,8 @@ pub struct MetaServiceMgrImpl{
     bucket: String,
     zone: String,
     machine: String,
+    // the leases of the files led by this machine in seconds.
+    lease_timeout: u64,
     exec: Executor,
 }
 
@@ -64,7 +70,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             Err(error) => {
                 error!("failed to mount region: {}, bucket: {}, err: {}",
                 self.region, self.bucket, error);
-                return Err(Errno::Eintr);
+                return Err(Errno::Etimedout);
             }
         }
         if resp.status >= 300 {
@@ -76,32 +82,16 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
     }
     fn read_dir(&self, ino: u64, offset: i64)->Result<Vec<DirEntry>, Errno>{
         let mut entrys = Vec::new();
-        let ret = self.read_dir_files(ino, offset);
+        let ret = self.read_dir_page(ino, offset);
         match ret {
-            Ok(dirs) => {
-                if dirs.result.err_code != 0 {
-                    if dirs.result.err_code == 40003 {
-                        error!("no files found in bucket {} with ino: {}, offset: {}", self.bucket, ino, offset);
-                        return Err(Errno::Enoent);
-                    }
-                    error!("got error when read_dir_files for ino: {}, offset: {}, err: {}",
-                    ino, offset, dirs.result.err_msg);
-                    return Err(Errno::Eintr);
-                }
-                for i in dirs.files {
-                    let entry = DirEntry{
-                        ino: i.ino,
-                        file_type: i.dir_entry_type.into(),
-                        name: i.name,
-                    };
-                    entrys.push(entry);
+            Ok(files) => {
+                for f in files {
+                    entrys.push(self.to_dir_entry(&f));
                 }
                 return Ok(entrys);
             }
             Err(error) => {
-                error!("failed to read meta for ino: {}, offset: {}, err: {}",
-                ino, offset, error);
-                return Err(Errno::Eintr);
+                return Err(error);
             }
         }
     }
@@ -114,8 +104,8 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
                 attr = ret;
             }
             Err(error) => {
-                error!("failed to read_file_attr for ino: {}, err: {}", ino, error);
-                return Err(Errno::Eintr);
+                error!("failed to read_file_attr for ino: {}, err: {:?}", ino, error);
+                return Err(error);
             }
         }
 
@@ -159,7 +149,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             }
             Err(err) => {
                 error!("failed to set_file_attr: {}, err: {}", req_str, err);
-                return Err(Errno::Eintr);
+                return Err(Errno::Etimedout);
             }
         }
 
@@ -184,7 +174,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
         if resp.result.err_code != 0 {
             error!("failed to set_file_attr for {}, err_code: {}, err_msg: {}",
             req_str, resp.result.err_code, resp.result.err_msg);
-            return Err(Errno::Eintr);
+            return Err(to_errno(resp.result.err_code));
         }
 
         Ok(self.to_file_attr(&resp.attr))
@@ -194,12 +184,15 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
         let ret = self.read_dir_file_attr(ino, name);
         match ret {
             Ok(ret) => {
-                let file_attr = self.to_file_attr(&ret);
+                let mut file_attr = self.to_file_attr(&ret);
+                // the attr got by name doesn't carry the parent, it is the dir looked up in.
+                if file_attr.parent == 0 {
+                    file_attr.parent = ino;
+                }
                 return Ok(file_attr);
             }
             Err(error) => {
-                error!("failed to read_dir_file_attr for ino: {}, name: {}, err: {}", ino, name, error);
-                return Err(Errno::Eintr);
+                return Err(error);
             }
         }
     }
@@ -232,7 +225,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             }
             Err(error) => {
                 error!("failed to get file_leader, req: {}, err: {}", body, error);
-                return Err(Errno::Eintr);
+                return Err(Errno::Etimedout);
             }
         }
         if resp.status >= 300 {
@@ -253,16 +246,66 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
         if resp_leader.result.err_code != 0 {
             error!("failed to get file_leader for {}, err_code: {}, err_msg: {}", 
             body, resp_leader.result.err_code, resp_leader.result.err_msg);
-            return Err(Errno::Eintr);
+            return Err(to_errno(resp_leader.result.err_code));
         }
         Ok(FileLeader{
             zone: resp_leader.leader_info.zone,
             leader: resp_leader.leader_info.leader,
             ino: ino,
+            token: resp_leader.leader_info.token,
+            expired: resp_leader.leader_info.expired,
         })
     }
 
-    fn new_ino_leader(&self, parent: u64, name: &String, uid: u32, gid: u32, perm: u32, file_type: u8) -> Result<NewFileInfo, Errno> {
+    fn transfer_leader(&self, ino: u64, token: u64) -> Result<FileLeader, Errno> {
+        let req = ReqTransferLeader{
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            zone: self.zone.clone(),
+            machine: self.machine.clone(),
+            ino: ino,
+            token: token,
+        };
+        let body: String;
+        let ret = json::encode_to_str::<ReqTransferLeader>(&req);
+        match ret {
+            Ok(ret) => {
+                body = ret;
+            }
+            Err(err) => {
+                error!("transfer_leader: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let url = format!("{}/v1/file/leader", self.meta_server_url);
+        self.change_file_leader("transfer_leader", &url, &body, ino)
+    }
+
+    fn migrate_leader(&self, ino: u64, machine: &String, token: u64) -> Result<FileLeader, Errno> {
+        let req = ReqMigrateLeader{
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            zone: self.zone.clone(),
+            machine: machine.clone(),
+            ino: ino,
+            token: token,
+        };
+        let body: String;
+        let ret = json::encode_to_str::<ReqMigrateLeader>(&req);
+        match ret {
+            Ok(ret) => {
+                body = ret;
+            }
+            Err(err) => {
+                error!("migrate_leader: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let url = format!("{}/v1/file/leader/migrate", self.meta_server_url);
+        self.change_file_leader("migrate_leader", &url, &body, ino)
+    }
+
+    fn new_ino_leader(&self, parent: u64, name: &String, uid: u32, gid: u32, perm: u32, file_type: u8, rdev: u32) -> Result<NewFileInfo, Errno> {
         let req_file_create = ReqFileCreate{
             zone: self.zone.clone(),
             machine: self.machine.clone(),
@@ -274,6 +317,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             gid: gid,
             perm: perm,
             file_type: file_type,
+            rdev: rdev,
         };
         let body : String;
         let ret = json::encode_to_str::<ReqFileCreate>(&req_file_create);
@@ -295,7 +339,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             }
             Err(error) => {
                 error!("failed to new_ino_leader for {}, err: {}", body, error);
-                return Err(Errno::Eintr);
+                return Err(Errno::Etimedout);
             }
         }
         if resp.status >= 300 {
@@ -316,7 +360,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
         if resp_file_created.result.err_code != 0 {
             error!("failed to new_io_leader for {}, err_code: {}, err_msg: {}", 
             body, resp_file_created.result.err_code, resp_file_created.result.err_msg);
-            return Err(Errno::Eintr);
+            return Err(to_errno(resp_file_created.result.err_code));
         }
 
         Ok(NewFileInfo{
@@ -324,6 +368,8 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
                 zone: resp_file_created.leader_info.zone,
                 leader: resp_file_created.leader_info.leader,
                 ino: resp_file_created.file_info.ino,
+                token: resp_file_created.leader_info.token,
+                expired: resp_file_created.leader_info.expired,
             },
             attr: self.to_file_attr(&resp_file_created.file_info),
         })
@@ -361,7 +407,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             }
             Err(err) => {
                 error!("failed to send {} to get_file_segments, err: {}", body, err);
-                return Err(Errno::Eintr);
+                return Err(Errno::Etimedout);
             }
         }
         let dur = begin.elapsed().as_nanos();
@@ -385,7 +431,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
         if resp.result.err_code != 0 {
             error!("failed to get_file_segments for {}, err_code: {}, err_msg: {}",
             body, resp.result.err_code, resp.result.err_msg);
-            return Err(Errno::Eintr);
+            return Err(to_errno(resp.result.err_code));
         }
         let dur = begin.elapsed().as_nanos();
         info!("get_file_segments: for ino: {}, decode from json takes: {}", ino, dur);
@@ -451,7 +497,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             Err(err) => {
                 error!("add_file_block: failed to send req to {} with body: {}, err: {}",
                 url, body, err);
-                return Errno::Eintr;
+                return Errno::Etimedout;
             }
         }
 
@@ -475,12 +521,12 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
 
         if resp.result.err_code != 0 {
             error!("add_file_block: failed to add file block for {}, err: {}", body, resp.result.err_msg);
-            return Errno::Eintr;
+            return to_errno(resp.result.err_code);
         }
         return Errno::Esucc;
     }
 
-    fn update_file_segments(&self, ino: u64, segs: &Vec<Segment>, removed_segs: &Vec<Segment>) -> Errno{
+    fn update_file_segments(&self, ino: u64, token: u64, segs: &Vec<Segment>, removed_segs: &Vec<Segment>) -> Errno{
         let req_update_seg = ReqUpdateSegments {
             region: self.region.clone(),
             bucket: self.bucket.clone(),
@@ -489,6 +535,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             generation: 0,
             segments: segs.clone(),
             remove_segments: removed_segs.clone(),
+            leader_token: token,
         };
 
         let body: String;
@@ -513,7 +560,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             Err(err) => {
                 error!("update_file_segments: failed to send req to {} with body: {}, err: {}",
                 url, body, err);
-                return Errno::Eintr;
+                return Errno::Etimedout;
             }
         }
 
@@ -537,7 +584,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
 
         if resp.result.err_code != 0 {
             error!("update_file_segments: failed to add file block for ino: {}, err: {}", ino, resp.result.err_msg);
-            return Errno::Eintr;
+            return to_errno(resp.result.err_code);
         }
 
         return Errno::Esucc;
@@ -578,7 +625,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             }
             Err(err) => {
                 error!("upload_segment: failed to send req: {}, err: {}", req_body, err);
-                return Errno::Eintr;
+                return Errno::Etimedout;
             }
         }
         if resp_body.status >= 300 {
@@ -602,7 +649,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
 
         if resp.result.err_code != 0 {
             error!("upload_segment: failed to upload: {}, err: {}", req_body, resp.result.err_msg);
-            return Errno::Eintr;
+            return to_errno(resp.result.err_code);
         }
 
         return Errno::Esucc;
@@ -615,6 +662,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             bucket: self.bucket.clone(),
             zone: self.zone.clone(),
             machine: self.machine.clone(),
+            lease_timeout: self.lease_timeout,
         };
         let req_str: String;
         let ret = json::encode_to_str::<ReqHeartbeat>(&req);
@@ -639,7 +687,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             }
             Err(err) => {
                 error!("heartbeat: failed to send heart: {} to server, err: {}", req_str, err);
-                return Err(Errno::Eintr);
+                return Err(Errno::Etimedout);
             }
         }
 
@@ -651,6 +699,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
         let mut result = HeartbeatResult{
             upload_segments: Vec::new(),
             remove_segments: Vec::new(),
+            invalid_inos: Vec::new(),
         };
 
         let resp: RespHeartbeat;
@@ -672,6 +721,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             });
             // TODO remove segments.
         }
+        result.invalid_inos = resp.invalid_inos;
 
         Ok(result)
     }
@@ -710,7 +760,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             }
             Err(error) => {
                 error!("delete_file: failed to send req to {} with body: {}, err: {}", url, req_body, error);
-                return Errno::Eintr;
+                return Errno::Etimedout;
             }
         }
         
@@ -728,13 +778,13 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
 
         if resp.result.err_code != 0 {
             error!("delete_file: failed to delete the file: {}, err: {}", req_body, resp.result.err_msg);
-            return Errno::Eintr;
+            return to_errno(resp.result.err_code);
         }
 
         return Errno::Esucc;
     }
 
-    fn rename(&self, parent: u64, name: &String, new_parent: u64, new_name: &String) -> Errno{
+    fn rename(&self, parent: u64, name: &String, new_parent: u64, new_name: &String) -> Result<Option<FileAttr>, Errno>{
         let ino: u64;
         let generation: u64;
         let ret = self.read_dir_file_attr(parent, name);
@@ -744,9 +794,9 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
                 generation = ret.generation;
             }
             Err(err) => {
-                error!("MetaServiceMgrImpl::rename: failed to get file attr for parent: {}, name: {}, err: {}",
+                error!("MetaServiceMgrImpl::rename: failed to get file attr for parent: {}, name: {}, err: {:?}",
                 parent, name, err);
-                return Errno::Enoent;
+                return Err(err);
             }
         }
 
@@ -771,7 +821,7 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             Err(err) => {
                 error!("MetaServiceMgrImpl::rename: failed to get file attr for parent: {}, name: {}, err: {}",
                 parent, name, err);
-                return Errno::Eintr;
+                return Err(Errno::Eintr);
             }
         }
         let url = format!("{}/v1/file/name", self.meta_server_url);
@@ -782,13 +832,13 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
                 if text.status >= 300 {
                     error!("MetaServiceMgrImpl::rename: failed to rename: parent: {}, name: {} to new_parent: {}, new_name: {}, err status: {}, resp: {}", 
                     parent, name, new_parent, new_name, text.status, text.body);
-                    return Errno::Eintr;
+                    return Err(Errno::Eintr);
                 }
                 resp_body = text.body;
             }
             Err(error) => {
                 error!("delete_file: failed to send req to {} with body: {}, err: {}", url, req_body, error);
-                return Errno::Eintr;
+                return Err(Errno::Etimedout);
             }
         }
         let resp: RespFileRename;
@@ -800,191 +850,941 @@ impl mgr::MetaServiceMgr for MetaServiceMgrImpl{
             Err(err) => {
                 error!("MetaServiceMgrImpl::rename: failed to rename: parent: {}, name: {} to new_parent: {}, new_name: {}, err: {}", 
                 parent, name, new_parent, new_name, err);
-                return Errno::Eintr;
+                return Err(Errno::Eintr);
             }
         }
 
         if resp.result.err_code != 0 {
             error!("MetaServiceMgrImpl::rename: failed to rename: parent: {}, name: {} to new_parent: {}, new_name: {}, err: {}", 
                 parent, name, new_parent, new_name, resp.result.err_msg);
-            return Errno::Eintr;
+            return Err(to_errno(resp.result.err_code));
         }
 
-        return Errno::Esucc;
-    }
-}
-
-impl MetaServiceMgrImpl {
-    pub fn new(meta_cfg: &Config, exec: &Executor) -> Result<MetaServiceMgrImpl, String> {
-        let http_client = Arc::new(http_client::HttpClient::new(3));
-        Ok(MetaServiceMgrImpl{
-            http_client: http_client,
-            meta_server_url: meta_cfg.metaserver_config.meta_server.clone(),
-            region: meta_cfg.s3_config.region.clone(),
-            bucket: meta_cfg.s3_config.bucket.clone(),
-            zone: meta_cfg.zone_config.zone.clone(),
-            machine: meta_cfg.zone_config.machine.clone(),
-            exec: exec.clone(),
-        })
-    }
-
-    fn to_file_attr(&self, msg_attr: &MsgFileAttr) -> FileAttr {
-        FileAttr {
-            ino: msg_attr.ino,
-            generation: msg_attr.generation,
-            size: msg_attr.size,
-            blocks: msg_attr.blocks,
-            atime: msg_attr.atime,
-            mtime: msg_attr.mtime,
-            ctime: msg_attr.ctime,
-            kind: msg_attr.kind.into(),
-            perm: msg_attr.perm,
-            nlink: msg_attr.nlink,
-            uid: msg_attr.uid,
-            gid: msg_attr.gid,
-            rdev: msg_attr.rdev,
-            flags: msg_attr.flags,
+        match resp.replaced {
+            Some(attr) => {
+                Ok(Some(self.to_file_attr(&attr)))
+            }
+            None => {
+                Ok(None)
+            }
         }
     }
 
-    fn read_file_attr(&self, ino: u64) -> Result<MsgFileAttr, String> {
-        let req_file_attr = ReqFileAttr{
+    fn delete_dir(&self, parent: u64, name: &String) -> Result<FileAttr, Errno> {
+        let req = ReqDeleteDir {
             region: self.region.clone(),
             bucket: self.bucket.clone(),
-            ino: ino,
+            zone: self.zone.clone(),
+            machine: self.machine.clone(),
+            parent: parent,
+            name: name.clone(),
         };
-        let ret = json::encode_to_str::<ReqFileAttr>(&req_file_attr);
-        let req_body : String;
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqDeleteDir>(&req);
         match ret {
-            Ok(body) => {
-                req_body = body;
+            Ok(ret) => {
+                req_body = ret;
             }
-            Err(error) => {
-                return Err(format!("failed to encode req_file_attr: {:?}, err: {}", req_file_attr, error));
+            Err(err) => {
+                error!("delete_dir: failed to encode the delete dir req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
             }
         }
-        let resp : RespText;
-        let url = format!("{}/v1/file/attr", self.meta_server_url);
-        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Get, false));
+
+        let resp_body: String;
+        let url = format!("{}/v1/dir/remove", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Post, false));
         match ret {
-            Ok(ret) => {
-                resp = ret;
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("delete_dir: failed to delete the dir: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
             }
             Err(error) => {
-                return Err(error);
+                error!("delete_dir: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
             }
         }
-        if resp.status >= 300 {
-            return Err(format!("failed to read_file_attr from {}, for ino: {}, err: {}",
-        url, ino, resp.body));
-        }
-        let resp_attr: RespFileAttr;
-        let ret = json::decode_from_str::<RespFileAttr>(&resp.body);
+
+        let resp: RespDeleteDir;
+        let ret = json::decode_from_str::<RespDeleteDir>(&resp_body);
         match ret {
             Ok(ret) => {
-                resp_attr = ret;
+                resp = ret;
             }
-            Err(error) => {
-                return Err(error);
+            Err(err) => {
+                error!("delete_dir: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
             }
         }
-        if resp_attr.result.err_code != 0 {
-            return Err(format!("failed to read_file_attr for ino: {}, err_code: {}, err_msg: {}",
-        ino, resp_attr.result.err_code, resp_attr.result.err_msg));
+
+        if resp.result.err_code != 0 {
+            error!("delete_dir: failed to delete the dir: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
         }
 
-        return Ok(resp_attr.attr);
+        Ok(self.to_file_attr(&resp.parent_attr))
     }
 
-    fn read_dir_file_attr(&self, ino: u64, name: &String) -> Result<MsgFileAttr, String>{
-        let req_dir_file_attr = ReqDirFileAttr{
+    fn create_symlink(&self, parent: u64, name: &String, uid: u32, gid: u32, target: &String) -> Result<FileAttr, Errno> {
+        let req = ReqCreateSymlink {
+            zone: self.zone.clone(),
+            machine: self.machine.clone(),
             region: self.region.clone(),
             bucket: self.bucket.clone(),
-            ino: ino,
-            name: String::from(name),
+            parent: parent,
+            name: name.clone(),
+            uid: uid,
+            gid: gid,
+            // the permission of symlink is always 0777.
+            perm: 0o777,
+            file_type: FileType::LINK.to(),
+            target: target.clone(),
         };
-        let ret = json::encode_to_str::<ReqDirFileAttr>(&req_dir_file_attr);
-        let req_child_file_attr_json: String;
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqCreateSymlink>(&req);
         match ret {
-            Ok(body) => {
-                req_child_file_attr_json = body;
+            Ok(ret) => {
+                req_body = ret;
             }
-            Err(error) => {
-                return Err(error);
+            Err(err) => {
+                error!("create_symlink: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
             }
         }
-        let resp_text : RespText;
-        let url = format!("{}/v1/dir/file/attr", self.meta_server_url);
-        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_child_file_attr_json.as_bytes(), &HttpMethod::Get, false));
+
+        let url = format!("{}/v1/dir/symlink", self.meta_server_url);
+        let resp_text: RespText;
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Put, false));
         match ret {
-            Ok(resp) => {
-                resp_text = resp;
+            Ok(ret) => {
+                resp_text = ret;
             }
-            Err(error) => {
-                return Err(error);
+            Err(err) => {
+                error!("create_symlink: failed to send req to {} with body: {}, err: {}", url, req_body, err);
+                return Err(Errno::Etimedout);
             }
         }
         if resp_text.status >= 300 {
-            return Err(format!("failed to get child file attr from url {}, err: {}", url, resp_text.body));
+            error!("create_symlink: failed to create symlink: {}, got status: {}, resp: {}",
+            req_body, resp_text.status, resp_text.body);
+            return Err(Errno::Eintr);
         }
-        let resp_attr : RespDirFileAttr;
-        let ret = json::decode_from_str::<RespDirFileAttr>(&resp_text.body);
+
+        let resp: RespCreateSymlink;
+        let ret = json::decode_from_str::<RespCreateSymlink>(&resp_text.body);
         match ret {
-            Ok(attr) => {
-                resp_attr = attr;
+            Ok(ret) => {
+                resp = ret;
             }
-            Err(error) => {
-                return Err(error);
+            Err(err) => {
+                error!("create_symlink: failed to decode from: {}, err: {}", resp_text.body, err);
+                return Err(Errno::Eintr);
             }
         }
-        if resp_attr.result.err_code != 0 {
-            return Err(format!("failed to get child file attrs for ino: {}, name: {}, err: {}", 
-            ino, name, resp_attr.result.err_msg));
+
+        if resp.result.err_code != 0 {
+            error!("create_symlink: failed to create symlink: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
         }
-        return Ok(resp_attr.attr);
+
+        Ok(self.to_file_attr(&resp.attr))
     }
 
-    fn read_dir_files(&self, ino: u64, offset: i64) -> Result<Box<RespReadDir>, String>{
-        let req_read_dir = ReqReadDir{
+    fn read_symlink(&self, ino: u64) -> Result<String, Errno> {
+        let req = ReqReadSymlink {
             region: self.region.clone(),
-            bucket:self.bucket.clone(),
+            bucket: self.bucket.clone(),
             ino: ino,
-            offset: offset,
         };
-        let ret = serde_json::to_string(&req_read_dir);
-        let req_read_dir_json: String;
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqReadSymlink>(&req);
         match ret {
             Ok(ret) => {
-                //send the req to meta server
-                req_read_dir_json = ret;
+                req_body = ret;
             }
-            Err(error) => {
-                return Err(format!("faied to convert {:?} to json, err: {}", req_read_dir, error));
+            Err(err) => {
+                error!("read_symlink: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
             }
         }
 
-        let resp_body :String;
-        let url = format!("{}/v1/dir/files", self.meta_server_url);
-        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_read_dir_json.as_bytes(), &HttpMethod::Get, false));
+        let url = format!("{}/v1/file/symlink", self.meta_server_url);
+        let resp_text: RespText;
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Get, false));
         match ret {
-            Ok(text) => {
-                if text.status >= 300 {
-                    return Err(format!("got resp {}", text.status));
-                }
-                resp_body = text.body;
+            Ok(ret) => {
+                resp_text = ret;
             }
-            Err(error) => {
-                return Err(format!("failed to get response for {}, err: {}", url, error));
+            Err(err) => {
+                error!("read_symlink: failed to send req to {} with body: {}, err: {}", url, req_body, err);
+                return Err(Errno::Etimedout);
             }
         }
-        
-        let resp_read_dir = json::decode_from_str::<RespReadDir>(&resp_body);
-        match resp_read_dir {
-            Ok(resp_read_dir) => {
-                return Ok(Box::new(resp_read_dir));
+        if resp_text.status >= 300 {
+            error!("read_symlink: failed to read symlink: {}, got status: {}, resp: {}",
+            req_body, resp_text.status, resp_text.body);
+            return Err(Errno::Eintr);
+        }
+
+        let resp: RespReadSymlink;
+        let ret = json::decode_from_str::<RespReadSymlink>(&resp_text.body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
             }
-            Err(error) => {
-                return Err(format!("failed to decode from {}, err: {}", resp_body, error));
+            Err(err) => {
+                error!("read_symlink: failed to decode from: {}, err: {}", resp_text.body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("read_symlink: failed to read symlink for ino: {}, err_code: {}, err: {}",
+            ino, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(resp.target)
+    }
+
+    // hard links are not supported, so the ino is removed with its only name.
+    fn unlink_file(&self, _parent: u64, _name: &String, ino: u64) -> Errno {
+        self.delete_file(ino)
+    }
+
+    fn get_fs_usage(&self) -> Result<FsUsage, Errno> {
+        let req = ReqFsUsage {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqFsUsage>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("get_fs_usage: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/fs/usage", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Get, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("get_fs_usage: failed to get usage: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("get_fs_usage: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespFsUsage;
+        let ret = json::decode_from_str::<RespFsUsage>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("get_fs_usage: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("get_fs_usage: failed to get usage: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(FsUsage{
+            used_size: resp.used_size,
+            file_count: resp.file_count,
+        })
+    }
+
+    fn set_xattr(&self, ino: u64, name: &String, value: &[u8], flags: i32) -> Result<(), Errno> {
+        let req = ReqSetXattr {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            ino: ino,
+            name: name.clone(),
+            value: value.to_vec(),
+            flags: flags,
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqSetXattr>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("set_xattr: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/file/xattr", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Put, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("set_xattr: failed to set xattr: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("set_xattr: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespSetXattr;
+        let ret = json::decode_from_str::<RespSetXattr>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("set_xattr: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("set_xattr: failed to set xattr: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(())
+    }
+
+    fn get_xattr(&self, ino: u64, name: &String) -> Result<Vec<u8>, Errno> {
+        let req = ReqGetXattr {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            ino: ino,
+            name: name.clone(),
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqGetXattr>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("get_xattr: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/file/xattr", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Get, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("get_xattr: failed to get xattr: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("get_xattr: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespGetXattr;
+        let ret = json::decode_from_str::<RespGetXattr>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("get_xattr: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("get_xattr: failed to get xattr: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(resp.value)
+    }
+
+    fn list_xattr(&self, ino: u64) -> Result<Vec<String>, Errno> {
+        let req = ReqListXattr {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            ino: ino,
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqListXattr>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("list_xattr: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/file/xattrs", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Get, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("list_xattr: failed to list xattr: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("list_xattr: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespListXattr;
+        let ret = json::decode_from_str::<RespListXattr>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("list_xattr: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("list_xattr: failed to list xattr: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(resp.names)
+    }
+
+    fn remove_xattr(&self, ino: u64, name: &String) -> Result<(), Errno> {
+        let req = ReqRemoveXattr {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            ino: ino,
+            name: name.clone(),
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqRemoveXattr>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("remove_xattr: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/file/xattr/remove", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Post, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("remove_xattr: failed to remove xattr: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("remove_xattr: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespRemoveXattr;
+        let ret = json::decode_from_str::<RespRemoveXattr>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("remove_xattr: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("remove_xattr: failed to remove xattr: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(())
+    }
+
+    fn add_orphan(&self, parent: u64, name: &String, ino: u64) -> Result<(), Errno> {
+        let req = ReqAddOrphan {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            zone: self.zone.clone(),
+            machine: self.machine.clone(),
+            parent_ino: parent,
+            file_name: name.clone(),
+            ino: ino,
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqAddOrphan>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("add_orphan: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/file/orphan", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Put, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("add_orphan: failed to add orphan: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("add_orphan: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespOrphan;
+        let ret = json::decode_from_str::<RespOrphan>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("add_orphan: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("add_orphan: failed to add orphan: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(())
+    }
+
+    fn remove_orphan(&self, ino: u64) -> Result<(), Errno> {
+        let req = ReqOrphan {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            zone: self.zone.clone(),
+            machine: self.machine.clone(),
+            ino: ino,
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqOrphan>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("remove_orphan: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/file/orphan/remove", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Post, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("remove_orphan: failed to remove orphan: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("remove_orphan: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespOrphan;
+        let ret = json::decode_from_str::<RespOrphan>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("remove_orphan: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("remove_orphan: failed to remove orphan: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(())
+    }
+
+    fn list_orphans(&self) -> Result<Vec<u64>, Errno> {
+        let req = ReqListOrphans {
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            zone: self.zone.clone(),
+            machine: self.machine.clone(),
+        };
+        let req_body: String;
+        let ret = json::encode_to_str::<ReqListOrphans>(&req);
+        match ret {
+            Ok(ret) => {
+                req_body = ret;
+            }
+            Err(err) => {
+                error!("list_orphans: failed to encode req: {:?}, err: {}", req, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body: String;
+        let url = format!("{}/v1/machine/orphans", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Get, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("list_orphans: failed to list orphans: {}, err status: {}, resp: {}", req_body, text.status, text.body);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("list_orphans: failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+
+        let resp: RespListOrphans;
+        let ret = json::decode_from_str::<RespListOrphans>(&resp_body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("list_orphans: failed to decode from: {}, err: {}", resp_body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        if resp.result.err_code != 0 {
+            error!("list_orphans: failed to list orphans: {}, err_code: {}, err: {}",
+            req_body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+
+        Ok(resp.inos)
+    }
+}
+
+impl MetaServiceMgrImpl {
+    pub fn new(meta_cfg: &Config, exec: &Executor) -> Result<MetaServiceMgrImpl, String> {
+        let http_client = Arc::new(http_client::HttpClient::new(3));
+        Ok(MetaServiceMgrImpl{
+            http_client: http_client,
+            meta_server_url: meta_cfg.metaserver_config.meta_server.clone(),
+            region: meta_cfg.s3_config.region.clone(),
+            bucket: meta_cfg.s3_config.bucket.clone(),
+            zone: meta_cfg.zone_config.zone.clone(),
+            machine: meta_cfg.zone_config.machine.clone(),
+            lease_timeout: meta_cfg.heartbeat_config.lease_timeout,
+            exec: exec.clone(),
+        })
+    }
+
+    // send the request of changing the leader of ino, op is the name of the caller for logging.
+    fn change_file_leader(&self, op: &str, url: &String, body: &String, ino: u64) -> Result<FileLeader, Errno> {
+        let resp_text: RespText;
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(url, &body.as_bytes(), &HttpMethod::Put, false));
+        match ret {
+            Ok(ret) => {
+                resp_text = ret;
+            }
+            Err(err) => {
+                error!("{}: failed to send req: {}, err: {}", op, body, err);
+                return Err(Errno::Etimedout);
+            }
+        }
+        if resp_text.status >= 300 {
+            error!("{}: got status {} for req: {}, resp: {}", op, resp_text.status, body, resp_text.body);
+            return Err(Errno::Eintr);
+        }
+        let resp: RespFileLeader;
+        let ret = json::decode_from_str::<RespFileLeader>(&resp_text.body);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                error!("{}: failed to decode resp: {}, err: {}", op, resp_text.body, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        if resp.result.err_code != 0 {
+            error!("{}: failed to change leader for {}, err_code: {}, err_msg: {}",
+            op, body, resp.result.err_code, resp.result.err_msg);
+            return Err(to_errno(resp.result.err_code));
+        }
+        Ok(FileLeader{
+            zone: resp.leader_info.zone,
+            leader: resp.leader_info.leader,
+            ino: ino,
+            token: resp.leader_info.token,
+            expired: resp.leader_info.expired,
+        })
+    }
+
+    fn to_file_attr(&self, msg_attr: &MsgFileAttr) -> FileAttr {
+        FileAttr {
+            ino: msg_attr.ino,
+            generation: msg_attr.generation,
+            size: msg_attr.size,
+            blocks: msg_attr.blocks,
+            atime: msg_attr.atime,
+            mtime: msg_attr.mtime,
+            ctime: msg_attr.ctime,
+            kind: msg_attr.kind.into(),
+            perm: msg_attr.perm,
+            nlink: msg_attr.nlink,
+            uid: msg_attr.uid,
+            gid: msg_attr.gid,
+            rdev: msg_attr.rdev,
+            flags: msg_attr.flags,
+            parent: msg_attr.parent,
+        }
+    }
+
+    fn read_file_attr(&self, ino: u64) -> Result<MsgFileAttr, Errno> {
+        let req_file_attr = ReqFileAttr{
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            ino: ino,
+        };
+        let ret = json::encode_to_str::<ReqFileAttr>(&req_file_attr);
+        let req_body : String;
+        match ret {
+            Ok(body) => {
+                req_body = body;
+            }
+            Err(error) => {
+                error!("failed to encode req_file_attr: {:?}, err: {}", req_file_attr, error);
+                return Err(Errno::Eintr);
+            }
+        }
+        let resp : RespText;
+        let url = format!("{}/v1/file/attr", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_body.as_bytes(), &HttpMethod::Get, false));
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(error) => {
+                error!("failed to send req to {} with body: {}, err: {}", url, req_body, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+        if resp.status >= 300 {
+            error!("failed to read_file_attr from {}, for ino: {}, err: {}", url, ino, resp.body);
+            return Err(Errno::Eintr);
+        }
+        let resp_attr: RespFileAttr;
+        let ret = json::decode_from_str::<RespFileAttr>(&resp.body);
+        match ret {
+            Ok(ret) => {
+                resp_attr = ret;
+            }
+            Err(error) => {
+                error!("failed to decode {} for read_file_attr of ino: {}, err: {}", resp.body, ino, error);
+                return Err(Errno::Eintr);
+            }
+        }
+        if resp_attr.result.err_code != 0 {
+            error!("failed to read_file_attr for ino: {}, err_code: {}, err_msg: {}",
+            ino, resp_attr.result.err_code, resp_attr.result.err_msg);
+            return Err(to_errno(resp_attr.result.err_code));
+        }
+
+        return Ok(resp_attr.attr);
+    }
+
+    fn read_dir_file_attr(&self, ino: u64, name: &String) -> Result<MsgFileAttr, Errno>{
+        let req_dir_file_attr = ReqDirFileAttr{
+            region: self.region.clone(),
+            bucket: self.bucket.clone(),
+            ino: ino,
+            name: String::from(name),
+        };
+        let ret = json::encode_to_str::<ReqDirFileAttr>(&req_dir_file_attr);
+        let req_child_file_attr_json: String;
+        match ret {
+            Ok(body) => {
+                req_child_file_attr_json = body;
+            }
+            Err(error) => {
+                error!("failed to encode req_dir_file_attr: {:?}, err: {}", req_dir_file_attr, error);
+                return Err(Errno::Eintr);
+            }
+        }
+        let resp_text : RespText;
+        let url = format!("{}/v1/dir/file/attr", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_child_file_attr_json.as_bytes(), &HttpMethod::Get, false));
+        match ret {
+            Ok(resp) => {
+                resp_text = resp;
+            }
+            Err(error) => {
+                error!("failed to send req to {} with body: {}, err: {}", url, req_child_file_attr_json, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+        if resp_text.status >= 300 {
+            error!("failed to get child file attr from url {}, err: {}", url, resp_text.body);
+            return Err(Errno::Eintr);
+        }
+        let resp_attr : RespDirFileAttr;
+        let ret = json::decode_from_str::<RespDirFileAttr>(&resp_text.body);
+        match ret {
+            Ok(attr) => {
+                resp_attr = attr;
+            }
+            Err(error) => {
+                error!("failed to decode {} for child file attr of ino: {}, name: {}, err: {}", resp_text.body, ino, name, error);
+                return Err(Errno::Eintr);
+            }
+        }
+        if resp_attr.result.err_code != 0 {
+            // lookup of a non-existing name is common, don't log it as error.
+            if resp_attr.result.err_code != ERR_YIGFS_NO_SUCH_FILE {
+                error!("failed to get child file attrs for ino: {}, name: {}, err_code: {}, err: {}",
+                ino, name, resp_attr.result.err_code, resp_attr.result.err_msg);
+            }
+            return Err(to_errno(resp_attr.result.err_code));
+        }
+        return Ok(resp_attr.attr);
+    }
+
+    // list a page of the files after offset, the end of the dir is returned as an empty page.
+    fn read_dir_page(&self, ino: u64, offset: i64) -> Result<Vec<RespDirEntry>, Errno>{
+        let ret = self.read_dir_files(ino, offset);
+        match ret {
+            Ok(dirs) => {
+                if dirs.result.err_code != 0 {
+                    if dirs.result.err_code == ERR_YIGFS_NOT_FIND_TARGET_DIR_FILES {
+                        info!("read_dir_page: no more files in dir: {} after offset: {}", ino, offset);
+                        return Ok(Vec::new());
+                    }
+                    error!("got error when read_dir_files for ino: {}, offset: {}, err: {}",
+                    ino, offset, dirs.result.err_msg);
+                    return Err(to_errno(dirs.result.err_code));
+                }
+                return Ok(dirs.files);
+            }
+            Err(error) => {
+                error!("failed to read meta for ino: {}, offset: {}, err: {:?}",
+                ino, offset, error);
+                return Err(error);
+            }
+        }
+    }
+
+    fn to_dir_entry(&self, f: &RespDirEntry) -> DirEntry {
+        DirEntry{
+            ino: f.ino,
+            file_type: f.dir_entry_type.into(),
+            name: f.name.clone(),
+            // the meta server lists the files whose ino is larger than the offset in the order of ino.
+            offset: f.ino as i64,
+        }
+    }
+
+    fn read_dir_files(&self, ino: u64, offset: i64) -> Result<Box<RespReadDir>, Errno>{
+        let req_read_dir = ReqReadDir{
+            region: self.region.clone(),
+            bucket:self.bucket.clone(),
+            ino: ino,
+            offset: offset,
+        };
+        let ret = serde_json::to_string(&req_read_dir);
+        let req_read_dir_json: String;
+        match ret {
+            Ok(ret) => {
+                //send the req to meta server
+                req_read_dir_json = ret;
+            }
+            Err(error) => {
+                error!("faied to convert {:?} to json, err: {}", req_read_dir, error);
+                return Err(Errno::Eintr);
+            }
+        }
+
+        let resp_body :String;
+        let url = format!("{}/v1/dir/files", self.meta_server_url);
+        let ret = self.exec.get_runtime().block_on(self.http_client.request(&url, &req_read_dir_json.as_bytes(), &HttpMethod::Get, false));
+        match ret {
+            Ok(text) => {
+                if text.status >= 300 {
+                    error!("failed to read dir from {}, got resp {}", url, text.status);
+                    return Err(Errno::Eintr);
+                }
+                resp_body = text.body;
+            }
+            Err(error) => {
+                error!("failed to get response for {}, err: {}", url, error);
+                return Err(Errno::Etimedout);
+            }
+        }
+        
+        let resp_read_dir = json::decode_from_str::<RespReadDir>(&resp_body);
+        match resp_read_dir {
+            Ok(resp_read_dir) => {
+                return Ok(Box::new(resp_read_dir));
+            }
+            Err(error) => {
+                error!("failed to decode from {}, err: {}", resp_body, error);
+                return Err(Errno::Eintr);
             }
         }
     }
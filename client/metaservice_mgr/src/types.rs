@@ -8,6 +8,10 @@ pub enum FileType {
     FILE = 1,
     DIR = 2,
     LINK = 3,
+    FIFO = 4,
+    SOCKET = 5,
+    CHAR = 6,
+    BLOCK = 7,
 }
 
 impl From<u8> for FileType {
@@ -22,6 +26,18 @@ impl From<u8> for FileType {
             3 => {
                 FileType::LINK
             }
+            4 => {
+                FileType::FIFO
+            }
+            5 => {
+                FileType::SOCKET
+            }
+            6 => {
+                FileType::CHAR
+            }
+            7 => {
+                FileType::BLOCK
+            }
             _ => {
                 FileType::UNKNOWN
             }
@@ -41,6 +57,18 @@ impl FileType {
             FileType::LINK => {
                 3
             }
+            FileType::FIFO => {
+                4
+            }
+            FileType::SOCKET => {
+                5
+            }
+            FileType::CHAR => {
+                6
+            }
+            FileType::BLOCK => {
+                7
+            }
             FileType::UNKNOWN => {
                 0
             }
@@ -53,6 +81,19 @@ pub struct DirEntry{
     pub ino: u64,
     pub file_type: FileType,
     pub name: String,
+    // opaque cursor to list the entries after this one, it increases in the listing order.
+    pub offset: i64,
+}
+
+impl DirEntry {
+    pub fn copy(&self) -> Self {
+        DirEntry{
+            ino: self.ino,
+            file_type: self.file_type,
+            name: self.name.clone(),
+            offset: self.offset,
+        }
+    }
 }
 
 #[derive (Debug)]
@@ -85,6 +126,38 @@ pub struct FileAttr {
     pub rdev: u32,
     /// Flags (macOS only, see chflags(2))
     pub flags: u32,
+    /// Parent dir ino, 0 if unknown.
+    pub parent: u64,
+}
+
+impl FileAttr {
+    pub fn copy(&self) -> Self {
+        FileAttr{
+            ino: self.ino,
+            generation: self.generation,
+            size: self.size,
+            blocks: self.blocks,
+            atime: self.atime,
+            mtime: self.mtime,
+            ctime: self.ctime,
+            kind: self.kind,
+            perm: self.perm,
+            nlink: self.nlink,
+            uid: self.uid,
+            gid: self.gid,
+            rdev: self.rdev,
+            flags: self.flags,
+            parent: self.parent,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FsUsage {
+    /// Total bytes of the files in the namespace
+    pub used_size: u64,
+    /// Number of inodes in the namespace
+    pub file_count: u64,
 }
 
 #[derive(Debug)]
@@ -92,6 +165,10 @@ pub struct FileLeader {
     pub zone: String,
     pub leader: String,
     pub ino: u64,
+    // the fencing token of the leadership, it increases on every change of the leader.
+    pub token: u64,
+    // the leader missed its heartbeats longer than its lease, so that it can be taken over.
+    pub expired: bool,
 }
 #[derive(Debug)]
 pub struct NewFileInfo {
@@ -280,5 +357,7 @@ pub struct HeartbeatRemoveSeg{
 pub struct HeartbeatResult{
     pub upload_segments: Vec<HeartbeatUploadSeg>,
     pub remove_segments: Vec<HeartbeatRemoveSeg>,
+    // the inos changed by the other machines, their cached meta should be dropped.
+    pub invalid_inos: Vec<u64>,
 }
 
@@ -0,0 +1,349 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, AtomicU32, Ordering};
+use std::thread;
+use crossbeam_channel::{Sender, Receiver, bounded};
+use common::config::MetaCacheConfig;
+use common::error::Errno;
+use metaservice_mgr::mgr::MetaServiceMgr;
+use metaservice_mgr::meta_cache::CachedMetaServiceMgr;
+use metaservice_mgr::types::{DirEntry, FileLeader, NewFileInfo, SetFileAttr,
+    Segment, HeartbeatResult, FsUsage, FileAttr, FileType};
+
+const DIR_INO: u64 = 1;
+const FILE_INO: u64 = 2;
+const FILE_NAME: &str = "file";
+
+// meta server with one dir and one file in it, it counts the requests which reach it.
+struct FakeMetaServiceMgr {
+    size: AtomicU64,
+    exists: Mutex<bool>,
+    attr_reads: AtomicU32,
+    lookups: AtomicU32,
+    // the read_file_attr notifies the first one and waits for the second one if it is set.
+    gate: Mutex<Option<(Sender<u8>, Receiver<u8>)>>,
+}
+
+impl FakeMetaServiceMgr {
+    fn new() -> Self {
+        FakeMetaServiceMgr{
+            size: AtomicU64::new(0),
+            exists: Mutex::new(true),
+            attr_reads: AtomicU32::new(0),
+            lookups: AtomicU32::new(0),
+            gate: Mutex::new(None),
+        }
+    }
+
+    fn attr(&self, ino: u64) -> FileAttr {
+        let kind: FileType;
+        let parent: u64;
+        if ino == DIR_INO {
+            kind = FileType::DIR;
+            parent = DIR_INO;
+        } else {
+            kind = FileType::FILE;
+            parent = DIR_INO;
+        }
+        FileAttr{
+            ino: ino,
+            generation: 0,
+            size: self.size.load(Ordering::SeqCst),
+            blocks: 0,
+            atime: 0,
+            mtime: 0,
+            ctime: 0,
+            kind: kind,
+            perm: 0o644,
+            nlink: 1,
+            uid: 0,
+            gid: 0,
+            rdev: 0,
+            flags: 0,
+            parent: parent,
+        }
+    }
+}
+
+impl MetaServiceMgr for FakeMetaServiceMgr {
+    fn mount(&self, _uid: u32, _gid: u32) -> Result<(), Errno> {
+        Ok(())
+    }
+
+    fn read_dir(&self, _ino: u64, _offset: i64)->Result<Vec<DirEntry>, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn read_dir_file_attr(&self, ino: u64, name: &String) -> Result<FileAttr, Errno> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        if ino != DIR_INO || name != FILE_NAME || !*self.exists.lock().unwrap() {
+            return Err(Errno::Enoent);
+        }
+        Ok(self.attr(FILE_INO))
+    }
+
+    fn read_file_attr(&self, ino: u64) -> Result<FileAttr, Errno> {
+        self.attr_reads.fetch_add(1, Ordering::SeqCst);
+        // take the attr before waiting, so that it is stale once the size is changed meanwhile.
+        let attr = self.attr(ino);
+        let gate = self.gate.lock().unwrap().take();
+        if let Some((entered, proceed)) = gate {
+            entered.send(1).unwrap();
+            proceed.recv().unwrap();
+        }
+        Ok(attr)
+    }
+
+    fn set_file_attr(&self, attr: &SetFileAttr) -> Result<FileAttr, Errno> {
+        if let Some(size) = attr.size {
+            self.size.store(size, Ordering::SeqCst);
+        }
+        Ok(self.attr(attr.ino))
+    }
+
+    fn new_ino_leader(&self, _parent: u64, _name: &String, _uid: u32, _gid: u32, _perm: u32, _file_type: u8, _rdev: u32) -> Result<NewFileInfo, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn get_file_leader(&self, _ino: u64) -> Result<FileLeader, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn transfer_leader(&self, _ino: u64, _token: u64) -> Result<FileLeader, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn migrate_leader(&self, _ino: u64, _machine: &String, _token: u64) -> Result<FileLeader, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn get_file_segments(&self, _ino: u64, _offset: Option<u64>, _size: Option<i64>) -> Result<Vec<Segment>, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn get_machine_id(&self) -> String {
+        String::from("fake")
+    }
+
+    fn add_file_block(&self, _ino: u64, _seg: &Segment) -> Errno {
+        self.size.fetch_add(4096, Ordering::SeqCst);
+        Errno::Esucc
+    }
+
+    fn update_file_segments(&self, _ino: u64, _token: u64, _segs: &Vec<Segment>, _removed_segs: &Vec<Segment>) -> Errno {
+        Errno::Enotsupp
+    }
+
+    fn upload_segment(&self, _id0: u64, _id1: u64, _next_offset: u64) -> Errno {
+        Errno::Enotsupp
+    }
+
+    fn heartbeat(&self)-> Result<HeartbeatResult, Errno> {
+        Ok(HeartbeatResult::default())
+    }
+
+    fn delete_file(&self, _ino: u64) -> Errno {
+        Errno::Enotsupp
+    }
+
+    fn rename(&self, _parent: u64, _name: &String, _new_parent: u64, _new_name: &String) -> Result<Option<FileAttr>, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn delete_dir(&self, _parent: u64, _name: &String) -> Result<FileAttr, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn create_symlink(&self, _parent: u64, _name: &String, _uid: u32, _gid: u32, _target: &String) -> Result<FileAttr, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn read_symlink(&self, _ino: u64) -> Result<String, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn unlink_file(&self, _parent: u64, _name: &String, _ino: u64) -> Errno {
+        *self.exists.lock().unwrap() = false;
+        Errno::Esucc
+    }
+
+    fn get_fs_usage(&self) -> Result<FsUsage, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn set_xattr(&self, _ino: u64, _name: &String, _value: &[u8], _flags: i32) -> Result<(), Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn get_xattr(&self, _ino: u64, _name: &String) -> Result<Vec<u8>, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn list_xattr(&self, _ino: u64) -> Result<Vec<String>, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn remove_xattr(&self, _ino: u64, _name: &String) -> Result<(), Errno> {
+        Err(Errno::Enotsupp)
+    }
+
+    fn add_orphan(&self, _parent: u64, _name: &String, _ino: u64) -> Result<(), Errno> {
+        Ok(())
+    }
+
+    fn remove_orphan(&self, _ino: u64) -> Result<(), Errno> {
+        Ok(())
+    }
+
+    fn list_orphans(&self) -> Result<Vec<u64>, Errno> {
+        Ok(Vec::new())
+    }
+}
+
+fn new_cache(fake: &Arc<FakeMetaServiceMgr>) -> CachedMetaServiceMgr {
+    let cfg = MetaCacheConfig{
+        attr_ttl_ms: 60000,
+        entry_ttl_ms: 60000,
+        negative_ttl_ms: 60000,
+        dir_ttl_ms: 60000,
+        capacity: 100,
+    };
+    CachedMetaServiceMgr::new(fake.clone(), &cfg)
+}
+
+fn read_size(cache: &CachedMetaServiceMgr, ino: u64) -> Result<u64, String> {
+    let ret = cache.read_file_attr(ino);
+    match ret {
+        Ok(ret) => {
+            return Ok(ret.size);
+        }
+        Err(err) => {
+            return Err(format!("failed to read attr of ino: {}, err: {:?}", ino, err));
+        }
+    }
+}
+
+#[test]
+fn test_meta_cache_attr_hit() -> Result<(), String> {
+    let fake = Arc::new(FakeMetaServiceMgr::new());
+    let cache = new_cache(&fake);
+    read_size(&cache, FILE_INO)?;
+    read_size(&cache, FILE_INO)?;
+    let reads = fake.attr_reads.load(Ordering::SeqCst);
+    if reads != 1 {
+        return Err(format!("got {} attr reads from meta server, expect 1", reads));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_meta_cache_attr_invalidate() -> Result<(), String> {
+    let fake = Arc::new(FakeMetaServiceMgr::new());
+    let cache = new_cache(&fake);
+    read_size(&cache, FILE_INO)?;
+    let seg = Segment::new(&String::from("fake"));
+    let ret = cache.add_file_block(FILE_INO, &seg);
+    if !ret.is_success() {
+        return Err(format!("failed to add file block, err: {:?}", ret));
+    }
+    let size = read_size(&cache, FILE_INO)?;
+    if size != 4096 {
+        return Err(format!("got size: {} after add_file_block, expect 4096", size));
+    }
+    cache.invalidate_inos(&vec![FILE_INO]);
+    read_size(&cache, FILE_INO)?;
+    let reads = fake.attr_reads.load(Ordering::SeqCst);
+    if reads != 3 {
+        return Err(format!("got {} attr reads from meta server, expect 3", reads));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_meta_cache_stale_attr_dropped() -> Result<(), String> {
+    let fake = Arc::new(FakeMetaServiceMgr::new());
+    let cache = Arc::new(new_cache(&fake));
+    let (entered_tx, entered_rx) = bounded::<u8>(1);
+    let (proceed_tx, proceed_rx) = bounded::<u8>(1);
+    *fake.gate.lock().unwrap() = Some((entered_tx, proceed_rx));
+    let reader_cache = cache.clone();
+    let reader = thread::spawn(move || {
+        read_size(&reader_cache, FILE_INO)
+    });
+    // the size is changed while the reader is fetching the attr with size 0.
+    entered_rx.recv().unwrap();
+    let seg = Segment::new(&String::from("fake"));
+    let ret = cache.add_file_block(FILE_INO, &seg);
+    if !ret.is_success() {
+        return Err(format!("failed to add file block, err: {:?}", ret));
+    }
+    proceed_tx.send(1).unwrap();
+    let size = reader.join().unwrap()?;
+    if size != 0 {
+        return Err(format!("the reader got size: {}, expect 0", size));
+    }
+    let size = read_size(&cache, FILE_INO)?;
+    if size != 4096 {
+        return Err(format!("got stale size: {} from cache, expect 4096", size));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_meta_cache_lookup() -> Result<(), String> {
+    let fake = Arc::new(FakeMetaServiceMgr::new());
+    let cache = new_cache(&fake);
+    let name = String::from(FILE_NAME);
+    for _ in 0..2 {
+        let ret = cache.read_dir_file_attr(DIR_INO, &name);
+        match ret {
+            Ok(ret) => {
+                if ret.ino != FILE_INO {
+                    return Err(format!("got ino: {} for {}, expect {}", ret.ino, name, FILE_INO));
+                }
+            }
+            Err(err) => {
+                return Err(format!("failed to lookup {}, err: {:?}", name, err));
+            }
+        }
+    }
+    let lookups = fake.lookups.load(Ordering::SeqCst);
+    if lookups != 1 {
+        return Err(format!("got {} lookups from meta server, expect 1", lookups));
+    }
+    // the file attr is cached by the lookup.
+    read_size(&cache, FILE_INO)?;
+    let reads = fake.attr_reads.load(Ordering::SeqCst);
+    if reads != 0 {
+        return Err(format!("got {} attr reads from meta server, expect 0", reads));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_meta_cache_negative_lookup() -> Result<(), String> {
+    let fake = Arc::new(FakeMetaServiceMgr::new());
+    let cache = new_cache(&fake);
+    let name = String::from(FILE_NAME);
+    let ret = cache.unlink_file(DIR_INO, &name, FILE_INO);
+    if !ret.is_success() {
+        return Err(format!("failed to unlink {}, err: {:?}", name, ret));
+    }
+    for _ in 0..2 {
+        let ret = cache.read_dir_file_attr(DIR_INO, &name);
+        match ret {
+            Ok(ret) => {
+                return Err(format!("got ino: {} for the unlinked {}", ret.ino, name));
+            }
+            Err(err) => {
+                if !err.is_enoent() {
+                    return Err(format!("failed to lookup {}, err: {:?}", name, err));
+                }
+            }
+        }
+    }
+    let lookups = fake.lookups.load(Ordering::SeqCst);
+    if lookups != 1 {
+        return Err(format!("got {} lookups from meta server, expect 1", lookups));
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 extern crate crossbeam_channel;
 
 use std::collections::HashMap;
+use std::sync::Mutex;
 use std::thread;
 use std::thread::JoinHandle;
 use common::numbers::NumberOp;
@@ -14,46 +15,61 @@ use crate::types::MsgGetFileSegments;
 use crate::types::MsgOpenHandle;
 use crate::types::MsgSetSegStatus;
 use crate::types::SegStatus;
-use crate::types::{FileHandle, MsgAddBlock, MsgAddSegment, MsgFileHandleOp, MsgGetLastSegment, MsgQueryHandle};
+use crate::types::MsgTruncate;
+use crate::types::{MsgGetFileSize, MsgGetLeaderToken};
+use crate::types::{FileHandle, FileHandleData, MsgAddBlock, MsgAddSegment, MsgFileHandleOp, MsgGetLastSegment, MsgQueryHandle};
 use log::{warn, error};
 
 pub struct FileHandleMgr {
     //for update file handle.
     handle_op_tx: Sender<MsgFileHandleOp>,
     stop_tx: Sender<u32>,
-    handle_mgr_th: Option<JoinHandle<()>>,
+    // taken by the first stop, so that the mgr can be stopped through the shared references.
+    handle_mgr_th: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl FileHandleMgr {
     pub fn create() -> FileHandleMgr {
         let (tx, rx) = bounded::<MsgFileHandleOp>(100);
         let (stop_tx, stop_rx) = bounded::<u32>(1);
-        
-        let mut handle_mgr = HandleMgr{
-            handles: HashMap::<u64, FileHandle>::new(),
-            handle_op_rx: rx,
-            stop_rx: stop_rx,
-        };
 
         let mgr = FileHandleMgr{
             handle_op_tx: tx,
             stop_tx: stop_tx,
-            handle_mgr_th: Some(thread::spawn(move|| handle_mgr.start())),
+            // the handles are created in the HandleMgr thread and never leave it.
+            handle_mgr_th: Mutex::new(Some(thread::spawn(move|| {
+                let mut handle_mgr = HandleMgr{
+                    handles: HashMap::<u64, FileHandle>::new(),
+                    handle_op_rx: rx,
+                    stop_rx: stop_rx,
+                };
+                handle_mgr.start();
+            }))),
         };
 
         return mgr;
     }
 
-    pub fn stop(&mut self){
-        let ret = self.stop_tx.send(1);
-        match ret {
-            Ok(_) => {}
+    pub fn stop(&self){
+        let th: Option<JoinHandle<()>>;
+        match self.handle_mgr_th.lock() {
+            Ok(mut ret) => {
+                th = ret.take();
+            }
             Err(err) => {
                 error!("failed to stop file handle mgr, err: {}", err);
+                return;
             }
         }
         // join the HandleMgr thread.
-        if let Some(h) = self.handle_mgr_th.take() {
+        if let Some(h) = th {
+            let ret = self.stop_tx.send(1);
+            match ret {
+                Ok(_) => {}
+                Err(err) => {
+                    error!("failed to stop file handle mgr, err: {}", err);
+                }
+            }
             let ret = h.join();
             match ret {
                 Ok(_) => {
@@ -69,7 +85,7 @@ impl FileHandleMgr {
     }
 
     pub fn add(&self, handle: &FileHandle) -> Errno {
-        let msg = MsgFileHandleOp::Add(handle.copy());
+        let msg = MsgFileHandleOp::Add(handle.to_data());
         let ret = self.handle_op_tx.send(msg);
         match ret {
             Ok(_) => {
@@ -136,6 +152,37 @@ impl FileHandleMgr {
         }
     }
 
+    // cut the blocks beyond size, returns the changed segments and the removed blocks as garbages.
+    pub fn truncate(&self, ino: u64, size: u64) -> (HashMap<u128, Segment>, HashMap<u128, Segment>, Errno) {
+        let segs: HashMap<u128, Segment> = HashMap::new();
+        let garbages: HashMap<u128, Segment> = HashMap::new();
+        let (tx, rx) = bounded::<ChangedSegments>(1);
+        let msg = MsgFileHandleOp::Truncate(MsgTruncate{
+            ino: ino,
+            size: size,
+            tx: tx,
+        });
+        let ret = self.handle_op_tx.send(msg);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("truncate: failed to send truncate msg for ino: {}, size: {}, err: {}", ino, size, err);
+                return (segs, garbages, Errno::Eintr);
+            }
+        }
+
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return (ret.segments, ret.garbages, Errno::Esucc);
+            }
+            Err(err) => {
+                error!("truncate: failed to get changed blocks for ino: {}, size: {}, err: {}", ino, size, err);
+                return (segs, garbages, Errno::Eintr);
+            }
+        }
+    }
+
     pub fn del(&self, ino: u64) -> Errno {
         let msg = MsgFileHandleOp::Del(ino);
         let ret = self.handle_op_tx.send(msg);
@@ -150,6 +197,37 @@ impl FileHandleMgr {
         }
     }
 
+    // replace the segments and blocks of the opened ino with the ones in handle, such as the newer ones
+    // got from meta server. nothing is done if ino is not opened.
+    pub fn refresh(&self, handle: &FileHandle) -> Errno {
+        let msg = MsgFileHandleOp::Refresh(handle.to_data());
+        let ret = self.handle_op_tx.send(msg);
+        match ret {
+            Ok(_) => {
+                return Errno::Esucc;
+            }
+            Err(err) => {
+                error!("failed to refresh handle for ino: {}, err: {}", handle.ino, err);
+                return Errno::Eintr;
+            }
+        }
+    }
+
+    // drop the handle of ino even if it is opened more than once, such as when its leadership is moved away.
+    pub fn remove(&self, ino: u64) -> Errno {
+        let msg = MsgFileHandleOp::Remove(ino);
+        let ret = self.handle_op_tx.send(msg);
+        match ret {
+            Ok(_) => {
+                return Errno::Esucc;
+            }
+            Err(err) => {
+                error!("failed to remove handle for ino: {}, err: {}", ino, err);
+                return Errno::Eintr;
+            }
+        }
+    }
+
     // Vec[0]: id0; Vec[1]: id1; Vec[2]: max_size of segment.
     pub fn get_last_segment(&self, ino: u64) -> Result<Vec<u64>, Errno> {
         let (tx, rx) = bounded::<Vec<u64>>(1);
@@ -183,7 +261,7 @@ impl FileHandleMgr {
     }
 
     pub fn get(&self, ino: u64) -> Result<FileHandle, Errno>{
-        let (tx, rx) = bounded::<Option<FileHandle>>(1);
+        let (tx, rx) = bounded::<Option<FileHandleData>>(1);
         let query = MsgQueryHandle{
             ino: ino,
             tx: tx,
@@ -201,7 +279,7 @@ impl FileHandleMgr {
                     Ok(ret) => {
                         match ret {
                             Some(h) => {
-                                return Ok(h);
+                                return Ok(FileHandle::from_data(h));
                             }
                             None => {
                                 return Err(Errno::Enoent);
@@ -221,10 +299,12 @@ impl FileHandleMgr {
         }
     }
 
-    pub fn open_handle(&self, ino: u64) -> Result<String, Errno>{
+    // add a reference to the handle of ino if it is opened, token is the fencing token of this open.
+    pub fn open_handle(&self, ino: u64, token: u64) -> Result<String, Errno>{
         let (tx, rx) = bounded::<String>(1);
         let query = MsgOpenHandle{
             ino: ino,
+            token: token,
             tx: tx,
         };
         defer!{
@@ -307,6 +387,96 @@ impl FileHandleMgr {
         }
     }
 
+    // get the fencing token of the leadership of ino.
+    pub fn get_leader_token(&self, ino: u64) -> Result<u64, Errno> {
+        let (tx, rx) = bounded::<Option<u64>>(1);
+        let msg = MsgFileHandleOp::GetLeaderToken(MsgGetLeaderToken{
+            ino: ino,
+            tx: tx,
+        });
+        let ret = self.handle_op_tx.send(msg);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_leader_token: failed to send query for ino: {}, err: {}", ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                match ret {
+                    Some(token) => {
+                        return Ok(token);
+                    }
+                    None => {
+                        return Err(Errno::Enoent);
+                    }
+                }
+            }
+            Err(err) => {
+                error!("get_leader_token: failed to recv token for ino: {}, err: {}", ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
+    pub fn get_file_size(&self, ino: u64) -> Result<u64, Errno> {
+        let (tx, rx) = bounded::<Option<u64>>(1);
+        let msg = MsgFileHandleOp::GetFileSize(MsgGetFileSize{
+            ino: ino,
+            tx: tx,
+        });
+        let ret = self.handle_op_tx.send(msg);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_file_size: failed to send query for ino: {}, err: {}", ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                match ret {
+                    Some(size) => {
+                        return Ok(size);
+                    }
+                    None => {
+                        return Err(Errno::Enoent);
+                    }
+                }
+            }
+            Err(err) => {
+                error!("get_file_size: failed to recv file size for ino: {}, err: {}", ino, err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
+    // the inos of all the opened files.
+    pub fn get_inos(&self) -> Result<Vec<u64>, Errno> {
+        let (tx, rx) = bounded::<Vec<u64>>(1);
+        let ret = self.handle_op_tx.send(MsgFileHandleOp::GetInos(tx));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_inos: failed to send query, err: {}", err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return Ok(ret);
+            }
+            Err(err) => {
+                error!("get_inos: failed to recv inos, err: {}", err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
     pub fn set_seg_status(&self, ino: u64, id0: u64, id1: u64, need_sync: bool) -> Errno {
         let msg = MsgFileHandleOp::SetSegStatus(MsgSetSegStatus{
             ino: ino,
@@ -361,8 +531,6 @@ struct HandleMgr {
     stop_rx: Receiver<u32>,
 }
 
-unsafe impl Send for HandleMgr {}
-
 impl HandleMgr {
     pub fn start(&mut self) {
         loop {
@@ -391,6 +559,12 @@ impl HandleMgr {
                         MsgFileHandleOp::Del(ino) => {
                             self.del(ino);
                         }
+                        MsgFileHandleOp::Remove(ino) => {
+                            self.remove(ino);
+                        }
+                        MsgFileHandleOp::Refresh(h) => {
+                            self.refresh(h);
+                        }
                         MsgFileHandleOp::Get(m) => {
                             self.get(m);
                         }
@@ -410,6 +584,18 @@ impl HandleMgr {
                         MsgFileHandleOp::GetFileSegments(m) => {
                             self.get_file_segments(m);
                         }
+                        MsgFileHandleOp::Truncate(m) => {
+                            self.truncate(&m);
+                        }
+                        MsgFileHandleOp::GetFileSize(m) => {
+                            self.get_file_size(m);
+                        }
+                        MsgFileHandleOp::GetLeaderToken(m) => {
+                            self.get_leader_token(m);
+                        }
+                        MsgFileHandleOp::GetInos(tx) => {
+                            self.get_inos(tx);
+                        }
                     }
                 },
                 recv(self.stop_rx) -> msg => {
@@ -432,12 +618,12 @@ impl HandleMgr {
         }
     }
 
-    fn add(&mut self, handle: FileHandle) {
+    fn add(&mut self, handle: FileHandleData) {
         if let Some(h) = self.handles.get_mut(&handle.ino) {
             h.reference += 1;
             return;
         }
-        self.handles.insert(handle.ino, handle);
+        self.handles.insert(handle.ino, FileHandle::from_data(handle));
     }
 
     fn add_segment(&mut self, msg: &MsgAddSegment) {
@@ -459,6 +645,9 @@ impl HandleMgr {
         if let Some(h) = self.handles.get_mut(&msg.ino) {
             let start = msg.block.offset;
             let end = msg.block.offset + msg.block.size as u64;
+            if end > h.size {
+                h.size = end;
+            }
 
             //check the sequence write
             let last_node = h.block_tree.get_largest_node();
@@ -545,6 +734,31 @@ impl HandleMgr {
         }
     }
 
+    fn truncate(&mut self, msg: &MsgTruncate) {
+        let mut changed_segs = ChangedSegments{
+            segments: HashMap::new(),
+            garbages: HashMap::new(),
+        };
+        if let Some(h) = self.handles.get_mut(&msg.ino) {
+            h.size = msg.size;
+            // the blocks don't overlap, so the largest node has the largest end.
+            // if it ends before size, nothing to cut and the extended range is a hole.
+            let last_node = h.block_tree.get_largest_node();
+            if last_node.borrow().is_nil() {
+                msg.response(changed_segs);
+                return;
+            }
+            let last_block = last_node.borrow().get_value();
+            if last_block.offset + last_block.size as u64 <= msg.size {
+                msg.response(changed_segs);
+                return;
+            }
+            h.cut_blocks(msg.size, u64::MAX, &mut changed_segs);
+            h.mark_dirty();
+        }
+        msg.response(changed_segs);
+    }
+
     fn del(&mut self, ino: u64) {
         // free the block_tree
         if let Some(h) = self.handles.get_mut(&ino) {
@@ -555,6 +769,21 @@ impl HandleMgr {
             }
         }
     }
+
+    fn remove(&mut self, ino: u64) {
+        if let Some(mut h) = self.handles.remove(&ino) {
+            h.block_tree.free();
+        }
+    }
+
+    fn refresh(&mut self, handle: FileHandleData) {
+        if let Some(h) = self.handles.get_mut(&handle.ino) {
+            let reference = h.reference;
+            h.block_tree.free();
+            *h = FileHandle::from_data(handle);
+            h.reference = reference;
+        }
+    }
     
     fn get_last_segment(&self, msg: &MsgGetLastSegment) {
         let mut v : Vec<u64> = Vec::new();
@@ -605,13 +834,13 @@ impl HandleMgr {
     }
 
     fn get(&mut self, msg: MsgQueryHandle){
-        let mut handle: Option<FileHandle> = None;
+        let mut handle: Option<FileHandleData> = None;
         let tx = msg.tx.clone();
         defer!{
             drop(tx);
         };
         if let Some(h) = self.handles.get(&msg.ino) {
-            handle = Some(h.copy());
+            handle = Some(h.to_data());
         }
         let ret = msg.tx.send(handle);
         match ret {
@@ -630,6 +859,9 @@ impl HandleMgr {
         };
         if let Some(h) = self.handles.get_mut(&msg.ino) {
             h.reference += 1;
+            if msg.token > h.leader_token {
+                h.leader_token = msg.token;
+            }
             leader = h.leader.clone();
         } else {
             leader = String::from("");
@@ -694,4 +926,43 @@ impl HandleMgr {
             }
         }
     }
-}
\ No newline at end of file
+
+    fn get_file_size(&self, m: MsgGetFileSize) {
+        let mut size: Option<u64> = None;
+        if let Some(h) = self.handles.get(&m.ino) {
+            size = Some(h.size);
+        }
+        let ret = m.tx.send(size);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_file_size: failed to send file size for ino: {}, err: {}", m.ino, err);
+            }
+        }
+    }
+
+    fn get_leader_token(&self, m: MsgGetLeaderToken) {
+        let mut token: Option<u64> = None;
+        if let Some(h) = self.handles.get(&m.ino) {
+            token = Some(h.leader_token);
+        }
+        let ret = m.tx.send(token);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_leader_token: failed to send token for ino: {}, err: {}", m.ino, err);
+            }
+        }
+    }
+
+    fn get_inos(&self, tx: Sender<Vec<u64>>) {
+        let inos: Vec<u64> = self.handles.keys().cloned().collect();
+        let ret = tx.send(inos);
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("get_inos: failed to send inos, err: {}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+use std::time::Instant;
+use common::error::Errno;
+use metaservice_mgr::types::Block;
+use io_engine::cache_store::CacheStore;
+use io_engine::backend_storage::BackendStore;
+use log::{info, warn, error};
+use crate::file_handle::FileHandleMgr;
+
+// read [offset, offset+size) of the opened ino by its blocks in handle_mgr, read_block reads the data of
+// block b from seg_offset. the holes are filled with zeros and the read stops at the end of file.
+pub fn read_file<F>(handle_mgr: &FileHandleMgr, ino: u64, offset: u64, size: u32, read_block: F) -> Result<Vec<u8>, Errno>
+where F: Fn(&Block, u64, u32) -> Result<Vec<u8>, Errno> {
+    let file_size: u64;
+    let ret = handle_mgr.get_file_size(ino);
+    match ret {
+        Ok(ret) => {
+            file_size = ret;
+        }
+        Err(err) => {
+            error!("read: failed to get file size for ino: {}, err: {:?}", ino, err);
+            return Err(err);
+        }
+    }
+    let mut data = Vec::<u8>::new();
+    // stop at the end of file.
+    if offset >= file_size {
+        return Ok(data);
+    }
+    let mut end = offset + size as u64;
+    if end > file_size {
+        end = file_size;
+    }
+    let mut start = offset;
+    let begin = Instant::now();
+    let blocks = handle_mgr.get_blocks(ino, offset, end - offset);
+    let dur = begin.elapsed().as_nanos();
+    info!("read: ino: {}, offset: {}, size: {}, get_blocks takes: {}", ino, offset, size, dur);
+    for b in &blocks {
+        let block_end = b.offset + b.size as u64;
+        if block_end <= start {
+            continue;
+        }
+        if b.offset >= end {
+            break;
+        }
+        // the gap before this block is a hole.
+        if b.offset > start {
+            data.resize(data.len() + (b.offset - start) as usize, 0);
+            start = b.offset;
+        }
+        let mut to_read = end - start;
+        if block_end < end {
+            to_read = block_end - start;
+        }
+        let seg_offset = b.seg_start_addr + start - b.offset;
+        let ret = read_block(b, seg_offset, to_read as u32);
+        match ret {
+            Ok(mut d) => {
+                if d.len() as u64 > to_read {
+                    d.truncate(to_read as usize);
+                }
+                if (d.len() as u64) < to_read {
+                    warn!("read: ino: {}, got {} bytes for seg(id0: {}, id1: {}) offset: {}, expect: {}",
+                    ino, d.len(), b.seg_id0, b.seg_id1, seg_offset, to_read);
+                    d.resize(to_read as usize, 0);
+                }
+                data.extend(d);
+                start += to_read;
+            }
+            Err(err) => {
+                error!("read: failed to read for ino: {}, offset: {}, start: {}, size: {}, err: {:?}",
+                ino, offset, start, to_read, err);
+                return Err(err);
+            }
+        }
+        if start >= end {
+            return Ok(data);
+        }
+    }
+    // the tail up to the end is a hole.
+    if start < end {
+        data.resize(data.len() + (end - start) as usize, 0);
+    }
+
+    return Ok(data);
+}
+
+// read the data of block b from seg_offset in the segment file under seg_dir.
+// it is read from the backend store if cache_store is None or the cache doesn't have it.
+pub fn read_block(cache_store: Option<&Arc<dyn CacheStore>>, backend_store: &Arc<dyn BackendStore>, seg_dir: &String,
+    ino: u64, b: &Block, seg_offset: u64, size: u32) -> Result<Vec<u8>, Errno> {
+    let mut data = Vec::<u8>::new();
+    let mut offset = seg_offset;
+    let mut total_read = size;
+    if total_read == 0 {
+        return Ok(data);
+    }
+    if let Some(cache_store) = cache_store {
+        while total_read > 0 {
+            let ret = cache_store.read(b.seg_id0, b.seg_id1, seg_dir, offset, total_read);
+            match ret {
+                Ok(ret) => {
+                    match ret {
+                        Some(d) => {
+                            if d.is_empty() {
+                                return Ok(data);
+                            }
+                            let l = d.len() as u32;
+                            if l >= total_read {
+                                data.extend_from_slice(&d[..total_read as usize]);
+                                return Ok(data);
+                            }
+                            total_read -= l;
+                            offset += l as u64;
+                            data.extend(d);
+                            continue;
+                        }
+                        None => {
+                            return Ok(data);
+                        }
+                    }
+                }
+                Err(err) => {
+                    if err.is_eof() {
+                        info!("read_block: ino: {}, got eof for seg(id0: {}, id1: {}) offset: {}, size: {}",
+                        ino, b.seg_id0, b.seg_id1, offset, total_read);
+                        return Ok(data);
+                    }
+                    if !err.is_bad_offset() {
+                        return Err(err);
+                    }
+                }
+            }
+            // the cache doesn't have the data yet.
+            break;
+        }
+    }
+    let ret = backend_store.read(b.seg_id0, b.seg_id1, offset, total_read);
+    match ret {
+        Ok(ret) => {
+            if let Some(d) = ret {
+                data.extend(d);
+            }
+        }
+        Err(err) => {
+            if !err.is_invalid_range() {
+                return Err(err);
+            }
+            error!("read_block: ino: {}, offset: {}, size: {} exceeds the backend store's range",
+            ino, offset, total_read);
+        }
+    }
+    return Ok(data);
+}
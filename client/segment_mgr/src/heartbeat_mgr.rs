@@ -1,9 +1,10 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use common::thread::Thread;
 use crossbeam_channel::{Receiver, Sender, bounded, select};
 use metaservice_mgr::mgr::MetaServiceMgr;
 use std::sync::Arc;
 use crate::{segment_mgr::SegmentMgr, segment_sync::SegSyncer};
+use crate::lease::Lease;
 use metaservice_mgr::types::HeartbeatResult;
 use log::{warn, error};
 
@@ -15,13 +16,14 @@ pub struct HeartbeatMgr{
 }
 
 impl HeartbeatMgr {
-    pub fn new(timeout: u64, syncer: Arc<SegSyncer>, meta_mgr: Arc<dyn MetaServiceMgr>, segment_mgr: Arc<SegmentMgr>) -> Self {
+    pub fn new(timeout: u64, lease: Arc<Lease>, syncer: Arc<SegSyncer>, meta_mgr: Arc<dyn MetaServiceMgr>, segment_mgr: Arc<SegmentMgr>) -> Self {
         let (stop_tx, stop_rx) = bounded::<u8>(1);
         let mut hm = HeartbeatMgr{
             stop_tx: stop_tx,
             thr: Thread::create(&format!("HeartbeatMgr")),
         };
         let hi = HeartbeatImpl::new(timeout,
+            lease,
             stop_rx,
             syncer,
             meta_mgr,
@@ -49,6 +51,8 @@ impl Drop for HeartbeatMgr{
 
 struct HeartbeatImpl {
     timeout: u64,
+    // renewed by each successful heartbeat.
+    lease: Arc<Lease>,
     stop_rx: Receiver<u8>,
     segment_syncer: Arc<SegSyncer>,
     meta_mgr: Arc<dyn MetaServiceMgr>,
@@ -57,12 +61,14 @@ struct HeartbeatImpl {
 
 impl HeartbeatImpl {
     pub fn new(timeout: u64, 
+        lease: Arc<Lease>,
         stop_rx: Receiver<u8>, 
         syncer: Arc<SegSyncer>, 
         meta_mgr: Arc<dyn MetaServiceMgr>,
         segment_mgr: Arc<SegmentMgr>) -> Self{
         HeartbeatImpl{
             timeout: timeout,
+            lease: lease,
             stop_rx: stop_rx,
             segment_syncer: syncer,
             meta_mgr: meta_mgr,
@@ -87,9 +93,11 @@ impl HeartbeatImpl {
                 default(Duration::from_secs(self.timeout)) => {
                     // perform heartbeat
                     let result: HeartbeatResult;
+                    let sent = Instant::now();
                     let ret = self.meta_mgr.heartbeat();
                     match ret {
                         Ok(ret) => {
+                            self.lease.renew(sent);
                             result = ret;
                         }
                         Err(err) => {
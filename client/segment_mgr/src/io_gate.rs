@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+// IoGate stops the io of the inos being moved to other machines.
+// the io waits at the gate while it is closed, and closing the gate waits until the running io of ino finishes.
+pub struct IoGate {
+    // ino -> (whether the gate is closed, the number of the running io).
+    states: Mutex<HashMap<u64, (bool, u32)>>,
+    cond: Condvar,
+}
+
+// the io of ino is running until it is dropped.
+pub struct IoGuard<'a> {
+    gate: &'a IoGate,
+    ino: u64,
+}
+
+impl Drop for IoGuard<'_> {
+    fn drop(&mut self) {
+        let mut states = self.gate.lock();
+        if let Some(s) = states.get_mut(&self.ino) {
+            s.1 -= 1;
+            if !s.0 && s.1 == 0 {
+                states.remove(&self.ino);
+            }
+        }
+        self.gate.cond.notify_all();
+    }
+}
+
+impl Default for IoGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IoGate {
+    pub fn new() -> Self {
+        IoGate{
+            states: Mutex::new(HashMap::new()),
+            cond: Condvar::new(),
+        }
+    }
+
+    // start the io of ino, wait if its gate is closed.
+    pub fn enter(&self, ino: u64) -> IoGuard<'_> {
+        let mut states = self.lock();
+        while states.get(&ino).map_or(false, |s| s.0) {
+            states = self.wait(states);
+        }
+        states.entry(ino).or_insert((false, 0)).1 += 1;
+        IoGuard{
+            gate: self,
+            ino: ino,
+        }
+    }
+
+    // stop the new io of ino and wait until the running ones finish.
+    pub fn close(&self, ino: u64) {
+        let mut states = self.lock();
+        // only one closes the gate of ino at a time.
+        while states.get(&ino).map_or(false, |s| s.0) {
+            states = self.wait(states);
+        }
+        states.entry(ino).or_insert((false, 0)).0 = true;
+        while states.get(&ino).map_or(false, |s| s.1 > 0) {
+            states = self.wait(states);
+        }
+    }
+
+    // let the io of ino go on.
+    pub fn open(&self, ino: u64) {
+        let mut states = self.lock();
+        if let Some(s) = states.get_mut(&ino) {
+            s.0 = false;
+            if s.1 == 0 {
+                states.remove(&ino);
+            }
+        }
+        self.cond.notify_all();
+    }
+
+    // the states are always consistent when the lock is released, so a poisoned lock is still used.
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, (bool, u32)>> {
+        self.states.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn wait<'a>(&self, states: MutexGuard<'a, HashMap<u64, (bool, u32)>>) -> MutexGuard<'a, HashMap<u64, (bool, u32)>> {
+        self.cond.wait(states).unwrap_or_else(|e| e.into_inner())
+    }
+}
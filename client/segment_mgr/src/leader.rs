@@ -1,15 +1,28 @@
 use common::error::Errno;
 use crate::types::BlockIo;
-pub trait Leader {
-    // open the segment for io
-    fn open(&self, ino: u64) -> Errno;
+pub trait Leader: Send + Sync {
+    // open the segment for io, size is the current size of the file.
+    // token is the fencing token of the leadership, the updates of a stale leader are rejected by it.
+    fn open(&self, ino: u64, size: u64, token: u64) -> Errno;
     // write the block into the segment file.
     // all the blocks are appended to the segment file.
     fn write(&self, ino: u64, offset: u64, data: &[u8]) -> Result<BlockIo, Errno>;
-    // read the data into Vec<u8>
+    // read the data into Vec<u8>, the holes are filled with zeros and the read stops at the end of file.
     fn read(&self, ino: u64, offset: u64, size: u32) -> Result<Vec<u8>, Errno>;
+    // get the size of the opened file, including the data not updated into meta server yet.
+    fn get_file_size(&self, ino: u64) -> Result<u64, Errno>;
+    // cut the data beyond size, the extended range becomes a hole.
+    fn truncate(&self, ino: u64, size: u64) -> Errno;
+    // wait until the changed segments of ino are updated into meta server.
+    fn flush(&self, ino: u64) -> Errno;
+    // flush the cached data of ino to disk and update the segments into meta server.
+    // datasync means only the data need to be flushed.
+    fn fsync(&self, ino: u64, datasync: bool) -> Errno;
     // close the file handle specified by ino.
     fn close(&self, ino: u64) -> Errno;
-    // release this leader.
-    fn release(&mut self);
+    // move the leadership of ino to machine, the data of ino is flushed and uploaded into backend store first.
+    fn migrate(&self, ino: u64, machine: &String) -> Errno;
+    // release this leader, it may still be shared by the others, such as the leader server,
+    // whose later calls fail after it.
+    fn release(&self);
 }
\ No newline at end of file
@@ -0,0 +1,292 @@
+use std::sync::Arc;
+use common::error::Errno;
+use metaservice_mgr::types::Segment;
+use io_engine::cache_store::CacheStore;
+use io_engine::backend_storage::BackendStore;
+use log::{info, warn, error};
+use crate::{leader::Leader, segment_sync::SegSyncer};
+use crate::file_handle::FileHandleMgr;
+use crate::file_reader;
+use crate::types::{FileHandle, BlockIo};
+use crate::segment_mgr::SegmentMgr;
+
+// LeaderFollower reads the files led by other machines from the backend store without contacting their leaders.
+// the blocks are got from meta server on each open and refreshed when they are not uploaded yet, only the data
+// already uploaded by the leader can be read, the others fail with Eagain. it never writes.
+pub struct LeaderFollower {
+    machine: String,
+    cache_store: Arc<dyn CacheStore>,
+    backend_store: Arc<dyn BackendStore>,
+    sync_mgr: Arc<SegSyncer>,
+    segment_mgr: Arc<SegmentMgr>,
+    handle_mgr: FileHandleMgr,
+    // whether the segments are downloaded into the local cache store for the following reads.
+    cache: bool,
+}
+
+impl Leader for LeaderFollower {
+    fn open(&self, ino: u64, size: u64, _token: u64) -> Errno {
+        // the opens of the same ino share the handle, but the blocks are refreshed to see the latest writes.
+        let ret = self.handle_mgr.open_handle(ino, 0);
+        match ret {
+            Ok(_) => {
+                let ret = self.refresh(ino, size);
+                if !ret.is_success() {
+                    warn!("LeaderFollower: open: failed to refresh the blocks of ino: {}, err: {:?}", ino, ret);
+                }
+                return Errno::Esucc;
+            }
+            Err(err) => {
+                if !err.is_enoent() {
+                    error!("LeaderFollower: open: failed to get file handle for ino: {}, err: {:?}", ino, err);
+                    return err;
+                }
+            }
+        }
+        let segments: Vec<Segment>;
+        let ret = self.segment_mgr.get_file_segments(ino, &self.machine);
+        match ret {
+            Ok(ret) => {
+                segments = ret;
+            }
+            Err(err) => {
+                error!("LeaderFollower: open: failed to get_file_segments for ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+        }
+        if self.cache {
+            for seg in &segments {
+                self.cache_segment(seg);
+            }
+        }
+        let file_handle = FileHandle::create(ino, self.machine.clone(), 0, segments, size);
+        let ret = self.handle_mgr.add(&file_handle);
+        if !ret.is_success() {
+            error!("LeaderFollower: open: failed to add file handle for ino: {}, err: {:?}", ino, ret);
+            return ret;
+        }
+        info!("LeaderFollower: open: ino: {} is opened for reading from backend store", ino);
+        return Errno::Esucc;
+    }
+
+    fn read(&self, ino: u64, offset: u64, size: u32) -> Result<Vec<u8>, Errno> {
+        let ret = self.read_uploaded(ino, offset, size);
+        match ret {
+            Ok(ret) => {
+                return Ok(ret);
+            }
+            Err(err) => {
+                if !err.is_eagain() {
+                    return Err(err);
+                }
+            }
+        }
+        // the blocks may be overwritten by the leader since they are got, retry with the latest ones.
+        let size_now: u64;
+        let ret = self.handle_mgr.get_file_size(ino);
+        match ret {
+            Ok(ret) => {
+                size_now = ret;
+            }
+            Err(err) => {
+                error!("LeaderFollower: read: failed to get file size for ino: {}, err: {:?}", ino, err);
+                return Err(err);
+            }
+        }
+        let ret = self.refresh(ino, size_now);
+        if !ret.is_success() {
+            error!("LeaderFollower: read: failed to refresh the blocks of ino: {}, err: {:?}", ino, ret);
+            return Err(Errno::Eagain);
+        }
+        self.read_uploaded(ino, offset, size)
+    }
+
+    fn get_file_size(&self, ino: u64) -> Result<u64, Errno> {
+        self.handle_mgr.get_file_size(ino)
+    }
+
+    fn write(&self, ino: u64, offset: u64, _data: &[u8]) -> Result<BlockIo, Errno> {
+        error!("LeaderFollower: write: ino: {} with offset: {} is opened read-only", ino, offset);
+        Err(Errno::Erofs)
+    }
+
+    fn truncate(&self, ino: u64, size: u64) -> Errno {
+        error!("LeaderFollower: truncate: ino: {} to size: {} is opened read-only", ino, size);
+        Errno::Erofs
+    }
+
+    fn flush(&self, _ino: u64) -> Errno {
+        // nothing is changed by the follower.
+        Errno::Esucc
+    }
+
+    fn fsync(&self, _ino: u64, _datasync: bool) -> Errno {
+        Errno::Esucc
+    }
+
+    fn close(&self, ino: u64) -> Errno {
+        if self.cache {
+            let ret = self.handle_mgr.get_file_segments(ino);
+            match ret {
+                Ok(segs) => {
+                    for s in &segs {
+                        if s.backend_size == 0 {
+                            continue;
+                        }
+                        let ret = self.cache_store.close(s.seg_id0, s.seg_id1);
+                        if !ret.is_success() {
+                            error!("LeaderFollower: close: failed to close seg: (id0: {}, id1: {}), err: {:?}",
+                            s.seg_id0, s.seg_id1, ret);
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("LeaderFollower: close: failed to get file segments for ino: {}, err: {:?}", ino, err);
+                    return err;
+                }
+            }
+        }
+        self.handle_mgr.del(ino)
+    }
+
+    fn migrate(&self, ino: u64, machine: &String) -> Errno {
+        error!("LeaderFollower: migrate: ino: {} is not led by the follower, cannot move it to: {}", ino, machine);
+        Errno::Enotsupp
+    }
+
+    fn release(&self) {
+        let ret = self.handle_mgr.get_inos();
+        match ret {
+            Ok(inos) => {
+                for ino in inos {
+                    let ret = self.close(ino);
+                    if !ret.is_success() {
+                        error!("LeaderFollower: release: failed to close ino: {}, err: {:?}", ino, ret);
+                    }
+                }
+            }
+            Err(err) => {
+                error!("LeaderFollower: release: failed to get the opened files, err: {:?}", err);
+            }
+        }
+        self.handle_mgr.stop();
+    }
+}
+
+impl LeaderFollower {
+    pub fn new(machine: &String, mgr: Arc<SegmentMgr>, cache: Arc<dyn CacheStore>, backend: Arc<dyn BackendStore>,
+        sync_mgr: Arc<SegSyncer>, cache_segments: bool) -> Self {
+        LeaderFollower {
+            machine: machine.clone(),
+            cache_store: cache,
+            backend_store: backend,
+            sync_mgr: sync_mgr,
+            segment_mgr: mgr,
+            handle_mgr: FileHandleMgr::create(),
+            cache: cache_segments,
+        }
+    }
+
+    // read [offset, offset+size) of ino from the uploaded data, Eagain if some blocks are not uploaded yet.
+    fn read_uploaded(&self, ino: u64, offset: u64, size: u32) -> Result<Vec<u8>, Errno> {
+        file_reader::read_file(&self.handle_mgr, ino, offset, size, |b, seg_offset, to_read| {
+            let seg_dir: String;
+            let mut data: Vec<u8>;
+            if !self.cache {
+                seg_dir = String::from("");
+                let ret = file_reader::read_block(None, &self.backend_store, &seg_dir, ino, b, seg_offset, to_read);
+                match ret {
+                    Ok(ret) => {
+                        data = ret;
+                    }
+                    Err(err) => {
+                        return Err(err);
+                    }
+                }
+            } else {
+                // the part not downloaded yet is read from the backend store.
+                seg_dir = self.segment_mgr.get_segment_dir(b.seg_id0, b.seg_id1);
+                let ret = file_reader::read_block(Some(&self.cache_store), &self.backend_store, &seg_dir, ino, b,
+                    seg_offset, to_read);
+                match ret {
+                    Ok(ret) => {
+                        data = ret;
+                    }
+                    Err(err) => {
+                        return Err(err);
+                    }
+                }
+                // the cache stops at its end while the download is in progress, the rest is in the backend store.
+                if (data.len() as u32) < to_read {
+                    let ret = file_reader::read_block(None, &self.backend_store, &seg_dir, ino, b,
+                        seg_offset + data.len() as u64, to_read - data.len() as u32);
+                    match ret {
+                        Ok(ret) => {
+                            data.extend(ret);
+                        }
+                        Err(err) => {
+                            return Err(err);
+                        }
+                    }
+                }
+            }
+            if (data.len() as u32) < to_read {
+                warn!("LeaderFollower: read: ino: {}, seg(id0: {}, id1: {}) offset: {}, size: {} is not uploaded yet, got: {}",
+                ino, b.seg_id0, b.seg_id1, seg_offset, to_read, data.len());
+                return Err(Errno::Eagain);
+            }
+            return Ok(data);
+        })
+    }
+
+    // replace the blocks of the opened ino with the ones in meta server.
+    // the segments got here are not downloaded into the cache store, the reads of them go to the backend store.
+    fn refresh(&self, ino: u64, size: u64) -> Errno {
+        let segments: Vec<Segment>;
+        let ret = self.segment_mgr.get_file_segments(ino, &self.machine);
+        match ret {
+            Ok(ret) => {
+                segments = ret;
+            }
+            Err(err) => {
+                error!("LeaderFollower: refresh: failed to get_file_segments for ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+        }
+        let file_handle = FileHandle::create(ino, self.machine.clone(), 0, segments, size);
+        self.handle_mgr.refresh(&file_handle)
+    }
+
+    // download the uploaded part of seg into the cache store in background.
+    // the reads fall back to the backend store if it fails, so the errors are only logged.
+    fn cache_segment(&self, seg: &Segment) {
+        // nothing is uploaded yet, such as the segment created for the empty file.
+        if seg.backend_size == 0 {
+            return;
+        }
+        let seg_dir = self.segment_mgr.get_segment_dir(seg.seg_id0, seg.seg_id1);
+        let ret = self.cache_store.open(seg.seg_id0, seg.seg_id1, &seg_dir);
+        if !ret.is_success() {
+            error!("LeaderFollower: cache_segment: failed to open seg(id0: {}, id1: {}) in dir: {}, err: {:?}",
+            seg.seg_id0, seg.seg_id1, seg_dir, ret);
+            return;
+        }
+        let ret = self.cache_store.stat(seg.seg_id0, seg.seg_id1, &seg_dir);
+        match ret {
+            Ok(ret) => {
+                if ret.size >= seg.backend_size {
+                    return;
+                }
+                let ret = self.sync_mgr.download_segment(&seg_dir, seg.seg_id0, seg.seg_id1, ret.size, seg.capacity);
+                if !ret.is_success() {
+                    error!("LeaderFollower: cache_segment: failed to download seg(id0: {}, id1: {}), err: {:?}",
+                    seg.seg_id0, seg.seg_id1, ret);
+                }
+            }
+            Err(err) => {
+                error!("LeaderFollower: cache_segment: failed to stat seg(id0: {}, id1: {}), err: {:?}",
+                seg.seg_id0, seg.seg_id1, err);
+            }
+        }
+    }
+}
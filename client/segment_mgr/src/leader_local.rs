@@ -1,15 +1,21 @@
 use std::sync::Arc;
 use std::time::Instant;
 use common::runtime::Executor;
+use common::config::WriteBufferConfig;
 use common::error::Errno;
-use metaservice_mgr::types::{Segment, Block};
+use metaservice_mgr::types::{Segment, FileLeader};
 use io_engine::cache_store::CacheStore;
 use io_engine::backend_storage::BackendStore;
 use log::{info, warn, error};
 use crate::{leader::Leader, segment_sync::SegSyncer};
 use crate::file_handle::FileHandleMgr;
+use crate::file_reader;
+use crate::segment_writer::SegmentWriter;
+use crate::write_buffer::WriteBufferMgr;
 use crate::types::{FileHandle, BlockIo};
 use crate::segment_mgr::SegmentMgr;
+use crate::lease::Lease;
+use crate::io_gate::IoGate;
 
 pub struct LeaderLocal {
     machine: String,
@@ -18,13 +24,21 @@ pub struct LeaderLocal {
     exec: Executor,
     sync_mgr: Arc<SegSyncer>,
     segment_mgr: Arc<SegmentMgr>,
-    handle_mgr: FileHandleMgr,
+    handle_mgr: Arc<FileHandleMgr>,
+    writer: SegmentWriter,
+    // the small writes are coalesced in it before written by writer.
+    write_buffer: WriteBufferMgr,
+    // whether fsync waits until the segments are uploaded into backend store.
+    fsync_wait_upload: bool,
+    // the io of the inos being moved to other machines waits in it.
+    io_gate: IoGate,
 }
 
 impl Leader for LeaderLocal {
-    fn open(&self, ino: u64) -> Errno {
+    fn open(&self, ino: u64, size: u64, token: u64) -> Errno {
+        let _io = self.io_gate.enter(ino);
         let segments : Vec<Segment>;
-        let ret = self.handle_mgr.open_handle(ino);
+        let ret = self.handle_mgr.open_handle(ino, token);
         match ret {
             Ok(ret) => {
                 info!("open: got handle for ino: {}, leader: {}", ino, ret);
@@ -94,8 +108,25 @@ impl Leader for LeaderLocal {
         let begin = Instant::now();
         // note: the blocks in segments doesn't contain ino, seg_ids from meta service.
         // must set it manually.
-        let file_handle = FileHandle::create(ino, self.machine.clone(), segments);
+        // the last segment is led by the former leader if ino is taken over, its tail may not be uploaded yet,
+        // so the following writes go to a new segment of this machine.
+        let mut taken_over = false;
+        if let Some(s) = segments.last() {
+            taken_over = s.leader != self.machine;
+        }
+        let file_handle = FileHandle::create(ino, self.machine.clone(), token, segments, size);
         self.handle_mgr.add(&file_handle);
+        if taken_over {
+            let seg = self.segment_mgr.new_segment(&String::from(""));
+            let ret = self.handle_mgr.add_segment(ino, &seg);
+            if !ret.is_success() {
+                error!("open: failed to add segment(id0: {}, id1: {}) for the taken over ino: {}, err: {:?}",
+                seg.seg_id0, seg.seg_id1, ino, ret);
+                self.handle_mgr.del(ino);
+                return ret;
+            }
+            info!("open: ino: {} is taken over, write to new segment(id0: {}, id1: {})", ino, seg.seg_id0, seg.seg_id1);
+        }
         let dur = begin.elapsed().as_nanos();
         info!("open: add file_handle for ino: {} takes: {}", ino, dur);
 
@@ -103,212 +134,136 @@ impl Leader for LeaderLocal {
     }
 
     fn read(&self, ino: u64, offset: u64, size: u32) -> Result<Vec<u8>, Errno> {
-        let mut start = offset;
-        let mut total_read = size;
-        let mut data = Vec::<u8>::new();
-        let begin = Instant::now();
-        let blocks = self.handle_mgr.get_blocks(ino, offset, size as u64);
-        let dur = begin.elapsed().as_nanos();
-        info!("read: ino: {}, offset: {}, size: {}, get_blocks takes: {}", ino, offset, size, dur);
-        for b in &blocks {
-            if b.offset <= start && start <= (b.offset + b.size as u64) {
-                let mut to_read = total_read;
-                if to_read >= b.size as u32 {
-                    to_read = b.size as u32;
-                }
-                // read the data.
-                let mut need_read_backend_store = false;
-                let seg_dir = self.segment_mgr.get_segment_dir(b.seg_id0, b.seg_id1);
-                let seg_offset = b.seg_start_addr + start - b.offset;
-                let ret = self.cache_store.read(b.seg_id0, 
-                    b.seg_id1, &seg_dir, seg_offset, to_read);
-                match ret {
-                    Ok(ret) => {
-                        if let Some(d) = ret{
-                            let l = d.len() as u32;
-                            total_read -= l as u32;
-                            start += l as u64;
-                            data.extend(d);
-                        }
-                    }
-                    Err(err) => {
-                        if err.is_eof() {
-                            info!("LeadLocal: read: ino: {}, got eof for seg(id0: {}, id1: {}) offset: {}, start: {}, size: {}",
-                            ino, b.seg_id0, b.seg_id1, offset, start, to_read);
-                            continue;
-                        }
-                        if err.is_bad_offset(){
-                            need_read_backend_store = true;
-                        } else {
-                            error!("LeadLocal: read: failed to read for ino: {}, offset: {}, start: {}, size: {}, err: {:?}", 
-                            ino, offset, start, to_read, err);
-                            return Err(err);
-                        }
-                    }
-                }
-                // need to read the backend store?
-                if need_read_backend_store {
-                    let ret = self.backend_store.read(b.seg_id0, 
-                        b.seg_id1, seg_offset, to_read);
-                    match ret {
-                        Ok(ret) => {
-                            if let Some(d) = ret {
-                                let l = d.len() as u32;
-                                total_read -= l as u32;
-                                start += l as u64;
-                                data.extend(d);
-                            }
-                        }
-                        Err(err) => {
-                            if err.is_invalid_range() {
-                                error!("LeadLocal: backend_read: ino: {}, offset: {}, start: {}, size: {} 
-                                exceeds the backend store's range", ino, offset, start, to_read);
-                                continue;
-                            }
-                        }
-                    }
-                }
-                if total_read == 0 {
-                    warn!("LeadLocal: read: finished for ino: {}, offset: {}, start: {}, size: {}", 
-                    ino, offset, start, size);
-                    return Ok(data);
-                }
-            }
+        let _io = self.io_gate.enter(ino);
+        let ret = self.flush_buffer(ino);
+        if !ret.is_success() {
+            return Err(ret);
         }
+        file_reader::read_file(&self.handle_mgr, ino, offset, size, |b, seg_offset, to_read| {
+            let seg_dir = self.segment_mgr.get_segment_dir(b.seg_id0, b.seg_id1);
+            // read from the backend store if it isn't in cache.
+            file_reader::read_block(Some(&self.cache_store), &self.backend_store, &seg_dir, ino, b, seg_offset, to_read)
+        })
+    }
 
-        return Ok(data);
+    fn get_file_size(&self, ino: u64) -> Result<u64, Errno> {
+        let _io = self.io_gate.enter(ino);
+        let ret = self.flush_buffer(ino);
+        if !ret.is_success() {
+            return Err(ret);
+        }
+        self.handle_mgr.get_file_size(ino)
     }
 
     fn write(&self, ino: u64, offset: u64, data: &[u8]) -> Result<BlockIo, Errno> {
-        let last_segment: Vec<u64>;
-        let ret = self.handle_mgr.get_last_segment(ino);
+        let _io = self.io_gate.enter(ino);
+        let ret = self.writer.check_lease(ino);
+        if !ret.is_success() {
+            return Err(ret);
+        }
+        if !self.write_buffer.is_enabled() {
+            return self.writer.write(&self.handle_mgr, ino, offset, data);
+        }
+        let ret = self.write_buffer.write(ino, offset, data);
+        if !ret.is_success() {
+            error!("write: failed to buffer the write of ino: {} with offset: {}, err: {:?}", ino, offset, ret);
+            return Err(ret);
+        }
+        // the segment of the buffered data is unknown until it is written.
+        return Ok(BlockIo{
+            id0: 0,
+            id1: 0,
+            offset: 0,
+            size: data.len() as u32,
+        });
+    }
+
+    fn truncate(&self, ino: u64, size: u64) -> Errno {
+        let _io = self.io_gate.enter(ino);
+        let ret = self.writer.check_lease(ino);
+        if !ret.is_success() {
+            return ret;
+        }
+        let ret = self.flush_buffer(ino);
+        if !ret.is_success() {
+            return ret;
+        }
+        let (segs, garbages, ret) = self.handle_mgr.truncate(ino, size);
+        if !ret.is_success() {
+            error!("truncate: failed to truncate ino: {} to size: {}, err: {:?}", ino, size, ret);
+            return ret;
+        }
+        if segs.is_empty() && garbages.is_empty() {
+            return Errno::Esucc;
+        }
+        let ret = self.writer.update_changed_segments(&self.handle_mgr, ino, segs, garbages);
+        if !ret.is_success() {
+            error!("truncate: failed to update changed segments for ino: {}, err: {:?}", ino, ret);
+            return ret;
+        }
+        // truncate is synchronous, wait until the removed blocks are updated into meta server.
+        return self.sync_segments(ino);
+    }
+
+    fn flush(&self, ino: u64) -> Errno {
+        let ret = self.sync_buffer(ino);
+        if !ret.is_success() {
+            return ret;
+        }
+        return self.sync_segments(ino);
+    }
+
+    fn fsync(&self, ino: u64, datasync: bool) -> Errno {
+        // the buffered data must be in the cache segment files before they are synced.
+        let ret = self.sync_buffer(ino);
+        if !ret.is_success() {
+            return ret;
+        }
+        let segs: Vec<Segment>;
+        let ret = self.handle_mgr.get_file_segments(ino);
         match ret {
             Ok(ret) => {
-                last_segment = ret;
+                segs = ret;
             }
             Err(err) => {
-                error!("write: failed to get_last_segment for ino: {}, err: {:?}", ino, err);
-                return Err(err);
-            }
-        }
-        if last_segment.is_empty() {
-            error!("write: failed to get_last_segment for ino: {}, no segments found.", ino);
-            return Err(Errno::Enoent);
-        }
-        let mut id0 = last_segment[0];
-        let mut id1 = last_segment[1];
-        let mut seg_max_size = last_segment[2];
-        let mut seg_size = last_segment[3];
-        let need_sync = last_segment[4];
-        let mut direct_io = false;
-        if need_sync == 1 {
-            direct_io = true;
-        }
-        
-        //println!("write: seg(id0: {}, id1: {}, max_size: {}, ino: {}, offset: {})", id0, id1, seg_max_size, ino, offset);
-        loop {
-            //println!("write: seg(id0: {}, id1: {}, max_size: {})", id0, id1, seg_max_size);
-            let seg_dir = self.segment_mgr.get_segment_dir(id0, id1);
-            // must check whether cache size is smaller than segment size or not. if so, write the backend directly.
-            // or if O_DIRECT, write to backend directly too.
-            
-            if direct_io {
-                // write to backend store directly.
-                let ret = self.backend_store.write(id0, id1, seg_size, data);
-                if ret.err.is_success() {
-                    // write block success.
-                    let b = Block {
-                        ino: ino,
-                        generation: 0,
-                        offset: offset,
-                        seg_id0: id0,
-                        seg_id1: id1,
-                        seg_start_addr: ret.offset,
-                        size: ret.nwrite as i64,
-                    };
-                    let err = self.add_block(ino, id0, id1, &b);
-                    if !err.is_success() {
-                        error!("write: failed to add_block{:?} for ino: {} with offset: {}, err: {:?}", b, ino, offset, ret);
-                        return Err(err);
-                    }
-                    return Ok(BlockIo{
-                        id0: id0,
-                        id1: id1,
-                        offset: ret.offset,
-                        size: ret.nwrite,
-                    });
-                }
-                error!("write: backend_store write failed for seg: id0: {}, id1: {}, offset: {}, err: {:?}",
-                id0, id1, seg_size, ret.err);
-                return Err(ret.err);
+                error!("fsync: failed to get file segments for ino: {}, err: {:?}", ino, err);
+                return err;
             }
-            let ret = self.cache_store.write(id0, id1, &seg_dir, offset, seg_max_size, data);
-            match ret {
-                Ok(r) => {
-                    // write block success.
-                    let b = Block {
-                        ino: ino,
-                        generation: 0,
-                        offset: offset,
-                        seg_id0: id0,
-                        seg_id1: id1,
-                        seg_start_addr: r.offset,
-                        size: r.nwrite as i64,
-                    };
-                    let ret = self.add_block(ino, id0, id1, &b);
-                    if !ret.is_success() {
-                        error!("write: failed to add_block{:?} for ino: {} with offset: {}, err: {:?}", b, ino, offset, ret);
-                        return Err(ret);
-                    }
-                    
-                    return Ok(BlockIo{
-                        id0: id0,
-                        id1: id1,
-                        offset: r.offset,
-                        size: r.nwrite,
-                    });
-                    // currently, will update the segments in close api.
-                    // upload the block to meta server.
-                    /*let ret = self.segment_mgr.upload_block(ino, id0, id1, &b);
-                    if ret.is_success(){
-                        return Ok(BlockIo{
-                            id0: id0,
-                            id1: id1,
-                            offset: r.offset,
-                            size: r.nwrite,
-                        });
-                    }
-                    error!("write: failed to upload block{:?} for ino: {}, err: {:?}", b, ino, ret);
-                    return Err(ret);*/
-                }
-                Err(err) => {
-                    if err.is_enospc() {
-                        error!("LeadLocal: write: segment(id0: {}, id1: {}, dir: {}) has no space left for ino: {} with offset: {}",
-                            id0, id1, seg_dir, ino, offset);
-                        let seg = self.segment_mgr.new_segment(&String::from(""));
-                        self.handle_mgr.add_segment(ino, &seg);
-                        id0 = seg.seg_id0;
-                        id1 = seg.seg_id1;
-                        seg_max_size = seg.capacity;
-                        // when create new segment, set the current seg_size to 0.
-                        seg_size = 0;
-                        info!("LeadLocal: write: add new segment(id0: {}, id1: {}) for ino: {} with offset: {}",
-                    id0, id1, ino, offset);
-                        continue;
-                    }
-                    error!("LeadLocal: write: failed to get response for seg(id0: {}, id1: {}) of ino: {} with offset: {}, err: {:?}", 
-                        id0, id1, ino, offset, err);
-                    return Err(err);
-                }
+        }
+        // flush the cache segment files first, so that the blocks in meta server always point to persisted data.
+        for s in &segs {
+            let ret = self.cache_store.sync(s.seg_id0, s.seg_id1, datasync);
+            if !ret.is_success() {
+                error!("fsync: failed to sync seg: (id0: {}, id1: {}) for ino: {}, err: {:?}",
+                s.seg_id0, s.seg_id1, ino, ret);
+                return ret;
+            }
+        }
+        let ret = self.flush(ino);
+        if !ret.is_success() {
+            return ret;
+        }
+        if !self.fsync_wait_upload {
+            return Errno::Esucc;
+        }
+        for s in &segs {
+            let seg_dir = self.segment_mgr.get_segment_dir(s.seg_id0, s.seg_id1);
+            let ret = self.sync_mgr.upload_segment_sync(&seg_dir, s.seg_id0, s.seg_id1, s.backend_size);
+            if !ret.is_success() {
+                error!("fsync: failed to upload seg: (id0: {}, id1: {}) for ino: {}, err: {:?}",
+                s.seg_id0, s.seg_id1, ino, ret);
+                return ret;
             }
-            
         }
+        return Errno::Esucc;
     }
 
     fn close(&self, ino: u64) -> Errno {
         // first we should update the segments into meta server.
         // second we should close all the file handles for the ino.
         // update the segments into meta server.
+        // the handle is closed even if the buffered data is lost, the error is returned at last.
+        let buffer_ret = self.sync_buffer(ino);
         let segs: Vec<Segment>;
         let ret = self.handle_mgr.get_file_segments(ino);
         match ret{
@@ -336,11 +291,87 @@ impl Leader for LeaderLocal {
         }
 
         let err = self.handle_mgr.del(ino);
+        if !buffer_ret.is_success() {
+            return buffer_ret;
+        }
         return err;
     }
 
-    fn release(&mut self) {
-        // TODO upload changed segments meta to server.
+    fn migrate(&self, ino: u64, machine: &String) -> Errno {
+        if *machine == self.machine {
+            return Errno::Esucc;
+        }
+        let leader: FileLeader;
+        let ret = self.segment_mgr.get_file_leader(ino);
+        match ret {
+            Ok(ret) => {
+                leader = ret;
+            }
+            Err(err) => {
+                error!("migrate: failed to get leader of ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+        }
+        if leader.leader != self.machine {
+            error!("migrate: ino: {} is led by {}, not this machine: {}", ino, leader.leader, self.machine);
+            return Errno::Eperm;
+        }
+        // upload most of the data while ino is still served, the io of ino is stopped only for the rest.
+        let opened = self.handle_mgr.get_leader_token(ino).is_ok();
+        if opened {
+            let ret = self.flush_handle(ino);
+            if !ret.is_success() {
+                error!("migrate: failed to flush ino: {}, err: {:?}", ino, ret);
+                return ret;
+            }
+        }
+        let ret = self.upload_segments(ino, false);
+        if !ret.is_success() {
+            return ret;
+        }
+        // stop the new io of ino and wait for the running ones, the handle is kept until ino is moved.
+        self.io_gate.close(ino);
+        let ret = self.move_leader(ino, machine, &leader, opened);
+        if !ret.is_success() {
+            // ino is still led by this machine, serve it again.
+            self.io_gate.open(ino);
+            return ret;
+        }
+        if opened {
+            // the io waiting for ino gets Enoent, ino needs to be reopened to be served by the new leader.
+            let ret = self.handle_mgr.remove(ino);
+            if !ret.is_success() {
+                error!("migrate: failed to remove the handle of ino: {}, err: {:?}", ino, ret);
+            }
+        }
+        self.io_gate.open(ino);
+        return Errno::Esucc;
+    }
+
+    fn release(&self) {
+        // flush all the opened files, so that the written data is not lost after unmount.
+        // the segments are uploaded into backend store later by the next mount.
+        let ret = self.handle_mgr.get_inos();
+        match ret {
+            Ok(inos) => {
+                info!("release: flush {} opened files", inos.len());
+                for ino in inos {
+                    let ret = self.flush_handle(ino);
+                    if !ret.is_success() {
+                        error!("release: failed to flush ino: {}, err: {:?}", ino, ret);
+                    }
+                    let ret = self.close(ino);
+                    if !ret.is_success() {
+                        error!("release: failed to close ino: {}, err: {:?}", ino, ret);
+                    }
+                }
+            }
+            Err(err) => {
+                error!("release: failed to get the opened files, err: {:?}", err);
+            }
+        }
+        // the remaining buffers are written by the buffer thread before handle_mgr is stopped.
+        self.write_buffer.stop();
         self.handle_mgr.stop();
     }
 }
@@ -348,7 +379,11 @@ impl Leader for LeaderLocal {
 impl LeaderLocal {
     pub fn new(machine: &String, exec: &Executor, mgr: Arc<SegmentMgr>, 
         cache: Arc<dyn CacheStore>, backend: Arc<dyn BackendStore>,
-        sync_mgr: Arc<SegSyncer>) -> Self {
+        sync_mgr: Arc<SegSyncer>, fsync_wait_upload: bool, write_buffer_config: &WriteBufferConfig,
+        lease: Arc<Lease>) -> Self {
+        let handle_mgr = Arc::new(FileHandleMgr::create());
+        let writer = SegmentWriter::new(cache.clone(), backend.clone(), sync_mgr.clone(), mgr.clone(), lease);
+        let write_buffer = WriteBufferMgr::create(write_buffer_config, writer.clone(), handle_mgr.clone());
         LeaderLocal {
             machine: machine.clone(),
             cache_store: cache,
@@ -356,21 +391,157 @@ impl LeaderLocal {
             exec: exec.clone(),
             sync_mgr: sync_mgr,
             segment_mgr: mgr,
-            handle_mgr: FileHandleMgr::create(),
+            handle_mgr: handle_mgr,
+            writer: writer,
+            write_buffer: write_buffer,
+            fsync_wait_upload: fsync_wait_upload,
+            io_gate: IoGate::new(),
         }
     }
 
-    fn add_block(&self, ino: u64, id0: u64, id1: u64, b: &Block)->Errno{
-        let (segs, garbages, ret) = self.handle_mgr.add_block(ino, id0, id1, &b);
+    // write the rest of ino into backend store and move its leadership to machine, the io of ino is stopped.
+    fn move_leader(&self, ino: u64, machine: &String, leader: &FileLeader, opened: bool) -> Errno {
+        if opened {
+            // the writes arrived during the upload are buffered, write them into the segments.
+            let ret = self.flush_buffer(ino);
+            if !ret.is_success() {
+                return ret;
+            }
+            // wait until the queued blocks are in meta server.
+            let ret = self.sync_segments(ino);
+            if !ret.is_success() {
+                return ret;
+            }
+            let ret = self.upload_segments(ino, false);
+            if !ret.is_success() {
+                return ret;
+            }
+        }
+        let ret = self.segment_mgr.migrate_leader(ino, machine, leader.token);
+        match ret {
+            Ok(ret) => {
+                info!("migrate: ino: {} is moved from {} to {}, token: {}", ino, self.machine, ret.leader, ret.token);
+            }
+            Err(err) => {
+                error!("migrate: failed to move ino: {} to {}, err: {:?}", ino, machine, err);
+                return err;
+            }
+        }
+        if opened {
+            // the cache segments of ino are not written by this machine any more.
+            let ret = self.handle_mgr.get_file_segments(ino);
+            match ret {
+                Ok(segs) => {
+                    for s in &segs {
+                        if s.leader != self.machine {
+                            continue;
+                        }
+                        let ret = self.cache_store.close(s.seg_id0, s.seg_id1);
+                        if !ret.is_success() {
+                            warn!("migrate: failed to close seg: (id0: {}, id1: {}), err: {:?}",
+                            s.seg_id0, s.seg_id1, ret);
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!("migrate: failed to get the segments of ino: {}, err: {:?}", ino, err);
+                }
+            }
+        }
+        return Errno::Esucc;
+    }
+
+    // write the buffered data of ino into its segments.
+    // the error of the data failed to be written in background is kept for the next sync_buffer.
+    fn flush_buffer(&self, ino: u64) -> Errno {
+        let ret = self.write_buffer.flush(ino, false);
         if !ret.is_success() {
-            error!("LeaderLocal::add_block: failed to add_block{:?} for ino: {}  err: {:?}", b, ino, ret);
-            return ret;
+            error!("flush_buffer: failed to write the buffered data of ino: {}, err: {:?}", ino, ret);
+        }
+        return ret;
+    }
+
+    // write the buffered data of ino into its segments, and report the error of the data failed to be
+    // written in background, it is used by flush, fsync and close.
+    fn sync_buffer(&self, ino: u64) -> Errno {
+        let ret = self.write_buffer.flush(ino, true);
+        if !ret.is_success() {
+            error!("sync_buffer: the buffered data of ino: {} is lost, err: {:?}", ino, ret);
         }
-        let ret = self.sync_mgr.update_changed_segments(ino, segs, garbages);
-        if !ret.is_success(){
-            error!("LeaderLocal::add_block: failed to update changed segments for ino: {}, err: {:?}", ino, ret);
+        return ret;
+    }
+
+    // wait until the changed segments of ino are updated into meta server.
+    fn sync_segments(&self, ino: u64) -> Errno {
+        let ret = self.sync_mgr.sync_changed_segments(ino);
+        if !ret.is_success() {
+            error!("sync_segments: failed to sync changed segments for ino: {}, err: {:?}", ino, ret);
+        }
+        return ret;
+    }
+
+    // persist the cache segment files of ino and update its changed segments into meta server.
+    fn flush_handle(&self, ino: u64) -> Errno {
+        let ret = self.flush_buffer(ino);
+        if !ret.is_success() {
             return ret;
         }
+        let segs: Vec<Segment>;
+        let ret = self.handle_mgr.get_file_segments(ino);
+        match ret {
+            Ok(ret) => {
+                segs = ret;
+            }
+            Err(err) => {
+                error!("flush_handle: failed to get file segments for ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+        }
+        for s in &segs {
+            let ret = self.cache_store.sync(s.seg_id0, s.seg_id1, false);
+            if !ret.is_success() {
+                error!("flush_handle: failed to sync seg: (id0: {}, id1: {}) for ino: {}, err: {:?}",
+                s.seg_id0, s.seg_id1, ino, ret);
+                return ret;
+            }
+        }
+        return self.flush(ino);
+    }
+
+    // upload the segments of ino written by this machine into backend store, with the block map in meta server.
+    // close the cache segment files after that if close_cache is true.
+    fn upload_segments(&self, ino: u64, close_cache: bool) -> Errno {
+        let segs: Vec<Segment>;
+        let ret = self.segment_mgr.get_file_segments(ino, &self.machine);
+        match ret {
+            Ok(ret) => {
+                segs = ret;
+            }
+            Err(err) => {
+                error!("upload_segments: failed to get file segments for ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+        }
+        for s in &segs {
+            // the segments of the other leaders are uploaded by them, and the empty one isn't written yet.
+            if s.leader != self.machine || s.blocks.is_empty() {
+                continue;
+            }
+            let seg_dir = self.segment_mgr.get_segment_dir(s.seg_id0, s.seg_id1);
+            let ret = self.sync_mgr.upload_segment_sync(&seg_dir, s.seg_id0, s.seg_id1, s.backend_size);
+            if !ret.is_success() {
+                error!("upload_segments: failed to upload seg: (id0: {}, id1: {}) for ino: {}, err: {:?}",
+                s.seg_id0, s.seg_id1, ino, ret);
+                return ret;
+            }
+            if close_cache {
+                let ret = self.cache_store.close(s.seg_id0, s.seg_id1);
+                if !ret.is_success() {
+                    error!("upload_segments: failed to close seg: (id0: {}, id1: {}), err: {:?}",
+                    s.seg_id0, s.seg_id1, ret);
+                }
+            }
+        }
         return Errno::Esucc;
     }
 }
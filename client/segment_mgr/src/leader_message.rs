@@ -0,0 +1,82 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+
+// the messages of the rpc between LeaderPeer and LeaderServer.
+// err_code is the Errno of the leader in `as i32`. the data of read & write is sent as the raw body,
+// the ino & offset of write are in the query, and the err_code of read is in LEADER_ERR_CODE_HEADER.
+pub const URL_LEADER_OPEN: &str = "/v1/leader/open";
+pub const URL_LEADER_READ: &str = "/v1/leader/read";
+pub const URL_LEADER_WRITE: &str = "/v1/leader/write";
+pub const URL_LEADER_FILE_SIZE: &str = "/v1/leader/size";
+pub const URL_LEADER_TRUNCATE: &str = "/v1/leader/truncate";
+pub const URL_LEADER_FLUSH: &str = "/v1/leader/flush";
+pub const URL_LEADER_FSYNC: &str = "/v1/leader/fsync";
+pub const URL_LEADER_CLOSE: &str = "/v1/leader/close";
+pub const URL_LEADER_MIGRATE: &str = "/v1/leader/migrate";
+
+// the shared secret of the machines, the requests without it are rejected.
+pub const LEADER_SECRET_HEADER: &str = "x-yigfs-leader-secret";
+pub const LEADER_ERR_CODE_HEADER: &str = "x-yigfs-err-code";
+pub const LEADER_QUERY_INO: &str = "ino";
+pub const LEADER_QUERY_OFFSET: &str = "offset";
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqLeaderOpen {
+    pub ino: u64,
+    pub size: u64,
+    pub token: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqLeaderRead {
+    pub ino: u64,
+    pub offset: u64,
+    pub size: u32,
+}
+
+// get_file_size, flush and close only need the ino.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqLeaderIno {
+    pub ino: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqLeaderTruncate {
+    pub ino: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqLeaderFsync {
+    pub ino: u64,
+    pub datasync: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ReqLeaderMigrate {
+    pub ino: u64,
+    // the machine which becomes the new leader.
+    pub machine: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespLeaderResult {
+    pub err_code: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespLeaderWrite {
+    pub err_code: i32,
+    pub id0: u64,
+    pub id1: u64,
+    pub offset: u64,
+    pub size: u32,
+}
+
+// the response of get_file_size.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RespLeaderSize {
+    pub err_code: i32,
+    pub size: u64,
+}
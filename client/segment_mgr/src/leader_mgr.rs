@@ -1,55 +1,139 @@
 
+
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use common::config::{LeaderRpcConfig, FollowerConfig, WriteBufferConfig};
+use common::error::Errno;
+use hyper::Client;
+use hyper::client::HttpConnector;
 use common::runtime::Executor;
 use io_engine::backend_storage::BackendStore;
 use io_engine::cache_store::CacheStore;
 
 use crate::{leader::Leader, segment_mgr::SegmentMgr, segment_sync::SegSyncer};
 use crate::leader_local::LeaderLocal;
+use crate::leader_follower::LeaderFollower;
 use crate::leader_not_support::LeaderNotSupport;
-use log::error;
+use crate::leader_peer::LeaderPeer;
+use crate::leader_server::LeaderServer;
+use crate::lease::Lease;
+use log::{warn, error};
 
 #[derive(Debug)]
 enum LeaderType {
     Local = 0,
     Peer = 1,
     Unknown = 2,
+    Follower = 3,
 }
 pub struct LeaderMgr {
     machine: String,
-    leaders: HashMap<u8, Box<dyn Leader>>,
+    exec: Executor,
+    leaders: HashMap<u8, Arc<dyn Leader>>,
+    // the peer leaders are created on demand and share this client.
+    peer_client: Client<HttpConnector>,
+    rpc_config: LeaderRpcConfig,
+    // serves the io of the peers with the local leader.
+    server: Option<LeaderServer>,
 }
 
 impl LeaderMgr {
     pub fn new(machine: &String, exec: &Executor, seg_mgr: Arc<SegmentMgr>, 
         cache_store: Arc<dyn CacheStore>, backend_store: Arc<dyn BackendStore>,
-        sync_mgr: Arc<SegSyncer>) -> Self {
-        let mut leaders = HashMap::<u8, Box<dyn Leader>>::new();
-        leaders.insert(LeaderType::Unknown as u8, Box::new(LeaderNotSupport::new()));
-        leaders.insert(LeaderType::Local as u8, Box::new(LeaderLocal::new(machine,  exec, seg_mgr, 
-            cache_store, backend_store, sync_mgr)));
+        sync_mgr: Arc<SegSyncer>, fsync_wait_upload: bool, rpc_config: &LeaderRpcConfig,
+        follower_config: &FollowerConfig, write_buffer_config: &WriteBufferConfig, lease: Arc<Lease>) -> Self {
+        let mut leaders = HashMap::<u8, Arc<dyn Leader>>::new();
+        leaders.insert(LeaderType::Unknown as u8, Arc::new(LeaderNotSupport::new()));
+        if follower_config.enable {
+            leaders.insert(LeaderType::Follower as u8, Arc::new(LeaderFollower::new(machine, seg_mgr.clone(),
+                cache_store.clone(), backend_store.clone(), sync_mgr.clone(), follower_config.cache)));
+        }
+        leaders.insert(LeaderType::Local as u8, Arc::new(LeaderLocal::new(machine,  exec, seg_mgr, 
+            cache_store, backend_store, sync_mgr, fsync_wait_upload, write_buffer_config, lease)));
         LeaderMgr{
             machine: machine.clone(),
+            exec: exec.clone(),
             leaders: leaders,
+            peer_client: Client::new(),
+            rpc_config: rpc_config.clone(),
+            server: None,
+        }
+    }
+
+    // start serving the peers with the local leader, it is skipped if the bind addr or the secret is not set,
+    // then the files led by this machine can't be accessed by the others.
+    pub fn start_server(&mut self) -> Errno {
+        if self.rpc_config.bind_addr.is_empty() || self.rpc_config.secret.is_empty() {
+            warn!("the bind_addr or the secret of leader rpc is not set, the leader server is not started");
+            return Errno::Esucc;
+        }
+        // will not crash here, because local is always in the hashmap.
+        let local = self.leaders.get(&(LeaderType::Local as u8)).unwrap().clone();
+        let ret = LeaderServer::start(&self.rpc_config.bind_addr, self.rpc_config.port, &self.rpc_config.secret,
+            local, &self.exec);
+        match ret {
+            Ok(ret) => {
+                self.server = Some(ret);
+                return Errno::Esucc;
+            }
+            Err(err) => {
+                error!("failed to start leader server on port: {}, err: {:?}", self.rpc_config.port, err);
+                return err;
+            }
         }
     }
+
     pub fn stop(&mut self){
-        for (k, l) in &mut self.leaders {
+        // stop serving the peers first, so that the local leader is released after their io.
+        if let Some(mut server) = self.server.take() {
+            server.stop();
+        }
+        for (k, l) in &self.leaders {
             l.release();
-            error!("leader of {:?} is stopped.", k);
+            warn!("leader of {:?} is stopped.", k);
         }
         self.leaders.clear();
     }
-    pub fn get_leader(&self, leader: &String) -> &Box<dyn Leader> {
+    pub fn get_leader(&self, leader: &String) -> Arc<dyn Leader> {
         let mut leader_type = LeaderType::Unknown as u8;
         if *leader == self.machine {
             leader_type = LeaderType::Local as u8;
-        } else if !leader.is_empty(){// peer is not supported yet.
+        } else if !leader.is_empty(){
             leader_type = LeaderType::Peer as u8;
         }
+        if leader_type == LeaderType::Peer as u8 {
+            return Arc::new(LeaderPeer::new(leader, self.rpc_config.port, &self.rpc_config.secret,
+            self.peer_client.clone(), &self.exec, Duration::from_millis(self.rpc_config.timeout_ms)));
+        }
 
         // will not crash here, because unknown is always in the hashmap.
-        self.leaders.get(&leader_type).unwrap()
+        self.leaders.get(&leader_type).unwrap().clone()
     }
-}
\ No newline at end of file
+
+    // move the leadership of ino from leader to machine, the leader uploads the data of ino before handing it off.
+    pub fn migrate_leader(&self, ino: u64, leader: &String, machine: &String) -> Errno {
+        if *leader == *machine {
+            return Errno::Esucc;
+        }
+        if *leader == self.machine {
+            // will not crash here, because local is always in the hashmap.
+            return self.leaders.get(&(LeaderType::Local as u8)).unwrap().migrate(ino, machine);
+        }
+        LeaderMgr::request_migrate(leader, ino, machine, &self.rpc_config, &self.exec)
+    }
+
+    // ask the leader server on leader to move the leadership of ino to machine,
+    // it is used by the processes without the leaders, such as the admin command.
+    pub fn request_migrate(leader: &String, ino: u64, machine: &String, rpc_config: &LeaderRpcConfig, exec: &Executor) -> Errno {
+        let peer = LeaderPeer::new(leader, rpc_config.port, &rpc_config.secret, Client::new(), exec,
+            Duration::from_millis(rpc_config.migrate_timeout_ms));
+        peer.migrate(ino, machine)
+    }
+
+    // get the follower which reads the files led by other machines from the backend store,
+    // None if the follower reads are not enabled.
+    pub fn get_follower(&self) -> Option<Arc<dyn Leader>> {
+        self.leaders.get(&(LeaderType::Follower as u8)).cloned()
+    }
+}
@@ -6,7 +6,7 @@ pub struct LeaderNotSupport {
 }
 
 impl Leader for LeaderNotSupport {
-    fn open(&self, _ino: u64) -> Errno {
+    fn open(&self, _ino: u64, _size: u64, _token: u64) -> Errno {
         Errno::Enotsupp
     }
 
@@ -14,15 +14,35 @@ impl Leader for LeaderNotSupport {
         Err(Errno::Enotsupp)
     }
 
+    fn get_file_size(&self, _ino: u64) -> Result<u64, Errno> {
+        Err(Errno::Enotsupp)
+    }
+
     fn write(&self, _ino: u64, _offset: u64, _data: &[u8]) -> Result<BlockIo, Errno> {
         Err(Errno::Enotsupp)
     }
 
+    fn truncate(&self, _ino: u64, _size: u64) -> Errno {
+        Errno::Enotsupp
+    }
+
+    fn flush(&self, _ino: u64) -> Errno {
+        Errno::Enotsupp
+    }
+
+    fn fsync(&self, _ino: u64, _datasync: bool) -> Errno {
+        Errno::Enotsupp
+    }
+
     fn close(&self, _ino: u64)->Errno{
         Errno::Enotsupp
     }
 
-    fn release(&mut self){}
+    fn migrate(&self, _ino: u64, _machine: &String) -> Errno {
+        Errno::Enotsupp
+    }
+
+    fn release(&self){}
 }
 
 impl LeaderNotSupport{
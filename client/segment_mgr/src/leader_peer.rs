@@ -0,0 +1,284 @@
+use std::time::Duration;
+use hyper::{Body, Client, Method, Request};
+use hyper::client::HttpConnector;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use common::error::Errno;
+use common::json;
+use common::runtime::Executor;
+use log::error;
+use crate::leader::Leader;
+use crate::types::BlockIo;
+use crate::leader_message::{ReqLeaderOpen, ReqLeaderRead, ReqLeaderIno,
+    ReqLeaderTruncate, ReqLeaderFsync, ReqLeaderMigrate, RespLeaderResult, RespLeaderWrite, RespLeaderSize};
+use crate::leader_message::{URL_LEADER_OPEN, URL_LEADER_READ, URL_LEADER_WRITE, URL_LEADER_FILE_SIZE,
+    URL_LEADER_TRUNCATE, URL_LEADER_FLUSH, URL_LEADER_FSYNC, URL_LEADER_CLOSE, URL_LEADER_MIGRATE};
+use crate::leader_message::{LEADER_SECRET_HEADER, LEADER_ERR_CODE_HEADER, LEADER_QUERY_INO, LEADER_QUERY_OFFSET};
+
+// the response of the leader server.
+struct PeerResp {
+    // from LEADER_ERR_CODE_HEADER, Esucc if it is not set.
+    err_code: i32,
+    body: Vec<u8>,
+}
+
+// LeaderPeer forwards the io of the files led by another machine to the LeaderServer on that machine.
+// it keeps no state, all the file handles are kept by the leader.
+pub struct LeaderPeer {
+    // the leader machine.
+    machine: String,
+    // http://machine:port
+    url: String,
+    secret: String,
+    http_client: Client<HttpConnector>,
+    exec: Executor,
+    timeout: Duration,
+}
+
+impl Leader for LeaderPeer {
+    fn open(&self, ino: u64, size: u64, token: u64) -> Errno {
+        let req = ReqLeaderOpen{
+            ino: ino,
+            size: size,
+            token: token,
+        };
+        let ret = self.call::<ReqLeaderOpen, RespLeaderResult>(URL_LEADER_OPEN, &req);
+        match ret {
+            Ok(ret) => {
+                return Errno::from_code(ret.err_code);
+            }
+            Err(err) => {
+                return err;
+            }
+        }
+    }
+
+    fn write(&self, ino: u64, offset: u64, data: &[u8]) -> Result<BlockIo, Errno> {
+        let path = format!("{}?{}={}&{}={}", URL_LEADER_WRITE, LEADER_QUERY_INO, ino, LEADER_QUERY_OFFSET, offset);
+        let resp: RespLeaderWrite;
+        let ret = self.send(&path, data.to_vec()).and_then(|r| self.decode::<RespLeaderWrite>(&path, &r.body));
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                return Err(err);
+            }
+        }
+        let err = Errno::from_code(resp.err_code);
+        if !err.is_success() {
+            return Err(err);
+        }
+        Ok(BlockIo{
+            id0: resp.id0,
+            id1: resp.id1,
+            offset: resp.offset,
+            size: resp.size,
+        })
+    }
+
+    fn read(&self, ino: u64, offset: u64, size: u32) -> Result<Vec<u8>, Errno> {
+        let req = ReqLeaderRead{
+            ino: ino,
+            offset: offset,
+            size: size,
+        };
+        let resp: PeerResp;
+        let ret = self.encode(URL_LEADER_READ, &req).and_then(|body| self.send(URL_LEADER_READ, body));
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                return Err(err);
+            }
+        }
+        let err = Errno::from_code(resp.err_code);
+        if !err.is_success() {
+            return Err(err);
+        }
+        Ok(resp.body)
+    }
+
+    fn get_file_size(&self, ino: u64) -> Result<u64, Errno> {
+        let req = ReqLeaderIno{
+            ino: ino,
+        };
+        self.call_size(URL_LEADER_FILE_SIZE, &req)
+    }
+
+    fn truncate(&self, ino: u64, size: u64) -> Errno {
+        let req = ReqLeaderTruncate{
+            ino: ino,
+            size: size,
+        };
+        self.call_result(URL_LEADER_TRUNCATE, &req)
+    }
+
+    fn flush(&self, ino: u64) -> Errno {
+        let req = ReqLeaderIno{
+            ino: ino,
+        };
+        self.call_result(URL_LEADER_FLUSH, &req)
+    }
+
+    fn fsync(&self, ino: u64, datasync: bool) -> Errno {
+        let req = ReqLeaderFsync{
+            ino: ino,
+            datasync: datasync,
+        };
+        self.call_result(URL_LEADER_FSYNC, &req)
+    }
+
+    fn close(&self, ino: u64) -> Errno {
+        let req = ReqLeaderIno{
+            ino: ino,
+        };
+        self.call_result(URL_LEADER_CLOSE, &req)
+    }
+
+    fn migrate(&self, ino: u64, machine: &String) -> Errno {
+        let req = ReqLeaderMigrate{
+            ino: ino,
+            machine: machine.clone(),
+        };
+        self.call_result(URL_LEADER_MIGRATE, &req)
+    }
+
+    fn release(&self) {
+    }
+}
+
+impl LeaderPeer {
+    pub fn new(machine: &String, port: u16, secret: &String, http_client: Client<HttpConnector>,
+        exec: &Executor, timeout: Duration) -> Self {
+        LeaderPeer{
+            machine: machine.clone(),
+            url: format!("http://{}:{}", machine, port),
+            secret: secret.clone(),
+            http_client: http_client,
+            exec: exec.clone(),
+            timeout: timeout,
+        }
+    }
+
+    fn call_result<Req: Default + Serialize>(&self, path: &str, req: &Req) -> Errno {
+        let ret = self.call::<Req, RespLeaderResult>(path, req);
+        match ret {
+            Ok(ret) => {
+                return Errno::from_code(ret.err_code);
+            }
+            Err(err) => {
+                return err;
+            }
+        }
+    }
+
+    fn call_size<Req: Default + Serialize>(&self, path: &str, req: &Req) -> Result<u64, Errno> {
+        let resp: RespLeaderSize;
+        let ret = self.call::<Req, RespLeaderSize>(path, req);
+        match ret {
+            Ok(ret) => {
+                resp = ret;
+            }
+            Err(err) => {
+                return Err(err);
+            }
+        }
+        let err = Errno::from_code(resp.err_code);
+        if !err.is_success() {
+            return Err(err);
+        }
+        Ok(resp.size)
+    }
+
+    // send the req to the leader and decode the response.
+    fn call<Req: Default + Serialize, Resp: Default + DeserializeOwned>(&self, path: &str, req: &Req) -> Result<Resp, Errno> {
+        let body = self.encode(path, req)?;
+        let resp = self.send(path, body)?;
+        self.decode::<Resp>(path, &resp.body)
+    }
+
+    fn encode<Req: Default + Serialize>(&self, path: &str, req: &Req) -> Result<Vec<u8>, Errno> {
+        let ret = json::encode_to_str::<Req>(req);
+        match ret {
+            Ok(ret) => {
+                return Ok(ret.into_bytes());
+            }
+            Err(err) => {
+                error!("encode: failed to encode req for {} of leader: {}, err: {}", path, self.machine, err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
+    fn decode<Resp: Default + DeserializeOwned>(&self, path: &str, body: &[u8]) -> Result<Resp, Errno> {
+        let body = String::from_utf8_lossy(body).into_owned();
+        let ret = json::decode_from_str::<Resp>(&body);
+        match ret {
+            Ok(ret) => {
+                return Ok(ret);
+            }
+            Err(err) => {
+                error!("decode: failed to decode resp for {} from leader: {}, err: {}", path, self.machine, err);
+                return Err(Errno::Eintr);
+            }
+        }
+    }
+
+    // send the body to path of the leader, the req is sent once because the writes are appended by the leader.
+    // Etimedout is returned if the leader cannot be connected or doesn't answer in time,
+    // the other errors are Eintr, such as the connection broken after the req is sent.
+    fn send(&self, path: &str, body: Vec<u8>) -> Result<PeerResp, Errno> {
+        let url = format!("{}{}", self.url, path);
+        let req: Request<Body>;
+        let ret = Request::builder().
+                    method(Method::PUT).
+                    uri(url.clone()).
+                    header(LEADER_SECRET_HEADER, self.secret.as_str()).
+                    body(Body::from(body));
+        match ret {
+            Ok(ret) => {
+                req = ret;
+            }
+            Err(err) => {
+                error!("send: failed to create req for {}, err: {}", url, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let ret = self.exec.get_runtime().block_on(async {
+            tokio::time::timeout(self.timeout, async {
+                let resp = self.http_client.request(req).await?;
+                let status = resp.status();
+                let err_code = resp.headers().get(LEADER_ERR_CODE_HEADER).
+                    and_then(|v| v.to_str().ok()).
+                    and_then(|v| v.parse::<i32>().ok());
+                let body = hyper::body::to_bytes(resp.into_body()).await?;
+                Ok::<_, hyper::Error>((status, err_code, body))
+            }).await
+        });
+        match ret {
+            Ok(Ok((status, err_code, body))) => {
+                if !status.is_success() {
+                    error!("send: got status: {} from {}", status, url);
+                    return Err(Errno::Eintr);
+                }
+                return Ok(PeerResp{
+                    err_code: err_code.unwrap_or(Errno::Esucc as i32),
+                    body: body.to_vec(),
+                });
+            }
+            Ok(Err(err)) => {
+                error!("send: failed to send req to {}, err: {}", url, err);
+                if err.is_connect() {
+                    return Err(Errno::Etimedout);
+                }
+                return Err(Errno::Eintr);
+            }
+            Err(_) => {
+                error!("send: req to {} timed out after {:?}", url, self.timeout);
+                return Err(Errno::Etimedout);
+            }
+        }
+    }
+}
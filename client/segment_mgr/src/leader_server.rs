@@ -0,0 +1,315 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use hyper::{Body, Request, Response, Server, StatusCode};
+use hyper::service::{make_service_fn, service_fn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use common::error::Errno;
+use common::json;
+use common::runtime::Executor;
+use log::{info, warn, error};
+use crate::leader::Leader;
+use crate::leader_message::{ReqLeaderOpen, ReqLeaderRead, ReqLeaderIno,
+    ReqLeaderTruncate, ReqLeaderFsync, ReqLeaderMigrate, RespLeaderResult, RespLeaderWrite, RespLeaderSize};
+use crate::leader_message::{URL_LEADER_OPEN, URL_LEADER_READ, URL_LEADER_WRITE, URL_LEADER_FILE_SIZE,
+    URL_LEADER_TRUNCATE, URL_LEADER_FLUSH, URL_LEADER_FSYNC, URL_LEADER_CLOSE, URL_LEADER_MIGRATE};
+use crate::leader_message::{LEADER_SECRET_HEADER, LEADER_ERR_CODE_HEADER, LEADER_QUERY_INO, LEADER_QUERY_OFFSET};
+
+// how long stop waits for the connections to be closed, the idle keep-alive connections of the peers
+// may not be closed by the graceful shutdown.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+// LeaderServer serves the io forwarded by the LeaderPeer of the other machines with the local leader.
+pub struct LeaderServer {
+    exec: Executor,
+    stop_tx: Option<oneshot::Sender<()>>,
+    server_handle: Option<JoinHandle<()>>,
+}
+
+impl LeaderServer {
+    pub fn start(bind_addr: &String, port: u16, secret: &String, leader: Arc<dyn Leader>, exec: &Executor) -> Result<Self, Errno> {
+        let addr: SocketAddr;
+        let ret = format!("{}:{}", bind_addr, port).parse::<SocketAddr>();
+        match ret {
+            Ok(ret) => {
+                addr = ret;
+            }
+            Err(err) => {
+                error!("LeaderServer: got invalid bind addr: {}, port: {}, err: {}", bind_addr, port, err);
+                return Err(Errno::Einval);
+            }
+        }
+        if secret.is_empty() {
+            error!("LeaderServer: the secret is not set");
+            return Err(Errno::Einval);
+        }
+        let secret = Arc::new(secret.clone());
+        // the listener must be created in the runtime.
+        let _guard = exec.get_runtime().enter();
+        let builder;
+        let ret = Server::try_bind(&addr);
+        match ret {
+            Ok(ret) => {
+                builder = ret;
+            }
+            Err(err) => {
+                error!("LeaderServer: failed to bind {}, err: {}", addr, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let make_svc = make_service_fn(move |_conn| {
+            let leader = leader.clone();
+            let secret = secret.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    serve(leader.clone(), secret.clone(), req)
+                }))
+            }
+        });
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+        let server = builder.serve(make_svc).with_graceful_shutdown(async {
+            stop_rx.await.ok();
+        });
+        let server_handle = exec.get_runtime().spawn(async move {
+            let ret = server.await;
+            if let Err(err) = ret {
+                error!("LeaderServer: server exits with err: {}", err);
+            }
+        });
+        info!("LeaderServer: listening on {}", addr);
+        Ok(LeaderServer{
+            exec: exec.clone(),
+            stop_tx: Some(stop_tx),
+            server_handle: Some(server_handle),
+        })
+    }
+
+    // stop accepting the requests and wait for the ones being served, at most STOP_TIMEOUT.
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(());
+        }
+        if let Some(mut handle) = self.server_handle.take() {
+            let ret = self.exec.get_runtime().block_on(async {
+                tokio::time::timeout(STOP_TIMEOUT, &mut handle).await
+            });
+            match ret {
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => {
+                    error!("LeaderServer: failed to wait for the server to stop, err: {}", err);
+                }
+                Err(_) => {
+                    warn!("LeaderServer: the connections are not closed in {:?}, abort the server", STOP_TIMEOUT);
+                    handle.abort();
+                }
+            }
+        }
+        info!("LeaderServer: stopped");
+    }
+}
+
+async fn serve(leader: Arc<dyn Leader>, secret: Arc<String>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let path = String::from(req.uri().path());
+    let authorized = match req.headers().get(LEADER_SECRET_HEADER) {
+        Some(v) => is_same_secret(v.as_bytes(), secret.as_bytes()),
+        None => false,
+    };
+    if !authorized {
+        error!("serve: got unauthorized req for {}", path);
+        return Ok(to_response(StatusCode::UNAUTHORIZED, Vec::new()));
+    }
+    let query = String::from(req.uri().query().unwrap_or(""));
+    let body: Vec<u8>;
+    let ret = hyper::body::to_bytes(req.into_body()).await;
+    match ret {
+        Ok(ret) => {
+            body = ret.to_vec();
+        }
+        Err(err) => {
+            error!("serve: failed to read body for {}, err: {}", path, err);
+            return Ok(to_response(StatusCode::BAD_REQUEST, Vec::new()));
+        }
+    }
+    // the leader blocks on the io, so it runs out of the async threads.
+    let ret = tokio::task::spawn_blocking(move || {
+        dispatch(&*leader, &path, &query, &body)
+    }).await;
+    match ret {
+        Ok(Ok(ret)) => {
+            return Ok(ret);
+        }
+        Ok(Err(status)) => {
+            return Ok(to_response(status, Vec::new()));
+        }
+        Err(err) => {
+            error!("serve: failed to run the request, err: {}", err);
+            return Ok(to_response(StatusCode::INTERNAL_SERVER_ERROR, Vec::new()));
+        }
+    }
+}
+
+// compare all the bytes, so that the time taken doesn't tell how many leading bytes match.
+fn is_same_secret(got: &[u8], secret: &[u8]) -> bool {
+    if got.len() != secret.len() {
+        return false;
+    }
+    let mut diff: u8 = 0;
+    for (a, b) in got.iter().zip(secret.iter()) {
+        diff |= a ^ b;
+    }
+    diff == 0
+}
+
+fn to_response(status: StatusCode, body: Vec<u8>) -> Response<Body> {
+    let mut resp = Response::new(Body::from(body));
+    *resp.status_mut() = status;
+    resp
+}
+
+// the data read is the body, err_code is in the header.
+fn to_data_response(err: Errno, data: Vec<u8>) -> Response<Body> {
+    let mut resp = to_response(StatusCode::OK, data);
+    resp.headers_mut().insert(LEADER_ERR_CODE_HEADER, hyper::header::HeaderValue::from(err as i32));
+    resp
+}
+
+// get the u64 value of key in the query, such as ino=1&offset=0.
+fn get_query_u64(path: &str, query: &str, key: &str) -> Result<u64, StatusCode> {
+    for kv in query.split('&') {
+        let mut it = kv.splitn(2, '=');
+        if it.next() != Some(key) {
+            continue;
+        }
+        let ret = it.next().unwrap_or("").parse::<u64>();
+        match ret {
+            Ok(ret) => {
+                return Ok(ret);
+            }
+            Err(err) => {
+                error!("get_query_u64: got invalid {} in query: {} for {}, err: {}", key, query, path, err);
+                return Err(StatusCode::BAD_REQUEST);
+            }
+        }
+    }
+    error!("get_query_u64: no {} in query: {} for {}", key, query, path);
+    Err(StatusCode::BAD_REQUEST)
+}
+
+fn decode<T: Default + DeserializeOwned>(path: &str, body: &[u8]) -> Result<T, StatusCode> {
+    let body = String::from_utf8_lossy(body).into_owned();
+    let ret = json::decode_from_str::<T>(&body);
+    match ret {
+        Ok(ret) => {
+            return Ok(ret);
+        }
+        Err(err) => {
+            error!("decode: failed to decode req for {}, err: {}", path, err);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+}
+
+fn encode<T: Default + Serialize>(path: &str, resp: &T) -> Result<Response<Body>, StatusCode> {
+    let ret = json::encode_to_str::<T>(resp);
+    match ret {
+        Ok(ret) => {
+            return Ok(to_response(StatusCode::OK, ret.into_bytes()));
+        }
+        Err(err) => {
+            error!("encode: failed to encode resp for {}, err: {}", path, err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+}
+
+fn to_resp_result(path: &str, err: Errno) -> Result<Response<Body>, StatusCode> {
+    let resp = RespLeaderResult{
+        err_code: err as i32,
+    };
+    encode(path, &resp)
+}
+
+fn to_resp_size(path: &str, ret: Result<u64, Errno>) -> Result<Response<Body>, StatusCode> {
+    let mut resp: RespLeaderSize = Default::default();
+    match ret {
+        Ok(ret) => {
+            resp.size = ret;
+        }
+        Err(err) => {
+            resp.err_code = err as i32;
+        }
+    }
+    encode(path, &resp)
+}
+
+// call the leader for the request of path, the errors of the leader are returned in the err_code of the response.
+fn dispatch(leader: &dyn Leader, path: &str, query: &str, body: &[u8]) -> Result<Response<Body>, StatusCode> {
+    match path {
+        URL_LEADER_OPEN => {
+            let req = decode::<ReqLeaderOpen>(path, body)?;
+            to_resp_result(path, leader.open(req.ino, req.size, req.token))
+        }
+        URL_LEADER_READ => {
+            let req = decode::<ReqLeaderRead>(path, body)?;
+            let ret = leader.read(req.ino, req.offset, req.size);
+            match ret {
+                Ok(ret) => {
+                    Ok(to_data_response(Errno::Esucc, ret))
+                }
+                Err(err) => {
+                    Ok(to_data_response(err, Vec::new()))
+                }
+            }
+        }
+        URL_LEADER_WRITE => {
+            let ino = get_query_u64(path, query, LEADER_QUERY_INO)?;
+            let offset = get_query_u64(path, query, LEADER_QUERY_OFFSET)?;
+            let mut resp: RespLeaderWrite = Default::default();
+            let ret = leader.write(ino, offset, body);
+            match ret {
+                Ok(ret) => {
+                    resp.id0 = ret.id0;
+                    resp.id1 = ret.id1;
+                    resp.offset = ret.offset;
+                    resp.size = ret.size;
+                }
+                Err(err) => {
+                    resp.err_code = err as i32;
+                }
+            }
+            encode(path, &resp)
+        }
+        URL_LEADER_FILE_SIZE => {
+            let req = decode::<ReqLeaderIno>(path, body)?;
+            to_resp_size(path, leader.get_file_size(req.ino))
+        }
+        URL_LEADER_TRUNCATE => {
+            let req = decode::<ReqLeaderTruncate>(path, body)?;
+            to_resp_result(path, leader.truncate(req.ino, req.size))
+        }
+        URL_LEADER_FLUSH => {
+            let req = decode::<ReqLeaderIno>(path, body)?;
+            to_resp_result(path, leader.flush(req.ino))
+        }
+        URL_LEADER_FSYNC => {
+            let req = decode::<ReqLeaderFsync>(path, body)?;
+            to_resp_result(path, leader.fsync(req.ino, req.datasync))
+        }
+        URL_LEADER_CLOSE => {
+            let req = decode::<ReqLeaderIno>(path, body)?;
+            to_resp_result(path, leader.close(req.ino))
+        }
+        URL_LEADER_MIGRATE => {
+            let req = decode::<ReqLeaderMigrate>(path, body)?;
+            to_resp_result(path, leader.migrate(req.ino, &req.machine))
+        }
+        _ => {
+            error!("dispatch: got unknown path: {}", path);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
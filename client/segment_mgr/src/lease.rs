@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// the lease of the leaderships held by this machine. meta server keeps them for timeout after the last
+// heartbeat, then the files may be taken over by the other machines. the local leader refuses the changes
+// once the lease expires, so that a leader cut off from meta server doesn't ack the writes which are
+// overwritten by the new leader.
+pub struct Lease {
+    timeout: Duration,
+    // the instants are kept as the milliseconds since base.
+    base: Instant,
+    // when the last successful heartbeat was sent.
+    renewed_ms: AtomicU64,
+}
+
+impl Lease {
+    // the lease starts from now, timeout is in seconds and 0 means the lease never expires.
+    pub fn new(timeout: u64) -> Self {
+        Lease{
+            timeout: Duration::from_secs(timeout),
+            base: Instant::now(),
+            renewed_ms: AtomicU64::new(0),
+        }
+    }
+
+    // renew the lease by the successful heartbeat sent at sent, the lease in meta server starts after it.
+    pub fn renew(&self, sent: Instant) {
+        let ms = sent.saturating_duration_since(self.base).as_millis() as u64;
+        self.renewed_ms.fetch_max(ms, Ordering::SeqCst);
+    }
+
+    pub fn is_valid(&self) -> bool {
+        if self.timeout.as_millis() == 0 {
+            return true;
+        }
+        let now = self.base.elapsed().as_millis() as u64;
+        now < self.renewed_ms.load(Ordering::SeqCst) + self.timeout.as_millis() as u64
+    }
+}
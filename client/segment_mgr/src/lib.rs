@@ -5,7 +5,16 @@ pub mod leader;
 pub mod file_handle;
 pub mod heartbeat_mgr;
 pub mod segment_sync;
+pub mod lease;
+pub mod io_gate;
 mod leader_local;
+mod leader_follower;
+mod file_reader;
+mod segment_writer;
+mod write_buffer;
 mod leader_not_support;
+pub mod leader_peer;
+pub mod leader_server;
+pub mod leader_message;
 mod segment_sync_handler;
 mod segment_state;
\ No newline at end of file
@@ -1,12 +1,14 @@
 extern crate tokio;
 extern crate hash_ring;
+extern crate libc;
 
+use std::ffi::CString;
 use std::sync::Arc;
-use crate::types::DataDir;
+use crate::types::{DataDir, CacheCapacity};
 use common::{error::Errno, numbers::NumberOp};
 use common::config::Config;
 use metaservice_mgr::mgr::MetaServiceMgr;
-use metaservice_mgr::types::{Segment, Block};
+use metaservice_mgr::types::{Segment, Block, FileLeader};
 use hash_ring::HashRing;
 use log::error;
 
@@ -43,6 +45,15 @@ impl SegmentMgr {
         Ok(segs)
     }
 
+    pub fn get_file_leader(&self, ino: u64) -> Result<FileLeader, Errno> {
+        self.meta_service_mgr.get_file_leader(ino)
+    }
+
+    // hand the leadership of ino over to machine, token is the one held by this machine.
+    pub fn migrate_leader(&self, ino: u64, machine: &String, token: u64) -> Result<FileLeader, Errno> {
+        self.meta_service_mgr.migrate_leader(ino, machine, token)
+    }
+
     pub fn new_segment(&self, leader: &String) -> Segment {
         let l: String;
         if leader == "" {
@@ -93,8 +104,8 @@ impl SegmentMgr {
         self.data_dirs[idx].dir.clone()
     }
 
-    pub fn update_segments(&self, ino: u64, segs: &Vec<Segment>, removed_segs: &Vec<Segment>) -> Errno {
-        let ret = self.meta_service_mgr.update_file_segments(ino, &segs, &removed_segs);
+    pub fn update_segments(&self, ino: u64, token: u64, segs: &Vec<Segment>, removed_segs: &Vec<Segment>) -> Errno {
+        let ret = self.meta_service_mgr.update_file_segments(ino, token, &segs, &removed_segs);
         if !ret.is_success() {
             error!("update_segments: failed to update segments for ino: {}, err: {:?}", ino, ret);
             return ret;
@@ -102,7 +113,64 @@ impl SegmentMgr {
         return ret;
     }
 
+    // capacity of data dir is size * num, if num is 0, the dir is only limited by the disk it resides.
+    pub fn get_cache_capacity(&self) -> CacheCapacity {
+        let mut cap = CacheCapacity::default();
+        for d in &self.data_dirs {
+            let disk_total: u64;
+            let disk_free: u64;
+            let ret = self.get_disk_space(&d.dir);
+            match ret {
+                Ok(ret) => {
+                    disk_total = ret.0;
+                    disk_free = ret.1;
+                }
+                Err(err) => {
+                    error!("get_cache_capacity: failed to get disk space for dir: {}, err: {:?}", d.dir, err);
+                    continue;
+                }
+            }
+            if d.num == 0 {
+                cap.total += disk_total;
+                cap.free += disk_free;
+                continue;
+            }
+            let total = d.size * d.num as u64;
+            cap.total += total;
+            if disk_free < total {
+                cap.free += disk_free;
+            } else {
+                cap.free += total;
+            }
+        }
+        cap
+    }
+
     // private member functions.
+    // returns the (total, available) bytes of the disk which dir resides.
+    fn get_disk_space(&self, dir: &String) -> Result<(u64, u64), Errno> {
+        let path: CString;
+        let ret = CString::new(dir.as_bytes());
+        match ret {
+            Ok(ret) => {
+                path = ret;
+            }
+            Err(err) => {
+                error!("get_disk_space: invalid dir: {}, err: {}", dir, err);
+                return Err(Errno::Eintr);
+            }
+        }
+        let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::statvfs(path.as_ptr(), &mut st) };
+        if ret != 0 {
+            error!("get_disk_space: failed to statvfs dir: {}, err: {}", dir, std::io::Error::last_os_error());
+            return Err(Errno::Eintr);
+        }
+        let total = st.f_blocks as u64 * st.f_frsize as u64;
+        let free = st.f_bavail as u64 * st.f_frsize as u64;
+        Ok((total, free))
+    }
+
     fn get_segment_dir_idx(&self, id0: u64, id1: u64) -> usize {
         let id = NumberOp::to_u128(id0, id1);
         let idx = self.dirs_sharder.get_node(id.to_string());
@@ -1,15 +1,16 @@
 
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::time::Duration;
 use common::thread::Thread;
 use common::error::Errno;
-use crossbeam_channel::{Sender, unbounded, bounded};
+use crossbeam_channel::{Sender, RecvTimeoutError, unbounded, bounded};
 use io_engine::cache_store::CacheStore;
 use io_engine::backend_storage::BackendStore;
 use metaservice_mgr::meta_store::MetaStore;
 use metaservice_mgr::types::Segment;
 
-use crate::types::ChangedSegsUpdate;
+use crate::types::{ChangedSegsUpdate, ChangedSegsSync};
 use crate::types::MetaSyncOp;
 use crate::{segment_sync_handler::SegSyncHandler, types::{SegDownload, SegSyncOp, SegUpload}};
 use log::error;
@@ -50,6 +51,7 @@ impl SegSyncer {
             id1: id1,
             dir: dir.clone(),
             offset: offset,
+            tx: None,
         };
         let ret = self.op_tx.send(SegSyncOp::OpUpload(op));
         match ret {
@@ -64,6 +66,38 @@ impl SegSyncer {
         }
     }
 
+    // upload the segment and wait until the upload finishes.
+    pub fn upload_segment_sync(&self, dir: &String, id0: u64, id1: u64, offset: u64) -> Errno {
+        let (tx, rx) = bounded::<Errno>(1);
+        let op = SegUpload{
+            id0: id0,
+            id1: id1,
+            dir: dir.clone(),
+            offset: offset,
+            tx: Some(tx),
+        };
+        let ret = self.op_tx.send(SegSyncOp::OpUpload(op));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("upload_segment_sync: failed to send upload op for id0: {}, id1: {}, offset: {}, err: {}",
+                id0, id1, offset, err);
+                return Errno::Eintr;
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return ret;
+            }
+            Err(err) => {
+                error!("upload_segment_sync: failed to recv upload result for id0: {}, id1: {}, offset: {}, err: {}",
+                id0, id1, offset, err);
+                return Errno::Eintr;
+            }
+        }
+    }
+
     pub fn download_segment(&self, dir: &String, id0: u64, id1: u64, offset: u64, capacity: u64) -> Errno {
         let op = SegDownload{
             id0: id0,
@@ -85,9 +119,11 @@ impl SegSyncer {
         }
     }
 
-    pub fn update_changed_segments(&self, ino: u64, segs: HashMap<u128, Segment>, garbages: HashMap<u128, Segment>) -> Errno{
+    // token is the fencing token of the leader, the update is rejected by meta server if it is stale.
+    pub fn update_changed_segments(&self, ino: u64, token: u64, segs: HashMap<u128, Segment>, garbages: HashMap<u128, Segment>) -> Errno{
         let op = ChangedSegsUpdate{
             ino: ino,
+            token: token,
             segs: segs,
             garbages: garbages,
         };
@@ -103,6 +139,61 @@ impl SegSyncer {
             }
         }
     }
+
+    // wait until all the changed segments of ino sent before are updated into meta server.
+    pub fn sync_changed_segments(&self, ino: u64) -> Errno {
+        let (tx, rx) = bounded::<Errno>(1);
+        let op = ChangedSegsSync{
+            ino: ino,
+            tx: tx,
+        };
+        let ret = self.meta_sync_tx.send(MetaSyncOp::OpSyncChangedSegs(op));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("sync_changed_segments: failed to send sync req for ino: {}, err: {}", ino, err);
+                return Errno::Eintr;
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return ret;
+            }
+            Err(err) => {
+                error!("sync_changed_segments: failed to recv sync result for ino: {}, err: {}", ino, err);
+                return Errno::Eintr;
+            }
+        }
+    }
+
+    // wait until all the running uploads and downloads finish, used on shutdown.
+    // returns Etimedout if they don't finish in timeout.
+    pub fn drain(&self, timeout: Duration) -> Errno {
+        let (tx, rx) = bounded::<Errno>(1);
+        let ret = self.op_tx.send(SegSyncOp::OpDrain(tx));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("drain: failed to send drain op, err: {}", err);
+                return Errno::Eintr;
+            }
+        }
+        let ret = rx.recv_timeout(timeout);
+        match ret {
+            Ok(ret) => {
+                return ret;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                error!("drain: the segments are not synced in {:?}", timeout);
+                return Errno::Etimedout;
+            }
+            Err(err) => {
+                error!("drain: failed to recv drain result, err: {}", err);
+                return Errno::Eintr;
+            }
+        }
+    }
 }
 
 impl Drop for SegSyncer{
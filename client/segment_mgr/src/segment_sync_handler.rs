@@ -27,6 +27,10 @@ pub struct SegSyncHandler{
     meta_sync_rx: Receiver<MetaSyncOp>,
     stop_rx: Receiver<u8>,
     seg_state_machines: HashMap<u128, SegStateMachine>,
+    // the waiters which wait for the upload of the segment to finish.
+    upload_waiters: HashMap<u128, Vec<Sender<Errno>>>,
+    // the waiters which wait for all the segment state machines to finish.
+    drain_waiters: Vec<Sender<Errno>>,
 }
 
 impl SegSyncHandler{
@@ -53,6 +57,8 @@ impl SegSyncHandler{
             meta_sync_rx: meta_sync_rx,
             stop_rx: stop_rx,
             seg_state_machines: HashMap::new(),
+            upload_waiters: HashMap::new(),
+            drain_waiters: Vec::new(),
         }
     }
 
@@ -130,6 +136,10 @@ impl SegSyncHandler{
             SegSyncOp::OpUpload(op) => {
                 // we will skip the op if it is being processed.
                 let seg_id = NumberOp::to_u128(op.id0, op.id1);
+                // the waiter is notified when the running sync of the segment finishes.
+                if let Some(tx) = op.tx {
+                    self.upload_waiters.entry(seg_id).or_insert(Vec::new()).push(tx);
+                }
                 if self.seg_state_machines.contains_key(&seg_id) {
                     //println!("SegSyncHandler::do_op: seg(id0: {}, id1: {}) is being processed, skip upload op",
                 //op.id0, op.id1);
@@ -149,6 +159,7 @@ impl SegSyncHandler{
                 if !ret.is_success(){
                     error!("SegSyncHandler::OpUpload: failed to open segment id0:{}, id1: {}, dir: {}, err: {:?}",
                     op.id0, op.id1, op.dir, ret);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                 }
             }
 
@@ -171,20 +182,35 @@ impl SegSyncHandler{
                 if !ret.is_success(){
                     error!("SegSyncHandler::OpDownload: failed to open segment id0: {}, id1: {}, dir: {}, err: {:?}",
                     op.id0, op.id1, op.dir, ret);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                 }
             }
+
+            SegSyncOp::OpDrain(tx) => {
+                info!("SegSyncHandler::OpDrain: {} segments are being synced", self.seg_state_machines.len());
+                self.drain_waiters.push(tx);
+                self.notify_drain_waiters();
+            }
         }
     }
 
     fn do_meta_sync_op(&self, op: MetaSyncOp){
         match op{
             MetaSyncOp::OpUpdateChangedSegs(msg) => {
-                let ret = self.meta_store.update_changed_segments(msg.ino, &msg.segs, &msg.garbages);
+                let ret = self.meta_store.update_changed_segments(msg.ino, msg.token, &msg.segs, &msg.garbages);
                 if !ret.is_success(){
                     error!("SegSyncOp::OpUpdateChangedSegs: failed to update changed segments for ino: {}, err: {:?}",
                     msg.ino, ret);
                 }
             }
+            MetaSyncOp::OpSyncChangedSegs(msg) => {
+                // the meta store will send the result through msg.tx.
+                let ret = self.meta_store.sync_changed_segments(msg.ino, msg.tx);
+                if !ret.is_success(){
+                    error!("SegSyncOp::OpSyncChangedSegs: failed to sync changed segments for ino: {}, err: {:?}",
+                    msg.ino, ret);
+                }
+            }
         }
     }
 
@@ -217,7 +243,7 @@ impl SegSyncHandler{
                 op.id0, op.id1, s.get_current_state());
                 // close the segment and remove the seg state machine.
                 self.cache_store.close(op.id0, op.id1);
-                self.seg_state_machines.remove(&seg_id);
+                self.remove_seg_state(seg_id, Errno::Eintr);
                 return;
             }
             // check whether open failed or not.
@@ -225,7 +251,7 @@ impl SegSyncHandler{
                 error!("handle_cache_open: failed to open id0: {}, id1: {}, dir: {}, err: {:?}",
                 op.id0, op.id1, s.get_dir(), op.err);
                 self.cache_store.close(op.id0, op.id1);
-                self.seg_state_machines.remove(&seg_id);
+                self.remove_seg_state(seg_id, Errno::Eintr);
                 return;
             }
             // get next state to process.
@@ -239,7 +265,7 @@ impl SegSyncHandler{
                         dir: {}, offset: {}, err: {:?}", op.id0, op.id1, s.get_dir(), s.get_offset(), ret);
                         // close the segment and remove the seg state machine.
                         self.cache_store.close(op.id0, op.id1);
-                        self.seg_state_machines.remove(&seg_id);
+                        self.remove_seg_state(seg_id, Errno::Eintr);
                     }
                 }
                 SegState::BackendRead => {
@@ -250,7 +276,7 @@ impl SegSyncHandler{
                         error!("SegSyncHandler::handle_cache_open: failed to perform backend read for seg id0: {}, 
                         id1: {}, dir: {}, offset: {}, err: {:?}", op.id0, op.id1, s.get_dir(), s.get_offset(), ret);
                         self.cache_store.close(op.id0, op.id1);
-                        self.seg_state_machines.remove(&seg_id);
+                        self.remove_seg_state(seg_id, Errno::Eintr);
                     }
                 }
                 _ => {
@@ -258,7 +284,7 @@ impl SegSyncHandler{
                     next_state);
                     // close the segment and remove the seg state machine.
                     self.cache_store.close(op.id0, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                 }
             }
             return;
@@ -285,17 +311,21 @@ impl SegSyncHandler{
             match next_state {
                 SegState::BackendWrite => {
                     if !op.err.is_success() {
+                        let err: Errno;
                         if op.err.is_eof(){
                             error!("SegSyncHandler::handle_cache_read: got eof for seg: id0: {}, id1: {}, 
                             offset: {}.", op.id0, op.id1, s.get_offset());
-                            s.set_state(SegState::CacheClose);                            
+                            s.set_state(SegState::CacheClose);
+                            // all the data is uploaded.
+                            err = Errno::Esucc;
                         } else {
                             error!("SegSyncHandler::handle_cache_read: failed to read seg: id0: {}, id1: {},
                             offset: {}, err: {:?}", op.id0, op.id1, s.get_offset(), op.err);
+                            err = Errno::Eintr;
                         }
                         // close & remove the seg state machine.
                         self.cache_store.close(op.id0, op.id1);
-                        self.seg_state_machines.remove(&seg_id);
+                        self.remove_seg_state(seg_id, err);
                         return;
                     }
                     if let Some(data) = op.data {
@@ -307,7 +337,7 @@ impl SegSyncHandler{
                             offset: {}, err: {:?}", op.id0, op.id1, s.get_offset(), ret);
                             // close & remove the state machine.
                             self.cache_store.close(op.id0, op.id1);
-                            self.seg_state_machines.remove(&seg_id);
+                            self.remove_seg_state(seg_id, Errno::Eintr);
                         }
                         return;
                     }
@@ -317,7 +347,7 @@ impl SegSyncHandler{
                     error!("SegSyncHandler::handle_cache_read: got invalid state: {:?} for seg id0: {}, id1: {}",
                     next_state, op.id0, op.id1);
                     self.cache_store.close(op.id0, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                     return;
                 }
             }
@@ -335,14 +365,14 @@ impl SegSyncHandler{
                 error!("handle_cache_write: seg id0: {}, id1: {}, failed to write offset: {}, err: {:?}",
                 op.id0, op.id1, s.get_offset(), op.err);
                 self.cache_store.close(op.id0, op.id1);
-                self.seg_state_machines.remove(&seg_id);
+                self.remove_seg_state(seg_id, Errno::Eintr);
                 return;
             }
             if !s.is_state_match(&SegState::CacheWrite){
                 error!("handle_cache_write: seg id0: {}, id1: {}, got invalid state: {:?}, expected: CacheWrite",
                 op.id0, op.id1, s.get_current_state());
                 self.cache_store.close(op.id0, op.id1);
-                self.seg_state_machines.remove(&seg_id);
+                self.remove_seg_state(seg_id, Errno::Eintr);
                 return;
             }
             let next_state = s.get_next_state();
@@ -356,7 +386,7 @@ impl SegSyncHandler{
                         error!("handle_cache_write: failed to perform backend read for seg id0: {}, id1: {}, offset: {}, err: {:?}",
                     op.id0, op.id1, s.get_offset(), ret);
                         self.cache_store.close(op.id0, op.id1);
-                        self.seg_state_machines.remove(&seg_id);
+                        self.remove_seg_state(seg_id, Errno::Eintr);
                         return;
                     }
                 }
@@ -364,7 +394,7 @@ impl SegSyncHandler{
                     error!("handle_cache_write: got invalid next_state: {:?} for seg id0: {}, id1: {}",
                     next_state, op.id0, op.id1);
                     self.cache_store.close(op.id1, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                     return;
                 }
             }
@@ -399,14 +429,17 @@ impl SegSyncHandler{
         let seg_id = NumberOp::to_u128(op.id0, op.id1);
         // check the whether read op is successful or not.
         if !op.err.is_success(){
+            let err: Errno;
             if op.err.is_eof() || op.err.is_invalid_range() {
                 error!("handle_backend_store_read: got eof for seg: id0: {}, id1: {}", op.id0, op.id1);
+                err = Errno::Esucc;
             } else {
                 error!("handle_backend_store_read: read failed for seg: id0: {}, id1: {}, err: {:?}",
                 op.id0, op.id1, op.err);
+                err = Errno::Eintr;
             }
             self.cache_store.close(op.id0, op.id1);
-            self.seg_state_machines.remove(&seg_id);
+            self.remove_seg_state(seg_id, err);
             return;
         }
         if let Some(s) = self.seg_state_machines.get_mut(&seg_id){
@@ -414,7 +447,7 @@ impl SegSyncHandler{
                 error!("handle_backend_store_read: got invalid seg state, expected BackendRead, got: {:?}
                 for seg id0: {}, id1: {}", s.get_current_state(), op.id0, op.id1);
                 self.cache_store.close(op.id0, op.id1);
-                self.seg_state_machines.remove(&seg_id);
+                self.remove_seg_state(seg_id, Errno::Eintr);
                 return;
             }
             let next_state = s.get_next_state();
@@ -428,20 +461,20 @@ impl SegSyncHandler{
                             error!("handle_backend_store_read: failed to perform cache store write for seg: 
                             id0: {}, id1: {}, offset: {}, err: {:?}", op.id0, op.id1, s.get_offset(), ret);
                             self.cache_store.close(op.id0, op.id1);
-                            self.seg_state_machines.remove(&seg_id);
+                            self.remove_seg_state(seg_id, Errno::Eintr);
                         }
                         return;
                     }
                     error!("handle_backend_store_read: no more data read for seg: id0: {}, id1: {}, offset: {}",
                     op.id0, op.id1, s.get_offset());
                     self.cache_store.close(op.id0, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                 }
                 _ => {
                     error!("handle_backend_store_read: got invalid next state for seg: id0: {}, id1: {}, offset: {},
                     current state: {:?}, next state: {:?}", op.id0, op.id1, s.get_offset(), s.get_current_state(), next_state);
                     self.cache_store.close(op.id1, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                 }
             }
             return;
@@ -459,7 +492,7 @@ impl SegSyncHandler{
                  for seg id0: {}, id1: {}", s.get_current_state(), op.id0, op.id1);
                  // close the cache & remove the state machines.
                  self.cache_store.close(op.id0, op.id1);
-                 self.seg_state_machines.remove(&seg_id);
+                 self.remove_seg_state(seg_id, Errno::Eintr);
                  return;
             }
             // check whether former write op is successful or not.
@@ -473,7 +506,7 @@ impl SegSyncHandler{
                     error!("handle_backend_store_write: write failed for id0: {}, id1: {} with offset: {}, err: {:?}",
                     op.id0, op.id1, s.get_offset(), op.err);
                     self.cache_store.close(op.id0, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                     return;
                 }
             }
@@ -490,7 +523,7 @@ impl SegSyncHandler{
                         error!("handle_backend_store_write: failed to send update segment for id0: {}, id1: {}, offset: {}, err: {:?}",
                         op.id0, op.id1, offset, ret);
                         self.cache_store.close(op.id0, op.id1);
-                        self.seg_state_machines.remove(&seg_id);
+                        self.remove_seg_state(seg_id, Errno::Eintr);
                     }
                     return;
                 }
@@ -498,7 +531,7 @@ impl SegSyncHandler{
                     error!("handle_backend_store_write: got invalid state: {:?}, expected MetaUpload for id0: {}, id1: {}",
                     next_state, op.id0, op.id1);
                     self.cache_store.close(op.id0, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                     return;
                 }
             }
@@ -525,7 +558,7 @@ impl SegSyncHandler{
                  for seg id0: {}, id1: {}", s.get_current_state(), op.id0, op.id1);
                  // close the cache & remove the state machines.
                  self.cache_store.close(op.id0, op.id1);
-                 self.seg_state_machines.remove(&seg_id);
+                 self.remove_seg_state(seg_id, Errno::Eintr);
                  return;
             }
             // check whether former op is successful or not.
@@ -533,7 +566,7 @@ impl SegSyncHandler{
                 error!("handle_meta_store_upload_seg: failed to upload meta offset: {} for id0: {}, id1: {}, err: {:?}",
                 s.get_offset(), op.id0, op.id1, op.err);
                 self.cache_store.close(op.id0, op.id1);
-                self.seg_state_machines.remove(&seg_id);
+                self.remove_seg_state(seg_id, Errno::Eintr);
                 return;
             }
             // get next state to process.
@@ -547,7 +580,7 @@ impl SegSyncHandler{
                         error!("handle_meta_store_upload_seg: failed to preform cache read for id0: {}, id1: {},
                         offset: {}, err: {:?}", op.id0, op.id1, s.get_offset(), ret);
                         self.cache_store.close(op.id0, op.id1);
-                        self.seg_state_machines.remove(&seg_id);
+                        self.remove_seg_state(seg_id, Errno::Eintr);
                     }
                     return;
                 }
@@ -555,10 +588,48 @@ impl SegSyncHandler{
                     error!("handle_meta_store_upload_seg: got invalid seg next state {:?} for id0: {}, id1: {},
                         expected CacheRead", next_state, op.id0, op.id1);
                     self.cache_store.close(op.id0, op.id1);
-                    self.seg_state_machines.remove(&seg_id);
+                    self.remove_seg_state(seg_id, Errno::Eintr);
                     return;
                 }
             }
         }
     }
+
+    // remove the state machine of the segment and notify the waiters with err.
+    fn remove_seg_state(&mut self, seg_id: u128, err: Errno){
+        self.seg_state_machines.remove(&seg_id);
+        if let Some(waiters) = self.upload_waiters.remove(&seg_id) {
+            for tx in waiters {
+                let result: Errno;
+                if err.is_success() {
+                    result = Errno::Esucc;
+                } else {
+                    result = Errno::Eintr;
+                }
+                let ret = tx.send(result);
+                match ret {
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("remove_seg_state: failed to notify the waiter for seg: {}, err: {}", seg_id, e);
+                    }
+                }
+            }
+        }
+        self.notify_drain_waiters();
+    }
+
+    fn notify_drain_waiters(&mut self){
+        if !self.seg_state_machines.is_empty() {
+            return;
+        }
+        for tx in self.drain_waiters.drain(..) {
+            let ret = tx.send(Errno::Esucc);
+            match ret {
+                Ok(_) => {}
+                Err(e) => {
+                    error!("notify_drain_waiters: failed to notify the waiter, err: {}", e);
+                }
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use common::error::Errno;
+use metaservice_mgr::types::{Segment, Block};
+use io_engine::cache_store::CacheStore;
+use io_engine::backend_storage::BackendStore;
+use log::{info, error};
+use crate::segment_sync::SegSyncer;
+use crate::file_handle::FileHandleMgr;
+use crate::types::BlockIo;
+use crate::segment_mgr::SegmentMgr;
+use crate::lease::Lease;
+
+// SegmentWriter writes the data of the opened files into their segments and updates their block maps.
+// it is shared by the leader and its write buffer, the files are opened in handle_mgr of the leader.
+#[derive(Clone)]
+pub struct SegmentWriter {
+    cache_store: Arc<dyn CacheStore>,
+    backend_store: Arc<dyn BackendStore>,
+    sync_mgr: Arc<SegSyncer>,
+    segment_mgr: Arc<SegmentMgr>,
+    // the data is not written once the leaderships of this machine expire.
+    lease: Arc<Lease>,
+}
+
+impl SegmentWriter {
+    pub fn new(cache: Arc<dyn CacheStore>, backend: Arc<dyn BackendStore>, sync_mgr: Arc<SegSyncer>,
+        mgr: Arc<SegmentMgr>, lease: Arc<Lease>) -> Self {
+        SegmentWriter{
+            cache_store: cache,
+            backend_store: backend,
+            sync_mgr: sync_mgr,
+            segment_mgr: mgr,
+            lease: lease,
+        }
+    }
+
+    // Etimedout if the lease is expired, ino may be taken over by another machine already.
+    pub fn check_lease(&self, ino: u64) -> Errno {
+        if self.lease.is_valid() {
+            return Errno::Esucc;
+        }
+        error!("check_lease: the lease of this machine is expired, refuse the change of ino: {}", ino);
+        return Errno::Etimedout;
+    }
+
+    // append data of ino at offset into its last segment, a new segment is added if the last one is full.
+    // the segment is written into the backend store directly if its cache file isn't synced yet.
+    pub fn write(&self, handle_mgr: &FileHandleMgr, ino: u64, offset: u64, data: &[u8]) -> Result<BlockIo, Errno> {
+        let ret = self.check_lease(ino);
+        if !ret.is_success() {
+            return Err(ret);
+        }
+        let last_segment: Vec<u64>;
+        let ret = handle_mgr.get_last_segment(ino);
+        match ret {
+            Ok(ret) => {
+                last_segment = ret;
+            }
+            Err(err) => {
+                error!("write: failed to get_last_segment for ino: {}, err: {:?}", ino, err);
+                return Err(err);
+            }
+        }
+        if last_segment.is_empty() {
+            error!("write: failed to get_last_segment for ino: {}, no segments found.", ino);
+            return Err(Errno::Enoent);
+        }
+        let mut id0 = last_segment[0];
+        let mut id1 = last_segment[1];
+        let mut seg_max_size = last_segment[2];
+        let mut seg_size = last_segment[3];
+        let need_sync = last_segment[4];
+        let mut direct_io = false;
+        if need_sync == 1 {
+            direct_io = true;
+        }
+        
+        //println!("write: seg(id0: {}, id1: {}, max_size: {}, ino: {}, offset: {})", id0, id1, seg_max_size, ino, offset);
+        loop {
+            //println!("write: seg(id0: {}, id1: {}, max_size: {})", id0, id1, seg_max_size);
+            let seg_dir = self.segment_mgr.get_segment_dir(id0, id1);
+            // must check whether cache size is smaller than segment size or not. if so, write the backend directly.
+            // or if O_DIRECT, write to backend directly too.
+            
+            if direct_io {
+                // write to backend store directly.
+                let ret = self.backend_store.write(id0, id1, seg_size, data);
+                if ret.err.is_success() {
+                    // write block success.
+                    let b = Block {
+                        ino: ino,
+                        generation: 0,
+                        offset: offset,
+                        seg_id0: id0,
+                        seg_id1: id1,
+                        seg_start_addr: ret.offset,
+                        size: ret.nwrite as i64,
+                    };
+                    let err = self.add_block(handle_mgr, ino, id0, id1, &b);
+                    if !err.is_success() {
+                        error!("write: failed to add_block{:?} for ino: {} with offset: {}, err: {:?}", b, ino, offset, ret);
+                        return Err(err);
+                    }
+                    return Ok(BlockIo{
+                        id0: id0,
+                        id1: id1,
+                        offset: ret.offset,
+                        size: ret.nwrite,
+                    });
+                }
+                error!("write: backend_store write failed for seg: id0: {}, id1: {}, offset: {}, err: {:?}",
+                id0, id1, seg_size, ret.err);
+                return Err(ret.err);
+            }
+            let ret = self.cache_store.write(id0, id1, &seg_dir, offset, seg_max_size, data);
+            match ret {
+                Ok(r) => {
+                    // write block success.
+                    let b = Block {
+                        ino: ino,
+                        generation: 0,
+                        offset: offset,
+                        seg_id0: id0,
+                        seg_id1: id1,
+                        seg_start_addr: r.offset,
+                        size: r.nwrite as i64,
+                    };
+                    let ret = self.add_block(handle_mgr, ino, id0, id1, &b);
+                    if !ret.is_success() {
+                        error!("write: failed to add_block{:?} for ino: {} with offset: {}, err: {:?}", b, ino, offset, ret);
+                        return Err(ret);
+                    }
+                    
+                    return Ok(BlockIo{
+                        id0: id0,
+                        id1: id1,
+                        offset: r.offset,
+                        size: r.nwrite,
+                    });
+                    // currently, will update the segments in close api.
+                    // upload the block to meta server.
+                    /*let ret = self.segment_mgr.upload_block(ino, id0, id1, &b);
+                    if ret.is_success(){
+                        return Ok(BlockIo{
+                            id0: id0,
+                            id1: id1,
+                            offset: r.offset,
+                            size: r.nwrite,
+                        });
+                    }
+                    error!("write: failed to upload block{:?} for ino: {}, err: {:?}", b, ino, ret);
+                    return Err(ret);*/
+                }
+                Err(err) => {
+                    if err.is_enospc() {
+                        error!("SegmentWriter: write: segment(id0: {}, id1: {}, dir: {}) has no space left for ino: {} with offset: {}",
+                            id0, id1, seg_dir, ino, offset);
+                        let seg = self.segment_mgr.new_segment(&String::from(""));
+                        handle_mgr.add_segment(ino, &seg);
+                        id0 = seg.seg_id0;
+                        id1 = seg.seg_id1;
+                        seg_max_size = seg.capacity;
+                        // when create new segment, set the current seg_size to 0.
+                        seg_size = 0;
+                        info!("SegmentWriter: write: add new segment(id0: {}, id1: {}) for ino: {} with offset: {}",
+                    id0, id1, ino, offset);
+                        continue;
+                    }
+                    error!("SegmentWriter: write: failed to get response for seg(id0: {}, id1: {}) of ino: {} with offset: {}, err: {:?}", 
+                        id0, id1, ino, offset, err);
+                    return Err(err);
+                }
+            }
+            
+        }
+    }
+
+    // send the changed segments of ino to meta server with the fencing token of its handle.
+    pub fn update_changed_segments(&self, handle_mgr: &FileHandleMgr, ino: u64,
+        segs: HashMap<u128, Segment>, garbages: HashMap<u128, Segment>) -> Errno {
+        let token: u64;
+        let ret = handle_mgr.get_leader_token(ino);
+        match ret {
+            Ok(ret) => {
+                token = ret;
+            }
+            Err(err) => {
+                error!("update_changed_segments: failed to get leader token for ino: {}, err: {:?}", ino, err);
+                return err;
+            }
+        }
+        self.sync_mgr.update_changed_segments(ino, token, segs, garbages)
+    }
+
+    pub fn add_block(&self, handle_mgr: &FileHandleMgr, ino: u64, id0: u64, id1: u64, b: &Block)->Errno{
+        let (segs, garbages, ret) = handle_mgr.add_block(ino, id0, id1, &b);
+        if !ret.is_success() {
+            error!("SegmentWriter::add_block: failed to add_block{:?} for ino: {}  err: {:?}", b, ino, ret);
+            return ret;
+        }
+        let ret = self.update_changed_segments(handle_mgr, ino, segs, garbages);
+        if !ret.is_success(){
+            error!("SegmentWriter::add_block: failed to update changed segments for ino: {}, err: {:?}", ino, ret);
+            return ret;
+        }
+        return Errno::Esucc;
+    }
+}
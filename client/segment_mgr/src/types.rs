@@ -13,6 +13,13 @@ pub struct DataDir {
     pub num: u32,
 }
 
+// capacity of the local cache, summed over all the data dirs.
+#[derive(Debug, Default)]
+pub struct CacheCapacity {
+    pub total: u64,
+    pub free: u64,
+}
+
 
 
 // below structs are for Leader usage.
@@ -51,12 +58,32 @@ pub struct FileHandle {
     pub segments_index: HashMap<u128, usize>,
     pub block_tree: IntervalTree<Block>,
     pub seg_status: HashMap<u128, SegStatus>,
+    // size of the file, the range beyond the last block is a hole.
+    pub size: u64,
     pub is_dirty: u8,
     pub reference: i64,
+    // the fencing token of the leadership, sent with the segment updates of the file.
+    pub leader_token: u64,
+}
+
+// the content of FileHandle without the block tree, the nodes of the tree can't be shared by the threads,
+// so the handle is sent between the threads as this and its tree is rebuilt by the receiver.
+#[derive(Debug)]
+pub struct FileHandleData {
+    pub ino: u64,
+    pub leader: String,
+    // the segments without blocks.
+    pub segments: Vec<Segment>,
+    pub blocks: Vec<Block>,
+    pub seg_status: HashMap<u128, SegStatus>,
+    pub size: u64,
+    pub is_dirty: u8,
+    pub reference: i64,
+    pub leader_token: u64,
 }
 
 impl FileHandle {
-    pub fn create(ino: u64, leader: String, segments: Vec<Segment>) -> Self {
+    pub fn create(ino: u64, leader: String, leader_token: u64, segments: Vec<Segment>, size: u64) -> Self {
         let mut h = FileHandle{
             ino: ino,
             leader: leader,
@@ -64,8 +91,10 @@ impl FileHandle {
             segments_index: HashMap::new(),
             block_tree: IntervalTree::new(Block::default()),
             seg_status: HashMap::new(),
+            size: 0,
             is_dirty: 0,
             reference: 1,
+            leader_token: leader_token,
         };
 
         let mut idx = 0;
@@ -91,6 +120,14 @@ impl FileHandle {
                 h.add_block(block);
             }
         }
+        h.size = size;
+        let last_node = h.block_tree.get_largest_node();
+        if last_node.borrow().is_not_nil() {
+            let last_block = last_node.borrow().get_value();
+            if last_block.offset + last_block.size as u64 > h.size {
+                h.size = last_block.offset + last_block.size as u64;
+            }
+        }
         
         return h;
     }
@@ -102,8 +139,10 @@ impl FileHandle {
             segments_index: HashMap::new(),
             block_tree: self.block_tree.clone(),
             seg_status: self.seg_status.clone(),
+            size: self.size,
             is_dirty: self.is_dirty,
             reference: self.reference,
+            leader_token: self.leader_token,
         };
         let mut idx = 0;
         for s in &self.segments {
@@ -114,6 +153,40 @@ impl FileHandle {
         return handle;
     }
     
+    pub fn to_data(&self) -> FileHandleData {
+        FileHandleData{
+            ino: self.ino,
+            leader: self.leader.clone(),
+            segments: self.segments.iter().map(|s| s.copy()).collect(),
+            blocks: self.block_tree.traverse(),
+            seg_status: self.seg_status.clone(),
+            size: self.size,
+            is_dirty: self.is_dirty,
+            reference: self.reference,
+            leader_token: self.leader_token,
+        }
+    }
+
+    pub fn from_data(data: FileHandleData) -> Self {
+        let mut handle = FileHandle::new(data.ino);
+        handle.leader = data.leader;
+        handle.seg_status = data.seg_status;
+        handle.size = data.size;
+        handle.is_dirty = data.is_dirty;
+        handle.reference = data.reference;
+        handle.leader_token = data.leader_token;
+        let mut idx = 0;
+        for s in data.segments {
+            handle.segments_index.insert(NumberOp::to_u128(s.seg_id0, s.seg_id1), idx);
+            handle.segments.push(s);
+            idx += 1;
+        }
+        for b in data.blocks {
+            handle.add_block(b);
+        }
+        return handle;
+    }
+
     pub fn new(ino: u64)->Self{
         let mut h = FileHandle{
             ino: ino,
@@ -122,8 +195,10 @@ impl FileHandle {
             segments_index: HashMap::new(),
             block_tree: IntervalTree::new(Block::default()),
             seg_status: HashMap::new(),
+            size: 0,
             is_dirty: 0,
             reference: 1,
+            leader_token: 0,
         };
         return h;
     }
@@ -208,6 +283,47 @@ impl FileHandle {
         segs.insert(id, s);
     }
 
+    // remove the range [start, end) from the block tree, the blocks across the boundaries are split.
+    // the kept parts are added into the changed segments and the removed parts into the garbages.
+    pub fn cut_blocks(&mut self, start: u64, end: u64, changed_segs: &mut ChangedSegments) {
+        let nodes = self.block_tree.get(start, end);
+        for n in &nodes {
+            let b = n.borrow().get_value();
+            let block_end = b.offset + b.size as u64;
+            if block_end <= start || b.offset >= end {
+                continue;
+            }
+            self.block_tree.delete(n);
+            if b.offset < start {
+                let mut head = b.clone();
+                head.size = (start - b.offset) as i64;
+                self.block_tree.insert_node(head.offset, start, head.clone());
+                self.add_changed_block(&mut changed_segs.segments, &head);
+            }
+            let mut cut_start = b.offset;
+            if start > cut_start {
+                cut_start = start;
+            }
+            let mut cut_end = block_end;
+            if end < cut_end {
+                cut_end = end;
+            }
+            let mut garbage = b.clone();
+            garbage.offset = cut_start;
+            garbage.seg_start_addr += cut_start - b.offset;
+            garbage.size = (cut_end - cut_start) as i64;
+            self.add_garbage_block(&mut changed_segs.garbages, garbage);
+            if block_end > end {
+                let mut tail = b;
+                tail.seg_start_addr += end - tail.offset;
+                tail.offset = end;
+                tail.size = (block_end - end) as i64;
+                self.block_tree.insert_node(end, block_end, tail.clone());
+                self.add_changed_block(&mut changed_segs.segments, &tail);
+            }
+        }
+    }
+
     pub fn add_garbage_block(&mut self, segs: &mut HashMap<u128, Segment>, b: Block){
         if b.ino != self.ino {
             panic!("add_garbage_block: got invalid ino: {} for block: offset: {}, size: {}, expect: ino: {}",
@@ -256,7 +372,7 @@ pub struct MsgUpdateHandle{
 #[derive(Debug)]
 pub struct MsgQueryHandle{
     pub ino: u64,
-    pub tx: Sender<Option<FileHandle>>,
+    pub tx: Sender<Option<FileHandleData>>,
 }
 
 #[derive(Debug)]
@@ -281,7 +397,28 @@ impl MsgAddBlock{
             Ok(_) => {
                 return Errno::Esucc;
             }
-            Err(err) => {
+            Err(_) => {
+                return Errno::Eintr;
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MsgTruncate{
+    pub ino: u64,
+    pub size: u64,
+    pub tx: Sender<ChangedSegments>,
+}
+
+impl MsgTruncate{
+    pub fn response(&self, segs: ChangedSegments)->Errno{
+        let ret = self.tx.send(segs);
+        match ret{
+            Ok(_) => {
+                return Errno::Esucc;
+            }
+            Err(_) => {
                 return Errno::Eintr;
             }
         }
@@ -308,6 +445,18 @@ pub struct MsgGetBlocks{
     pub tx: Sender<Vec<Block>>,
 }
 
+#[derive(Debug)]
+pub struct MsgGetLeaderToken{
+    pub ino: u64,
+    pub tx: Sender<Option<u64>>,
+}
+
+#[derive(Debug)]
+pub struct MsgGetFileSize{
+    pub ino: u64,
+    pub tx: Sender<Option<u64>>,
+}
+
 #[derive(Debug)]
 pub struct MsgSetSegStatus{
     pub ino: u64,
@@ -319,6 +468,8 @@ pub struct MsgSetSegStatus{
 #[derive(Debug)]
 pub struct MsgOpenHandle{
     pub ino: u64,
+    // the fencing token of the open, the handle takes the newer one.
+    pub token: u64,
     pub tx: Sender<String>,
 }
 
@@ -330,9 +481,13 @@ pub struct MsgGetFileSegments{
 
 #[derive(Debug)]
 pub enum MsgFileHandleOp{
-    Add(FileHandle),
+    Add(FileHandleData),
     AddBlock(MsgAddBlock),
     Del(u64),
+    // drop the handle regardless of its references.
+    Remove(u64),
+    // replace the segments and blocks of the opened handle, keep its references.
+    Refresh(FileHandleData),
     Get(MsgQueryHandle),
     OpenHandle(MsgOpenHandle),
     GetBlocks(MsgGetBlocks),
@@ -340,6 +495,11 @@ pub enum MsgFileHandleOp{
     AddSegment(MsgAddSegment),
     SetSegStatus(MsgSetSegStatus),
     GetFileSegments(MsgGetFileSegments),
+    Truncate(MsgTruncate),
+    GetFileSize(MsgGetFileSize),
+    GetLeaderToken(MsgGetLeaderToken),
+    // list the inos of all the opened files.
+    GetInos(Sender<Vec<u64>>),
 }
 
 #[derive(Debug)]
@@ -348,6 +508,8 @@ pub struct SegUpload{
     pub id1: u64,
     pub dir: String,
     pub offset: u64, // from where to upload.
+    // if set, the result is sent through it when the upload finishes.
+    pub tx: Option<Sender<Errno>>,
 }
 
 #[derive(Debug)]
@@ -362,17 +524,50 @@ pub struct SegDownload{
 #[derive(Debug)]
 pub struct ChangedSegsUpdate{
     pub ino: u64,
+    // the fencing token of the leader of ino.
+    pub token: u64,
     pub segs: HashMap<u128, Segment>,
     pub garbages: HashMap<u128, Segment>,
 }
+#[derive(Debug)]
+pub struct ChangedSegsSync{
+    pub ino: u64,
+    pub tx: Sender<Errno>,
+}
+
 #[derive(Debug)]
 pub enum SegSyncOp{
     OpUpload(SegUpload),
     OpDownload(SegDownload),
+    // wait until all the running uploads and downloads finish.
+    OpDrain(Sender<Errno>),
 }
 
 
 #[derive(Debug)]
 pub enum MetaSyncOp{
     OpUpdateChangedSegs(ChangedSegsUpdate),
-}
\ No newline at end of file
+    OpSyncChangedSegs(ChangedSegsSync),
+}
+#[derive(Debug)]
+pub struct MsgBufferWrite{
+    pub ino: u64,
+    pub offset: u64,
+    pub data: Vec<u8>,
+    pub tx: Sender<Errno>,
+}
+
+#[derive(Debug)]
+pub struct MsgBufferFlush{
+    pub ino: u64,
+    // whether the error of the buffer failed to be written in background before is returned and cleared.
+    pub report: bool,
+    pub tx: Sender<Errno>,
+}
+
+#[derive(Debug)]
+pub enum WriteBufferOp{
+    Write(MsgBufferWrite),
+    // write the buffer of ino into its segments.
+    Flush(MsgBufferFlush),
+}
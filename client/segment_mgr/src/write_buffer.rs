@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crossbeam_channel::{Sender, Receiver, bounded, select, tick};
+use common::config::WriteBufferConfig;
+use common::error::Errno;
+use log::{info, warn, error};
+use crate::file_handle::FileHandleMgr;
+use crate::segment_writer::SegmentWriter;
+use crate::types::{MsgBufferWrite, MsgBufferFlush, WriteBufferOp};
+
+// WriteBufferMgr coalesces the consecutive writes of each ino into one larger write of its segments,
+// so that the block map is updated into meta server once for them.
+// the buffers are kept and written by the write buffer threads, each ino is handled by the thread ino % threads.
+pub struct WriteBufferMgr {
+    // 0 means the writes are not buffered.
+    size: u64,
+    // the op channels of the threads.
+    op_txs: Vec<Sender<WriteBufferOp>>,
+    stop_tx: Sender<u32>,
+    // taken by the first stop.
+    write_buffer_ths: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl WriteBufferMgr {
+    pub fn create(cfg: &WriteBufferConfig, writer: SegmentWriter, handle_mgr: Arc<FileHandleMgr>) -> Self {
+        let mut threads = cfg.threads;
+        if threads == 0 {
+            threads = 1;
+        }
+        let (stop_tx, stop_rx) = bounded::<u32>(threads as usize);
+        let mut op_txs = Vec::new();
+        let mut ths = Vec::new();
+        if cfg.size > 0 {
+            for _ in 0..threads {
+                let (tx, rx) = bounded::<WriteBufferOp>(100);
+                let mut handler = WriteBufferHandler{
+                    size: cfg.size,
+                    delay: cfg.delay(),
+                    writer: writer.clone(),
+                    handle_mgr: handle_mgr.clone(),
+                    buffers: HashMap::new(),
+                    errors: HashMap::new(),
+                    op_rx: rx,
+                    stop_rx: stop_rx.clone(),
+                };
+                op_txs.push(tx);
+                ths.push(thread::spawn(move || handler.start()));
+            }
+        }
+        WriteBufferMgr{
+            size: cfg.size,
+            op_txs: op_txs,
+            stop_tx: stop_tx,
+            write_buffer_ths: Mutex::new(ths),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.size > 0
+    }
+
+    // the op channel of the thread handling ino.
+    fn op_tx(&self, ino: u64) -> Option<&Sender<WriteBufferOp>> {
+        if self.op_txs.is_empty() {
+            return None;
+        }
+        self.op_txs.get((ino % self.op_txs.len() as u64) as usize)
+    }
+
+    // buffer the data of ino at offset, the buffer is written into segments once it is full.
+    pub fn write(&self, ino: u64, offset: u64, data: &[u8]) -> Errno {
+        let op_tx: &Sender<WriteBufferOp>;
+        match self.op_tx(ino) {
+            Some(ret) => {
+                op_tx = ret;
+            }
+            None => {
+                error!("write: the write buffer is disabled, cannot buffer the write of ino: {}", ino);
+                return Errno::Enotsupp;
+            }
+        }
+        let (tx, rx) = bounded::<Errno>(1);
+        let msg = MsgBufferWrite{
+            ino: ino,
+            offset: offset,
+            data: data.to_vec(),
+            tx: tx,
+        };
+        let ret = op_tx.send(WriteBufferOp::Write(msg));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("write: failed to send write of ino: {} with offset: {}, err: {}", ino, offset, err);
+                return Errno::Eintr;
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return ret;
+            }
+            Err(err) => {
+                error!("write: failed to recv write result of ino: {} with offset: {}, err: {}", ino, offset, err);
+                return Errno::Eintr;
+            }
+        }
+    }
+
+    // write the buffer of ino into its segments.
+    // if report is set, it also returns the error of the buffer of ino failed to be written in background before,
+    // otherwise the error is kept for the next report, such as fsync or close.
+    pub fn flush(&self, ino: u64, report: bool) -> Errno {
+        let op_tx: &Sender<WriteBufferOp>;
+        match self.op_tx(ino) {
+            Some(ret) => {
+                op_tx = ret;
+            }
+            None => {
+                return Errno::Esucc;
+            }
+        }
+        let (tx, rx) = bounded::<Errno>(1);
+        let msg = MsgBufferFlush{
+            ino: ino,
+            report: report,
+            tx: tx,
+        };
+        let ret = op_tx.send(WriteBufferOp::Flush(msg));
+        match ret {
+            Ok(_) => {}
+            Err(err) => {
+                error!("flush: failed to send flush of ino: {}, err: {}", ino, err);
+                return Errno::Eintr;
+            }
+        }
+        let ret = rx.recv();
+        match ret {
+            Ok(ret) => {
+                return ret;
+            }
+            Err(err) => {
+                error!("flush: failed to recv flush result of ino: {}, err: {}", ino, err);
+                return Errno::Eintr;
+            }
+        }
+    }
+
+    // the remaining buffers are written before the threads exit.
+    pub fn stop(&self) {
+        let ths: Vec<JoinHandle<()>>;
+        match self.write_buffer_ths.lock() {
+            Ok(mut ret) => {
+                ths = std::mem::take(&mut *ret);
+            }
+            Err(err) => {
+                error!("failed to stop write buffer, err: {}", err);
+                return;
+            }
+        }
+        if ths.is_empty() {
+            return;
+        }
+        // each thread exits after it gets one stop signal.
+        for _ in 0..ths.len() {
+            let ret = self.stop_tx.send(1);
+            if let Err(err) = ret {
+                error!("failed to stop write buffer, err: {}", err);
+            }
+        }
+        for h in ths {
+            let ret = h.join();
+            if ret.is_err() {
+                error!("WriteBufferMgr failes to stop, join failed");
+                return;
+            }
+        }
+        warn!("WriteBufferMgr has stopped.");
+    }
+}
+
+// the data of the consecutive writes of an ino from offset.
+struct WriteBuffer {
+    offset: u64,
+    data: Vec<u8>,
+    // when the first write is buffered.
+    since: Instant,
+}
+
+struct WriteBufferHandler {
+    size: u64,
+    delay: Duration,
+    writer: SegmentWriter,
+    handle_mgr: Arc<FileHandleMgr>,
+    // ino-->WriteBuffer
+    buffers: HashMap<u64, WriteBuffer>,
+    // the errors of the buffers failed to be written, they are kept until the next flush of the ino with report.
+    errors: HashMap<u64, Errno>,
+    op_rx: Receiver<WriteBufferOp>,
+    stop_rx: Receiver<u32>,
+}
+
+impl WriteBufferHandler {
+    fn start(&mut self) {
+        // the buffers are written in [delay, 2*delay) after their first writes.
+        let ticker = tick(self.delay);
+        loop {
+            select! {
+                recv(self.op_rx) -> msg => {
+                    let op: WriteBufferOp;
+                    match msg {
+                        Ok(msg) => {
+                            op = msg;
+                        }
+                        Err(err) => {
+                            error!("WriteBufferHandler: failed to got op msg, err: {}", err);
+                            continue;
+                        }
+                    }
+                    match op {
+                        WriteBufferOp::Write(msg) => {
+                            self.write(msg);
+                        }
+                        WriteBufferOp::Flush(msg) => {
+                            self.flush(&msg);
+                        }
+                    }
+                },
+                recv(ticker) -> _ => {
+                    self.flush_expired();
+                },
+                recv(self.stop_rx) -> _ => {
+                    let inos: Vec<u64> = self.buffers.keys().cloned().collect();
+                    info!("WriteBufferHandler: write {} buffers before stop", inos.len());
+                    for ino in inos {
+                        let ret = self.write_buffer(ino);
+                        if !ret.is_success() {
+                            error!("WriteBufferHandler: failed to write buffer of ino: {} before stop, err: {:?}", ino, ret);
+                        }
+                    }
+                    break;
+                },
+            }
+        }
+    }
+
+    fn write(&mut self, msg: MsgBufferWrite) {
+        let ino = msg.ino;
+        let mut ret = Errno::Esucc;
+        // continue the buffer if the write follows it.
+        let mut appended = false;
+        if let Some(b) = self.buffers.get_mut(&ino) {
+            if b.offset + b.data.len() as u64 == msg.offset {
+                b.data.extend_from_slice(&msg.data);
+                appended = true;
+            }
+        }
+        if !appended {
+            // write the former buffer first to keep the order of the writes.
+            ret = self.write_buffer(ino);
+            if ret.is_success() {
+                self.buffers.insert(ino, WriteBuffer{
+                    offset: msg.offset,
+                    data: msg.data,
+                    since: Instant::now(),
+                });
+            }
+        }
+        if ret.is_success() {
+            let mut full = false;
+            if let Some(b) = self.buffers.get(&ino) {
+                full = b.data.len() as u64 >= self.size;
+            }
+            if full {
+                ret = self.write_buffer(ino);
+            }
+        }
+        let err = msg.tx.send(ret);
+        if let Err(err) = err {
+            error!("WriteBufferHandler: failed to send write result of ino: {}, err: {}", ino, err);
+        }
+    }
+
+    fn flush(&mut self, msg: &MsgBufferFlush) {
+        let mut ret = self.write_buffer(msg.ino);
+        if !msg.report {
+            // the data is lost, keep the error until it is reported.
+            if !ret.is_success() {
+                self.errors.entry(msg.ino).or_insert(ret);
+            }
+        } else if let Some(err) = self.errors.remove(&msg.ino) {
+            if ret.is_success() {
+                ret = err;
+            }
+        }
+        let err = msg.tx.send(ret);
+        if let Err(err) = err {
+            error!("WriteBufferHandler: failed to send flush result of ino: {}, err: {}", msg.ino, err);
+        }
+    }
+
+    fn flush_expired(&mut self) {
+        let mut inos: Vec<u64> = Vec::new();
+        for (ino, b) in &self.buffers {
+            if b.since.elapsed() >= self.delay {
+                inos.push(*ino);
+            }
+        }
+        for ino in inos {
+            let ret = self.write_buffer(ino);
+            if !ret.is_success() {
+                self.errors.entry(ino).or_insert(ret);
+            }
+        }
+    }
+
+    // write the buffer of ino into its segments, the block map is updated into meta server once for it.
+    fn write_buffer(&mut self, ino: u64) -> Errno {
+        let b: WriteBuffer;
+        match self.buffers.remove(&ino) {
+            Some(ret) => {
+                b = ret;
+            }
+            None => {
+                return Errno::Esucc;
+            }
+        }
+        let mut written: usize = 0;
+        while written < b.data.len() {
+            let offset = b.offset + written as u64;
+            let ret = self.writer.write(&self.handle_mgr, ino, offset, &b.data[written..]);
+            match ret {
+                Ok(ret) => {
+                    if ret.size == 0 {
+                        error!("WriteBufferHandler: write_buffer: wrote nothing for ino: {} with offset: {}", ino, offset);
+                        return Errno::Eintr;
+                    }
+                    written += ret.size as usize;
+                }
+                Err(err) => {
+                    error!("WriteBufferHandler: write_buffer: failed to write ino: {} with offset: {}, size: {}, err: {:?}",
+                    ino, offset, b.data.len() - written, err);
+                    return err;
+                }
+            }
+        }
+        return Errno::Esucc;
+    }
+}
@@ -5,7 +5,7 @@ use segment_mgr::types::FileHandle;
 
 #[test]
 fn test_file_handle_mgr_start()->Result<(), String> {
-    let mut mgr = FileHandleMgr::create();
+    let mgr = FileHandleMgr::create();
     mgr.stop();
     let ret = mgr.get(1);
     match ret {
@@ -23,7 +23,7 @@ fn test_file_handle_mgr_start()->Result<(), String> {
 
 #[test]
 fn test_file_handle_mgr_add() -> Result<(), String>{
-    let mut mgr = FileHandleMgr::create();
+    let mgr = FileHandleMgr::create();
     let h1 = FileHandle::new(1);
     let ret = mgr.add(&h1);
     if !ret.is_success(){
@@ -51,7 +51,7 @@ fn test_file_handle_mgr_add() -> Result<(), String>{
 #[test]
 fn test_file_handle_mgr_del() -> Result<(), String>{
     let ino: u64 = 1;
-    let mut mgr = FileHandleMgr::create();
+    let mgr = FileHandleMgr::create();
     let h1 = FileHandle::new(ino);
     let ret = mgr.add(&h1);
     if !ret.is_success(){
@@ -95,10 +95,110 @@ fn test_file_handle_mgr_del() -> Result<(), String>{
     }
 }
 
+#[test]
+fn test_file_handle_mgr_remove() -> Result<(), String>{
+    let ino: u64 = 1;
+    let mgr = FileHandleMgr::create();
+    let h1 = FileHandle::create(ino, String::from("leader"), 0, Vec::new(), 0);
+    let ret = mgr.add(&h1);
+    if !ret.is_success(){
+        mgr.stop();
+        return Err(String::from("failed to add handle."));
+    }
+    // open it again, remove drops the handle regardless of the references.
+    let ret = mgr.open_handle(ino, 0);
+    if ret.is_err() {
+        mgr.stop();
+        return Err(format!("failed to open handle of ino: {}", ino));
+    }
+
+    let ret = mgr.remove(ino);
+    if !ret.is_success() {
+        mgr.stop();
+        return Err(String::from("failed to remove file handle."));
+    }
+
+    let ret = mgr.get(ino);
+    mgr.stop();
+    match ret {
+        Ok(ret) => {
+            return Err(format!("got valid handle of ino: {} even if the handle is removed.", ret.ino));
+        }
+        Err(err) => {
+            if err.is_enoent() {
+                return Ok(());
+            }
+            return Err(format!("the get api returns the incorrect error: {:?}", err));
+        }
+    }
+}
+
+#[test]
+fn test_file_handle_mgr_refresh() -> Result<(), String>{
+    let ino: u64 = 1;
+    let mgr = FileHandleMgr::create();
+    let h1 = FileHandle::create(ino, String::from("leader"), 0, Vec::new(), 0);
+    let ret = mgr.add(&h1);
+    if !ret.is_success(){
+        mgr.stop();
+        return Err(String::from("failed to add handle."));
+    }
+    let ret = mgr.open_handle(ino, 0);
+    if ret.is_err() {
+        mgr.stop();
+        return Err(format!("failed to open handle of ino: {}", ino));
+    }
+
+    // the leader writes 10 bytes after the handle is opened.
+    let mut seg = Segment::new(&String::from("leader"));
+    seg.blocks.push(Block{
+        ino: ino,
+        generation: 0,
+        offset: 0,
+        seg_id0: seg.seg_id0,
+        seg_id1: seg.seg_id1,
+        seg_start_addr: 0,
+        size: 10,
+    });
+    let h2 = FileHandle::create(ino, String::from("leader"), 0, vec![seg], 0);
+    let ret = mgr.refresh(&h2);
+    if !ret.is_success() {
+        mgr.stop();
+        return Err(format!("failed to refresh handle of ino: {}", ino));
+    }
+    let blocks = mgr.get_blocks(ino, 0, 10);
+    if blocks.len() != 1 || blocks[0].size != 10 {
+        mgr.stop();
+        return Err(format!("got invalid blocks: {:?} after refresh", blocks));
+    }
+    let ret = mgr.get_file_size(ino);
+    match ret {
+        Ok(size) => {
+            if size != 10 {
+                mgr.stop();
+                return Err(format!("got invalid size: {} after refresh", size));
+            }
+        }
+        Err(err) => {
+            mgr.stop();
+            return Err(format!("failed to get file size, err: {:?}", err));
+        }
+    }
+
+    // the handle is still opened twice.
+    mgr.del(ino);
+    let ret = mgr.get(ino);
+    mgr.stop();
+    if ret.is_err() {
+        return Err(format!("the handle of ino: {} is dropped with references after refresh", ino));
+    }
+    return Ok(());
+}
+
 #[test]
 fn test_file_handle_get_last_segment() -> Result<(), String>{
     let ino: u64 = 1;
-    let mut mgr = FileHandleMgr::create();
+    let mgr = FileHandleMgr::create();
     let h1 = FileHandle::new(ino);
     let ret = mgr.add(&h1);
     if !ret.is_success(){
@@ -154,7 +254,7 @@ fn test_file_handle_get_last_segment() -> Result<(), String>{
         seg_start_addr: 0,
         size: 5,
     };
-    let (segs, garbages, ret) = mgr.add_block(ino, id0, id1, &b1);
+    let (_, _, ret) = mgr.add_block(ino, id0, id1, &b1);
     if !ret.is_success(){
         mgr.stop();
         return Err(format!("failed to add block"));
@@ -206,7 +306,7 @@ fn test_file_handle_get_last_segment() -> Result<(), String>{
 #[test]
 fn test_file_handle_add_block() -> Result<(), String>{
     let ino: u64 = 1;
-    let mut mgr = FileHandleMgr::create();
+    let mgr = FileHandleMgr::create();
     let h1 = FileHandle::new(ino);
     let ret = mgr.add(&h1);
     if !ret.is_success(){
@@ -232,7 +332,7 @@ fn test_file_handle_add_block() -> Result<(), String>{
         seg_start_addr: 0,
         size: 5,
     };
-    let (segs, garbages,ret) = mgr.add_block(ino, id0, id1, &b1);
+    let (_, _, ret) = mgr.add_block(ino, id0, id1, &b1);
     if !ret.is_success(){
         mgr.stop();
         return Err(format!("failed to add block"));
@@ -287,4 +387,109 @@ fn test_file_handle_add_block() -> Result<(), String>{
     }
     mgr.stop();
     return Ok(());
-}
\ No newline at end of file
+}
+#[test]
+fn test_file_handle_truncate() -> Result<(), String>{
+    let ino: u64 = 1;
+    let mgr = FileHandleMgr::create();
+    let h1 = FileHandle::new(ino);
+    let ret = mgr.add(&h1);
+    if !ret.is_success(){
+        mgr.stop();
+        return Err(String::from("failed to add handle."));
+    }
+
+    let seg = Segment::new(&String::from("local"));
+    let id0 = seg.seg_id0;
+    let id1 = seg.seg_id1;
+    mgr.add_segment(ino, &seg);
+    // [0, 5) and [10, 15)
+    let b1 = Block{
+        ino: ino,
+        generation: 0,
+        offset: 0,
+        seg_id0: id0,
+        seg_id1: id1,
+        seg_start_addr: 0,
+        size: 5,
+    };
+    mgr.add_block(ino, id0, id1, &b1);
+    let b2 = Block{
+        ino: ino,
+        generation: 0,
+        offset: 10,
+        seg_id0: id0,
+        seg_id1: id1,
+        seg_start_addr: 5,
+        size: 5,
+    };
+    mgr.add_block(ino, id0, id1, &b2);
+
+    // extending doesn't change any block.
+    let (segs, garbages, ret) = mgr.truncate(ino, 20);
+    if !ret.is_success() || !segs.is_empty() || !garbages.is_empty() {
+        mgr.stop();
+        return Err(format!("extending truncate changed blocks, segs: {:?}, garbages: {:?}, err: {:?}",
+        segs, garbages, ret));
+    }
+
+    // cut in the middle of the first block, [2, 5) and [10, 15) are garbages.
+    let (segs, garbages, ret) = mgr.truncate(ino, 2);
+    if !ret.is_success() {
+        mgr.stop();
+        return Err(format!("failed to truncate, err: {:?}", ret));
+    }
+    let id = common::numbers::NumberOp::to_u128(id0, id1);
+    let changed = &segs[&id].blocks;
+    if changed.len() != 1 || changed[0].offset != 0 || changed[0].size != 2 {
+        mgr.stop();
+        return Err(format!("got invalid changed blocks: {:?}", changed));
+    }
+    let removed = &garbages[&id].blocks;
+    if removed.len() != 2 {
+        mgr.stop();
+        return Err(format!("got invalid garbage blocks: {:?}", removed));
+    }
+    for b in removed {
+        if !((b.offset == 2 && b.seg_start_addr == 2 && b.size == 3) ||
+            (b.offset == 10 && b.seg_start_addr == 5 && b.size == 5)) {
+            mgr.stop();
+            return Err(format!("got invalid garbage block: {:?}", b));
+        }
+    }
+    let blocks = mgr.get_blocks(ino, 0, 20);
+    if blocks.len() != 1 || blocks[0].offset != 0 || blocks[0].size != 2 {
+        mgr.stop();
+        return Err(format!("got invalid blocks after truncate: {:?}", blocks));
+    }
+    mgr.stop();
+    return Ok(());
+}
+
+#[test]
+fn test_file_handle_get_inos() -> Result<(), String>{
+    let mgr = FileHandleMgr::create();
+    for ino in 1..4 {
+        let ret = mgr.add(&FileHandle::new(ino));
+        if !ret.is_success(){
+            mgr.stop();
+            return Err(format!("failed to add handle for ino: {}", ino));
+        }
+    }
+    mgr.del(2);
+    let ret = mgr.get_inos();
+    match ret {
+        Ok(mut inos) => {
+            inos.sort();
+            mgr.stop();
+            if inos != vec![1, 3] {
+                return Err(format!("got invalid inos: {:?}", inos));
+            }
+            return Ok(());
+        }
+        Err(err) => {
+            mgr.stop();
+            return Err(format!("failed to get inos, err: {:?}", err));
+        }
+    }
+}
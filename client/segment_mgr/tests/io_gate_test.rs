@@ -0,0 +1,58 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use segment_mgr::io_gate::IoGate;
+
+#[test]
+fn test_io_gate_close_waits_running_io()->Result<(), String> {
+    let gate = Arc::new(IoGate::new());
+    let closed = Arc::new(AtomicBool::new(false));
+    let io = gate.enter(1);
+    let g = gate.clone();
+    let c = closed.clone();
+    let th = thread::spawn(move || {
+        g.close(1);
+        c.store(true, Ordering::SeqCst);
+    });
+    thread::sleep(Duration::from_millis(100));
+    if closed.load(Ordering::SeqCst) {
+        return Err(format!("the gate is closed with the running io"));
+    }
+    drop(io);
+    if th.join().is_err() {
+        return Err(format!("failed to join the closing thread"));
+    }
+    if !closed.load(Ordering::SeqCst) {
+        return Err(format!("the gate is not closed after the io finishes"));
+    }
+    gate.open(1);
+    return Ok(());
+}
+
+#[test]
+fn test_io_gate_io_waits_closed_gate()->Result<(), String> {
+    let gate = Arc::new(IoGate::new());
+    let entered = Arc::new(AtomicBool::new(false));
+    gate.close(1);
+    // the io of the other inos goes on.
+    drop(gate.enter(2));
+    let g = gate.clone();
+    let e = entered.clone();
+    let th = thread::spawn(move || {
+        let _io = g.enter(1);
+        e.store(true, Ordering::SeqCst);
+    });
+    thread::sleep(Duration::from_millis(100));
+    if entered.load(Ordering::SeqCst) {
+        return Err(format!("the io enters the closed gate"));
+    }
+    gate.open(1);
+    if th.join().is_err() {
+        return Err(format!("failed to join the io thread"));
+    }
+    if !entered.load(Ordering::SeqCst) {
+        return Err(format!("the io doesn't enter the opened gate"));
+    }
+    return Ok(());
+}
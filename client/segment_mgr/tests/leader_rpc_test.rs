@@ -0,0 +1,380 @@
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use hyper::{Body, Client, Method, Request, StatusCode};
+use common::error::Errno;
+use common::json;
+use common::runtime::Executor;
+use segment_mgr::leader::Leader;
+use segment_mgr::leader_peer::LeaderPeer;
+use segment_mgr::leader_server::LeaderServer;
+use segment_mgr::leader_message::{ReqLeaderOpen, ReqLeaderRead, ReqLeaderMigrate, RespLeaderWrite};
+use segment_mgr::leader_message::{URL_LEADER_OPEN, URL_LEADER_READ, LEADER_SECRET_HEADER, LEADER_ERR_CODE_HEADER};
+use segment_mgr::types::BlockIo;
+
+const LOCALHOST: &str = "127.0.0.1";
+const SECRET: &str = "leader-secret";
+// the io of this ino fails with Enoent on the fake leader.
+const MISSING_INO: u64 = 404;
+
+// the fake leader records the calls it serves.
+struct FakeLeader {
+    calls: Mutex<Vec<String>>,
+}
+
+impl FakeLeader {
+    fn new() -> Self {
+        FakeLeader{
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn result(&self, ino: u64) -> Errno {
+        if ino == MISSING_INO {
+            return Errno::Enoent;
+        }
+        Errno::Esucc
+    }
+}
+
+impl Leader for FakeLeader {
+    fn open(&self, ino: u64, size: u64, token: u64) -> Errno {
+        self.record(format!("open {} {} {}", ino, size, token));
+        self.result(ino)
+    }
+
+    fn write(&self, ino: u64, offset: u64, data: &[u8]) -> Result<BlockIo, Errno> {
+        self.record(format!("write {} {} {}", ino, offset, String::from_utf8_lossy(data)));
+        if ino == MISSING_INO {
+            return Err(Errno::Enoent);
+        }
+        Ok(BlockIo{
+            id0: 1,
+            id1: 2,
+            offset: offset,
+            size: data.len() as u32,
+        })
+    }
+
+    fn read(&self, ino: u64, offset: u64, size: u32) -> Result<Vec<u8>, Errno> {
+        self.record(format!("read {} {} {}", ino, offset, size));
+        if ino == MISSING_INO {
+            return Err(Errno::Enoent);
+        }
+        Ok(vec![b'a'; size as usize])
+    }
+
+    fn get_file_size(&self, ino: u64) -> Result<u64, Errno> {
+        self.record(format!("size {}", ino));
+        if ino == MISSING_INO {
+            return Err(Errno::Enoent);
+        }
+        Ok(ino * 100)
+    }
+
+    fn truncate(&self, ino: u64, size: u64) -> Errno {
+        self.record(format!("truncate {} {}", ino, size));
+        self.result(ino)
+    }
+
+    fn flush(&self, ino: u64) -> Errno {
+        self.record(format!("flush {}", ino));
+        self.result(ino)
+    }
+
+    fn fsync(&self, ino: u64, datasync: bool) -> Errno {
+        self.record(format!("fsync {} {}", ino, datasync));
+        self.result(ino)
+    }
+
+    fn close(&self, ino: u64) -> Errno {
+        self.record(format!("close {}", ino));
+        self.result(ino)
+    }
+
+    fn migrate(&self, ino: u64, machine: &String) -> Errno {
+        self.record(format!("migrate {} {}", ino, machine));
+        self.result(ino)
+    }
+
+    fn release(&self) {
+    }
+}
+
+// get a port which nobody listens on.
+fn free_port() -> u16 {
+    let listener = TcpListener::bind(format!("{}:0", LOCALHOST)).unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+fn start_server(leader: &Arc<FakeLeader>, exec: &Executor) -> Result<(LeaderServer, u16), String> {
+    let port = free_port();
+    let ret = LeaderServer::start(&String::from(LOCALHOST), port, &String::from(SECRET), leader.clone(), exec);
+    match ret {
+        Ok(ret) => {
+            return Ok((ret, port));
+        }
+        Err(err) => {
+            return Err(format!("failed to start the leader server on port: {}, err: {:?}", port, err));
+        }
+    }
+}
+
+fn new_peer(port: u16, secret: &str, exec: &Executor) -> LeaderPeer {
+    LeaderPeer::new(&String::from(LOCALHOST), port, &String::from(secret), Client::new(), exec, Duration::from_secs(5))
+}
+
+// send the raw req to the leader server, returns the status, the err_code header and the body.
+fn send_raw(exec: &Executor, port: u16, path: &str, secret: Option<&str>, body: String) -> Result<(StatusCode, Option<i32>, Vec<u8>), String> {
+    let mut builder = Request::builder().
+                method(Method::PUT).
+                uri(format!("http://{}:{}{}", LOCALHOST, port, path));
+    if let Some(s) = secret {
+        builder = builder.header(LEADER_SECRET_HEADER, s);
+    }
+    let req = builder.body(Body::from(body)).unwrap();
+    let ret = exec.get_runtime().block_on(async {
+        let resp = Client::new().request(req).await?;
+        let status = resp.status();
+        let err_code = resp.headers().get(LEADER_ERR_CODE_HEADER).
+            and_then(|v| v.to_str().ok()).
+            and_then(|v| v.parse::<i32>().ok());
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        Ok::<_, hyper::Error>((status, err_code, body.to_vec()))
+    });
+    match ret {
+        Ok(ret) => {
+            return Ok(ret);
+        }
+        Err(err) => {
+            return Err(format!("failed to send req to {}, err: {}", path, err));
+        }
+    }
+}
+
+#[test]
+fn test_leader_message_encode_decode()->Result<(), String> {
+    let req = ReqLeaderOpen{
+        ino: 1,
+        size: 2,
+        token: 3,
+    };
+    let body = json::encode_to_str::<ReqLeaderOpen>(&req)?;
+    if body != r#"{"ino":1,"size":2,"token":3}"# {
+        return Err(format!("got unexpected encoded open req: {}", body));
+    }
+    let req = json::decode_from_str::<ReqLeaderMigrate>(&String::from(r#"{"ino":5,"machine":"m1"}"#))?;
+    if req.ino != 5 || req.machine != "m1" {
+        return Err(format!("got unexpected decoded migrate req: {:?}", req));
+    }
+    let resp = RespLeaderWrite{
+        err_code: Errno::Enospc as i32,
+        id0: 1,
+        id1: 2,
+        offset: 3,
+        size: 4,
+    };
+    let body = json::encode_to_str::<RespLeaderWrite>(&resp)?;
+    let decoded = json::decode_from_str::<RespLeaderWrite>(&body)?;
+    if decoded.err_code != resp.err_code || decoded.id0 != resp.id0 || decoded.id1 != resp.id1 ||
+        decoded.offset != resp.offset || decoded.size != resp.size {
+        return Err(format!("got {:?} after encoding & decoding {:?}", decoded, resp));
+    }
+    // the missing fields are rejected.
+    if json::decode_from_str::<ReqLeaderRead>(&String::from(r#"{"ino":1}"#)).is_ok() {
+        return Err(format!("the read req without offset & size is decoded"));
+    }
+    return Ok(());
+}
+
+#[test]
+fn test_leader_rpc_round_trip()->Result<(), String> {
+    let exec = Executor::create();
+    let leader = Arc::new(FakeLeader::new());
+    let (mut server, port) = start_server(&leader, &exec)?;
+    let peer = new_peer(port, SECRET, &exec);
+    let machine = String::from("m2");
+    let mut errs: Vec<String> = Vec::new();
+    let ret = peer.open(1, 10, 7);
+    if !ret.is_success() {
+        errs.push(format!("open: {:?}", ret));
+    }
+    let ret = peer.write(1, 20, b"hello");
+    match ret {
+        Ok(ret) => {
+            if ret.id0 != 1 || ret.id1 != 2 || ret.offset != 20 || ret.size != 5 {
+                errs.push(format!("write: got unexpected block: {:?}", ret));
+            }
+        }
+        Err(err) => {
+            errs.push(format!("write: {:?}", err));
+        }
+    }
+    let ret = peer.read(1, 0, 3);
+    match ret {
+        Ok(ret) => {
+            if ret != b"aaa".to_vec() {
+                errs.push(format!("read: got unexpected data: {:?}", ret));
+            }
+        }
+        Err(err) => {
+            errs.push(format!("read: {:?}", err));
+        }
+    }
+    let ret = peer.get_file_size(1);
+    match ret {
+        Ok(ret) => {
+            if ret != 100 {
+                errs.push(format!("get_file_size: got unexpected size: {}", ret));
+            }
+        }
+        Err(err) => {
+            errs.push(format!("get_file_size: {:?}", err));
+        }
+    }
+    for (name, ret) in vec![
+        ("truncate", peer.truncate(1, 30)),
+        ("flush", peer.flush(1)),
+        ("fsync", peer.fsync(1, true)),
+        ("migrate", peer.migrate(1, &machine)),
+        ("close", peer.close(1)),
+    ] {
+        if !ret.is_success() {
+            errs.push(format!("{}: {:?}", name, ret));
+        }
+    }
+    // close the idle connections of the peer, so that the server stops at once.
+    drop(peer);
+    server.stop();
+    if !errs.is_empty() {
+        return Err(format!("got errors: {:?}", errs));
+    }
+    let expect = vec!["open 1 10 7", "write 1 20 hello", "read 1 0 3", "size 1", "truncate 1 30",
+        "flush 1", "fsync 1 true", "migrate 1 m2", "close 1"];
+    let calls = leader.calls();
+    if calls != expect {
+        return Err(format!("the leader got calls: {:?}, expect: {:?}", calls, expect));
+    }
+    return Ok(());
+}
+
+#[test]
+fn test_leader_rpc_err_code()->Result<(), String> {
+    let exec = Executor::create();
+    let leader = Arc::new(FakeLeader::new());
+    let (mut server, port) = start_server(&leader, &exec)?;
+    let peer = new_peer(port, SECRET, &exec);
+    let mut errs: Vec<String> = Vec::new();
+    // the errors of the leader are returned as they are.
+    let ret = peer.open(MISSING_INO, 0, 0);
+    if !ret.is_enoent() {
+        errs.push(format!("open: {:?}", ret));
+    }
+    let ret = peer.write(MISSING_INO, 0, b"data");
+    if !ret.as_ref().err().map_or(false, |e| e.is_enoent()) {
+        errs.push(format!("write: {:?}", ret));
+    }
+    let ret = peer.read(MISSING_INO, 0, 3);
+    if !ret.as_ref().err().map_or(false, |e| e.is_enoent()) {
+        errs.push(format!("read: {:?}", ret));
+    }
+    let ret = peer.get_file_size(MISSING_INO);
+    if !ret.as_ref().err().map_or(false, |e| e.is_enoent()) {
+        errs.push(format!("get_file_size: {:?}", ret));
+    }
+    let ret = peer.flush(MISSING_INO);
+    if !ret.is_enoent() {
+        errs.push(format!("flush: {:?}", ret));
+    }
+    // the err_code of read is carried in the header, the data is the body.
+    let req = ReqLeaderRead{
+        ino: MISSING_INO,
+        offset: 0,
+        size: 3,
+    };
+    let body = json::encode_to_str::<ReqLeaderRead>(&req)?;
+    let (status, err_code, data) = send_raw(&exec, port, URL_LEADER_READ, Some(SECRET), body.clone())?;
+    if status != StatusCode::OK || err_code != Some(Errno::Enoent as i32) || !data.is_empty() {
+        errs.push(format!("read missing: got status: {}, err_code: {:?}, data: {:?}", status, err_code, data));
+    }
+    let req = ReqLeaderRead{
+        ino: 1,
+        offset: 0,
+        size: 3,
+    };
+    let body = json::encode_to_str::<ReqLeaderRead>(&req)?;
+    let (status, err_code, data) = send_raw(&exec, port, URL_LEADER_READ, Some(SECRET), body)?;
+    if status != StatusCode::OK || err_code != Some(Errno::Esucc as i32) || data != b"aaa".to_vec() {
+        errs.push(format!("read: got status: {}, err_code: {:?}, data: {:?}", status, err_code, data));
+    }
+    // the bad requests are rejected by the status.
+    let (status, _, _) = send_raw(&exec, port, URL_LEADER_OPEN, Some(SECRET), String::from("{"))?;
+    if status != StatusCode::BAD_REQUEST {
+        errs.push(format!("invalid body: got status: {}", status));
+    }
+    let (status, _, _) = send_raw(&exec, port, "/v1/leader/unknown", Some(SECRET), String::from("{}"))?;
+    if status != StatusCode::NOT_FOUND {
+        errs.push(format!("unknown path: got status: {}", status));
+    }
+    drop(peer);
+    server.stop();
+    if !errs.is_empty() {
+        return Err(format!("got errors: {:?}", errs));
+    }
+    return Ok(());
+}
+
+#[test]
+fn test_leader_rpc_secret()->Result<(), String> {
+    let exec = Executor::create();
+    let leader = Arc::new(FakeLeader::new());
+    let (mut server, port) = start_server(&leader, &exec)?;
+    let mut errs: Vec<String> = Vec::new();
+    let body = String::from(r#"{"ino":1,"size":0,"token":0}"#);
+    for secret in vec![None, Some("leader-secreT"), Some("leader-secret-"), Some("")] {
+        let (status, _, _) = send_raw(&exec, port, URL_LEADER_OPEN, secret, body.clone())?;
+        if status != StatusCode::UNAUTHORIZED {
+            errs.push(format!("secret: {:?}, got status: {}", secret, status));
+        }
+    }
+    // the peer with the wrong secret fails, but the leader is reachable, so it is not a timeout.
+    let peer = new_peer(port, "wrong-secret", &exec);
+    let ret = peer.open(1, 0, 0);
+    if ret.is_success() || ret.is_timedout() {
+        errs.push(format!("open with wrong secret: {:?}", ret));
+    }
+    drop(peer);
+    server.stop();
+    if !errs.is_empty() {
+        return Err(format!("got errors: {:?}", errs));
+    }
+    let calls = leader.calls();
+    if !calls.is_empty() {
+        return Err(format!("the leader served the unauthorized calls: {:?}", calls));
+    }
+    return Ok(());
+}
+
+#[test]
+fn test_leader_rpc_unreachable()->Result<(), String> {
+    let exec = Executor::create();
+    // nobody listens on the port.
+    let peer = new_peer(free_port(), SECRET, &exec);
+    let ret = peer.open(1, 0, 0);
+    if !ret.is_timedout() {
+        return Err(format!("open to the unreachable leader got: {:?}", ret));
+    }
+    let ret = peer.read(1, 0, 3);
+    if !ret.as_ref().err().map_or(false, |e| e.is_timedout()) {
+        return Err(format!("read from the unreachable leader got: {:?}", ret));
+    }
+    return Ok(());
+}
@@ -0,0 +1,30 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use segment_mgr::lease::Lease;
+
+#[test]
+fn test_lease_expire()->Result<(), String> {
+    let lease = Lease::new(1);
+    if !lease.is_valid() {
+        return Err(format!("the new lease is not valid"));
+    }
+    thread::sleep(Duration::from_millis(1100));
+    if lease.is_valid() {
+        return Err(format!("the lease is still valid after timeout"));
+    }
+    lease.renew(Instant::now());
+    if !lease.is_valid() {
+        return Err(format!("the lease is not valid after renew"));
+    }
+    return Ok(());
+}
+
+#[test]
+fn test_lease_disabled()->Result<(), String> {
+    let lease = Lease::new(0);
+    thread::sleep(Duration::from_millis(10));
+    if !lease.is_valid() {
+        return Err(format!("the lease with 0 timeout is expired"));
+    }
+    return Ok(());
+}
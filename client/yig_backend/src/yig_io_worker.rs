@@ -168,6 +168,11 @@ impl YigIoWorker{
             MsgFileOp::OpStat(msg_stat) => {
                 info!("stat: id0: {}, id1: {}", msg_stat.id0, msg_stat.id1);
             }
+            MsgFileOp::OpSync(msg_sync) => {
+                // the object is persisted once it is written into yig.
+                info!("sync: id0: {}, id1: {}", msg_sync.id0, msg_sync.id1);
+                msg_sync.response(Errno::Esucc);
+            }
         }
     }
 
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use common::config::Config;
+use common::error::Errno;
+use common::runtime::Executor;
+use metaservice_mgr::new_metaserver_mgr;
+use metaservice_mgr::mgr::MetaServiceMgr;
+use metaservice_mgr::types::FileLeader;
+use segment_mgr::leader_mgr::LeaderMgr;
+use log::{info, error};
+use crate::options::MigrateOptions;
+
+// move the leadership of a file to another machine, so that the operators can rebalance the hot files.
+// the current leader uploads the data of the file and hands it off through its leader server.
+pub fn migrate(cfg: &Config, opts: &MigrateOptions) -> Errno {
+    let exec = Executor::create();
+    let meta_service: Arc<dyn MetaServiceMgr>;
+    let ret = new_metaserver_mgr(cfg, &exec);
+    match ret {
+        Ok(ret) => {
+            meta_service = ret;
+        }
+        Err(err) => {
+            error!("migrate: failed to create meta service, err: {}", err);
+            return Errno::Eintr;
+        }
+    }
+    let leader: FileLeader;
+    let ret = meta_service.get_file_leader(opts.ino);
+    match ret {
+        Ok(ret) => {
+            leader = ret;
+        }
+        Err(err) => {
+            error!("migrate: failed to get leader of ino: {}, err: {:?}", opts.ino, err);
+            return err;
+        }
+    }
+    if leader.leader == opts.machine {
+        info!("migrate: ino: {} is led by {} already", opts.ino, opts.machine);
+        return Errno::Esucc;
+    }
+    if leader.expired {
+        // the leader is gone and cannot hand ino off, its data not uploaded is lost same as taking it over.
+        let ret = meta_service.migrate_leader(opts.ino, &opts.machine, leader.token);
+        match ret {
+            Ok(ret) => {
+                info!("migrate: ino: {} of the expired leader: {} is moved to {}", opts.ino, leader.leader, ret.leader);
+                return Errno::Esucc;
+            }
+            Err(err) => {
+                error!("migrate: failed to move ino: {} of the expired leader: {} to {}, err: {:?}",
+                opts.ino, leader.leader, opts.machine, err);
+                return err;
+            }
+        }
+    }
+    let ret = LeaderMgr::request_migrate(&leader.leader, opts.ino, &opts.machine, &cfg.leader_rpc_config, &exec);
+    if !ret.is_success() {
+        error!("migrate: leader: {} failed to move ino: {} to {}, err: {:?}", leader.leader, opts.ino, opts.machine, ret);
+        return ret;
+    }
+    info!("migrate: ino: {} is moved from {} to {}", opts.ino, leader.leader, opts.machine);
+    return Errno::Esucc;
+}
@@ -1,13 +1,17 @@
 mod options;
+mod admin;
 
 use std::sync::Arc;
+use std::time::Duration;
 use filesystem_mgr::{FilesystemMgr, MountOptions};
+use filesystem_mgr::shutdown;
 use common::parse_config;
 use common::runtime::Executor;
 use common::config::Config;
 use segment_mgr::segment_mgr::SegmentMgr;
 use segment_mgr::leader_mgr::LeaderMgr;
 use segment_mgr::heartbeat_mgr::HeartbeatMgr;
+use segment_mgr::lease::Lease;
 use segment_mgr::segment_sync::SegSyncer;
 use metaservice_mgr::new_metaserver_mgr;
 use metaservice_mgr::meta_store::MetaStore;
@@ -22,12 +26,22 @@ use log::{info, error};
 fn main() {
     let opts = options::parse();
     println!("{:?}", opts);
+    // the admin commands can be interrupted as usual.
+    if opts.migrate.is_none() {
+        // must be done before any thread is spawned, including the ones of log4rs.
+        let ret = shutdown::block_signals();
+        if !ret.is_success() {
+            println!("failed to block the shutdown signals, err: {:?}", ret);
+            return;
+        }
+    }
 
-    let cfg: Config;
-    let parse_result = parse_config(opts.config_file_path);
+    let mut cfg: Config;
+    let parse_result = parse_config(opts.config_file_path.clone());
     match parse_result {
         Ok(ret) => {
             cfg = ret;
+            opts.apply_mount_config(&mut cfg.mount_config);
         }
         Err(error)=>{
             println!("failed to parse with err: {:}", error);
@@ -47,6 +61,15 @@ fn main() {
         } 
     }
 
+    if let Some(ref m) = opts.migrate {
+        let ret = admin::migrate(&cfg, m);
+        if !ret.is_success() {
+            println!("failed to migrate ino: {} to {}, err: {:?}", m.ino, m.machine, ret);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let exec = Executor::create();
     let single_thread_exec = Executor::create_single_threaded();
     let meta_service = new_metaserver_mgr(&cfg, &single_thread_exec).unwrap();
@@ -94,18 +117,41 @@ fn main() {
     let segment_syncer = SegSyncer::new(cache_store.clone(), backend_store.clone(), meta_store.clone());
     let syncer = Arc::new(segment_syncer);
 
-    let leader_mgr = LeaderMgr::new(&meta_service.get_machine_id(),
-    &exec, segment_mgr.clone(), cache_store.clone(), backend_store.clone(), syncer.clone());
+    // the leaderships of this machine are renewed by the heartbeats.
+    let lease = Arc::new(Lease::new(cfg.heartbeat_config.lease_timeout));
+    let mut leader_mgr = LeaderMgr::new(&meta_service.get_machine_id(),
+    &exec, segment_mgr.clone(), cache_store.clone(), backend_store.clone(), syncer.clone(),
+    cfg.backend_store_config.fsync_wait_upload, &cfg.leader_rpc_config, &cfg.follower_config,
+    &cfg.write_buffer_config, lease.clone());
+    let ret = leader_mgr.start_server();
+    if !ret.is_success() {
+        error!("failed to start leader server, err: {:?}", ret);
+        return;
+    }
     // start heartbeat mgr.
-    let heartbeat_mgr = Arc::new(HeartbeatMgr::new(cfg.heartbeat_config.timeout, 
+    let heartbeat_mgr = Arc::new(HeartbeatMgr::new(cfg.heartbeat_config.timeout, lease,
         syncer.clone(), 
 meta_service.clone(),
-        segment_mgr));
+        segment_mgr.clone()));
     let mut filesystem = FilesystemMgr::create(meta_service.clone(), 
-    leader_mgr, heartbeat_mgr.clone());
-    let mount_options = MountOptions{
-        mnt: cfg.mount_config.mnt.clone(),
-    };
+    leader_mgr, heartbeat_mgr.clone(), segment_mgr);
+    let mount_options = MountOptions::new(&cfg.mount_config);
     
-    filesystem.mount(mount_options);
+    let ret = filesystem.mount(mount_options);
+    if !ret.is_success() {
+        error!("failed to mount yigfs, err: {:?}", ret);
+        std::process::exit(1);
+    }
+
+    // the opened files are flushed on unmount, wait for the queued syncs before exiting.
+    let timeout = Duration::from_millis(cfg.mount_config.shutdown_timeout_ms);
+    let ret = syncer.drain(timeout);
+    if !ret.is_success() {
+        error!("failed to drain the segment syncer, err: {:?}", ret);
+    }
+    let ret = meta_store.drain(timeout);
+    if !ret.is_success() {
+        error!("failed to drain the meta store, err: {:?}", ret);
+    }
+    info!("yigfs exits.");
 }
@@ -1,9 +1,85 @@
-use clap::{App, Arg};
+use clap::{App, Arg, SubCommand};
+use common::config::MountConfig;
 
 #[derive(Debug, Default)]
 pub struct Options{
     // region
     pub config_file_path: String,
+    // the mount options below override the ones in mount_config.
+    pub read_only: bool,
+    pub allow_other: bool,
+    pub default_permissions: bool,
+    pub fsname: Option<String>,
+    pub subtype: Option<String>,
+    pub max_read: Option<u32>,
+    pub max_write: Option<u32>,
+    pub attr_timeout_ms: Option<u64>,
+    pub entry_timeout_ms: Option<u64>,
+    // run the migrate admin command instead of mounting.
+    pub migrate: Option<MigrateOptions>,
+}
+
+// move the leadership of ino to machine.
+#[derive(Debug, Default)]
+pub struct MigrateOptions {
+    pub ino: u64,
+    pub machine: String,
+}
+
+impl Options {
+    // override the mount config with the options given in command line.
+    pub fn apply_mount_config(&self, mount_config: &mut MountConfig) {
+        if self.read_only {
+            mount_config.read_only = true;
+        }
+        if self.allow_other {
+            mount_config.allow_other = true;
+        }
+        if self.default_permissions {
+            mount_config.default_permissions = true;
+        }
+        if let Some(ref fsname) = self.fsname {
+            mount_config.fsname = fsname.clone();
+        }
+        if let Some(ref subtype) = self.subtype {
+            mount_config.subtype = subtype.clone();
+        }
+        if let Some(max_read) = self.max_read {
+            mount_config.max_read = max_read;
+        }
+        if let Some(max_write) = self.max_write {
+            mount_config.max_write = max_write;
+        }
+        if let Some(timeout) = self.attr_timeout_ms {
+            mount_config.attr_timeout_ms = timeout;
+        }
+        if let Some(timeout) = self.entry_timeout_ms {
+            mount_config.entry_timeout_ms = timeout;
+        }
+    }
+}
+
+fn is_number(v: String) -> Result<(), String> {
+    match v.parse::<u64>() {
+        Ok(_) => {
+            Ok(())
+        }
+        Err(err) => {
+            Err(format!("{} is not a number, err: {}", v, err))
+        }
+    }
+}
+
+// the sizes passed to fuse are 32 bits.
+fn is_u32(v: String) -> Result<(), String> {
+    match v.parse::<u32>() {
+        Ok(_) => {
+            Ok(())
+        }
+        Err(err) => {
+            Err(format!("{} is not a number in [0, {}], err: {}", v, u32::MAX, err))
+        }
+    }
 }
 
 pub fn parse() -> Options {
@@ -14,12 +90,78 @@ pub fn parse() -> Options {
         .long("config")
         .required(true)
         .takes_value(true))
+    .arg(Arg::with_name("ro")
+        .help("mount read-only")
+        .long("ro"))
+    .arg(Arg::with_name("allow_other")
+        .help("allow the other users to access the mount")
+        .long("allow-other"))
+    .arg(Arg::with_name("default_permissions")
+        .help("let the kernel check the permission by the file mode")
+        .long("default-permissions"))
+    .arg(Arg::with_name("fsname")
+        .help("the source shown in /proc/mounts")
+        .long("fsname")
+        .takes_value(true))
+    .arg(Arg::with_name("subtype")
+        .help("the type shown as fuse.<subtype> in /proc/mounts")
+        .long("subtype")
+        .takes_value(true))
+    .arg(Arg::with_name("max_read")
+        .help("max size of a read request in bytes")
+        .long("max-read")
+        .takes_value(true)
+        .validator(is_u32))
+    .arg(Arg::with_name("max_write")
+        .help("max size of a write handed to the leader at once in bytes")
+        .long("max-write")
+        .takes_value(true)
+        .validator(is_u32))
+    .arg(Arg::with_name("attr_timeout")
+        .help("how long the kernel caches the file attributes in milliseconds")
+        .long("attr-timeout")
+        .takes_value(true)
+        .validator(is_number))
+    .arg(Arg::with_name("entry_timeout")
+        .help("how long the kernel caches the dir entries in milliseconds")
+        .long("entry-timeout")
+        .takes_value(true)
+        .validator(is_number))
+    .subcommand(SubCommand::with_name("migrate")
+        .about("move the leadership of a file to another machine")
+        .arg(Arg::with_name("ino")
+            .help("the ino of the file")
+            .long("ino")
+            .required(true)
+            .takes_value(true)
+            .validator(is_number))
+        .arg(Arg::with_name("to")
+            .help("the machine which becomes the new leader")
+            .long("to")
+            .required(true)
+            .takes_value(true)))
     .get_matches();
 
     let mut opts: Options = Default::default();
     if let Some(value) = matches.value_of("config"){
         opts.config_file_path = String::from(value);
     }
-    
+    opts.read_only = matches.is_present("ro");
+    opts.allow_other = matches.is_present("allow_other");
+    opts.default_permissions = matches.is_present("default_permissions");
+    opts.fsname = matches.value_of("fsname").map(String::from);
+    opts.subtype = matches.value_of("subtype").map(String::from);
+    // the numbers are already checked by the validator.
+    opts.max_read = matches.value_of("max_read").and_then(|v| v.parse::<u32>().ok());
+    opts.max_write = matches.value_of("max_write").and_then(|v| v.parse::<u32>().ok());
+    opts.attr_timeout_ms = matches.value_of("attr_timeout").and_then(|v| v.parse::<u64>().ok());
+    opts.entry_timeout_ms = matches.value_of("entry_timeout").and_then(|v| v.parse::<u64>().ok());
+    if let Some(m) = matches.subcommand_matches("migrate") {
+        opts.migrate = Some(MigrateOptions{
+            ino: m.value_of("ino").and_then(|v| v.parse::<u64>().ok()).unwrap_or(0),
+            machine: m.value_of("to").map(String::from).unwrap_or_default(),
+        });
+    }
+
     opts
-}
\ No newline at end of file
+}
@@ -66,3 +66,26 @@ func CheckRenameParams(ctx context.Context, fileReq *types.RenameFileReq) (err e
 
 	return nil
 }
+
+func CheckSymlinkParams(ctx context.Context, link *types.CreateSymlinkReq) (err error) {
+	if link.BucketName == "" || link.FileName == "" || link.ParentIno == 0 || link.ZoneId == "" || link.Machine == "" || link.Target == "" {
+		helper.Logger.Error(ctx, "Some CreateSymlink required parameters are missing.")
+		return ErrYigFsMissingRequiredParams
+	}
+
+	if link.Type == 0 {
+		link.Type = types.LINK_FILE
+	} else if link.Type != types.LINK_FILE {
+		return ErrYigFsInvalidType
+	}
+
+	if link.Region == "" {
+		link.Region = "cn-bj-1"
+	}
+
+	if link.Perm == 0 {
+		link.Perm = types.LINK_PERM
+	}
+
+	return nil
+}
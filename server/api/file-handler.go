@@ -410,4 +410,188 @@ func (yigFs MetaAPIHandlers) RenameFileHandler(ctx iris.Context) {
 	resp.Result = GetErrInfo(NoYigFsErr)
 	ctx.JSON(resp)
 	return
-}
\ No newline at end of file
+}
+
+func (yigFs MetaAPIHandlers) DeleteDirHandler(ctx iris.Context) {
+	resp := &types.DeleteDirResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("DeleteDirHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	dirReq := &types.DeleteDirReq{}
+	if err := ctx.ReadJSON(&dirReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read DeleteDirReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if dirReq.BucketName == "" || dirReq.ParentIno == 0 || dirReq.FileName == "" {
+		helper.Logger.Error(reqContext, "Some DeleteDirHandler required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if dirReq.Region == "" {
+		dirReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	dirReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// delete the dir if it is empty.
+	parentInfo, err := yigFs.YigFsAPI.DeleteDir(reqContext, dirReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.File = parentInfo
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) CreateSymlinkHandler(ctx iris.Context) {
+	resp := &types.CreateSymlinkResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("CreateSymlinkHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	linkReq := &types.CreateSymlinkReq{}
+	if err := ctx.ReadJSON(&linkReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read CreateSymlinkReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	err := CheckSymlinkParams(reqContext, linkReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	uuidStr := uuid.New()
+	linkReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// create the symlink
+	linkInfo, err := yigFs.YigFsAPI.CreateSymlink(reqContext, linkReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.File = linkInfo
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) ReadSymlinkHandler(ctx iris.Context) {
+	resp := &types.ReadSymlinkResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("ReadSymlinkHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	fileReq := &types.GetFileInfoReq{}
+	if err := ctx.ReadJSON(&fileReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read GetFileInfoReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if fileReq.BucketName == "" || fileReq.Ino == 0 {
+		helper.Logger.Error(reqContext, "Some ReadSymlink required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if fileReq.Region == "" {
+		fileReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	fileReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// read the target of the symlink
+	target, err := yigFs.YigFsAPI.ReadSymlink(reqContext, fileReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.Target = target
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) GetFsUsageHandler(ctx iris.Context) {
+	resp := &types.GetFsUsageResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("GetFsUsageHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	fsReq := &types.GetFsUsageReq{}
+	if err := ctx.ReadJSON(&fsReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read GetFsUsageReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if fsReq.BucketName == "" {
+		helper.Logger.Error(reqContext, "Some GetFsUsage required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if fsReq.Region == "" {
+		fsReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	fsReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// get the usage of the bucket
+	usage, err := yigFs.YigFsAPI.GetFsUsage(reqContext, fsReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp = usage
+	resp.Result = GetErrInfo(NoYigFsErr)
+	ctx.JSON(resp)
+	return
+}
@@ -57,3 +57,100 @@ func(yigFs MetaAPIHandlers) GetFileLeaderHandler(ctx iris.Context) {
 	ctx.JSON(resp)
 	return
 }
+
+func(yigFs MetaAPIHandlers) TransferFileLeaderHandler(ctx iris.Context) {
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// the client decodes leader_info even if the request fails.
+	resp := &types.GetLeaderResp {
+		Result: types.YigFsMetaError{},
+		LeaderInfo: &types.LeaderInfo{},
+	}
+	defer GetSpendTime("TransferFileLeaderHandler")()
+
+	// get req
+	leaderReq := &types.ChangeLeaderReq{}
+	if err := ctx.ReadJSON(&leaderReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read TransferFileLeaderReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if leaderReq.BucketName == "" || leaderReq.ZoneId == "" || leaderReq.Machine == "" || leaderReq.Ino == 0 {
+		helper.Logger.Error(reqContext, "Some TransferFileLeader required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if leaderReq.Region == "" {
+		leaderReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	leaderReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// take over the file whose leader is down
+	transferLeaderResp, err := yigFs.YigFsAPI.TransferFileLeader(reqContext, leaderReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.LeaderInfo = transferLeaderResp.LeaderInfo
+	ctx.JSON(resp)
+	return
+}
+
+func(yigFs MetaAPIHandlers) MigrateFileLeaderHandler(ctx iris.Context) {
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	resp := &types.GetLeaderResp {
+		Result: types.YigFsMetaError{},
+		LeaderInfo: &types.LeaderInfo{},
+	}
+	defer GetSpendTime("MigrateFileLeaderHandler")()
+
+	// get req
+	leaderReq := &types.ChangeLeaderReq{}
+	if err := ctx.ReadJSON(&leaderReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read MigrateFileLeaderReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if leaderReq.BucketName == "" || leaderReq.ZoneId == "" || leaderReq.Machine == "" || leaderReq.Ino == 0 {
+		helper.Logger.Error(reqContext, "Some MigrateFileLeader required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if leaderReq.Region == "" {
+		leaderReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	leaderReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// move the file to the new leader
+	migrateLeaderResp, err := yigFs.YigFsAPI.MigrateFileLeader(reqContext, leaderReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.LeaderInfo = migrateLeaderResp.LeaderInfo
+	ctx.JSON(resp)
+	return
+}
@@ -13,6 +13,8 @@ type YigFsLayer interface {
 	GetFileAttr(ctx context.Context, file *types.GetFileInfoReq) (resp *types.FileInfo, err error)
 	InitDirAndZone(ctx context.Context, rootDir *types.InitDirReq) (err error)
 	GetFileLeader(ctx context.Context, leader *types.GetLeaderReq) (resp *types.GetLeaderResp, err error)
+	TransferFileLeader(ctx context.Context, leader *types.ChangeLeaderReq) (resp *types.GetLeaderResp, err error)
+	MigrateFileLeader(ctx context.Context, leader *types.ChangeLeaderReq) (resp *types.GetLeaderResp, err error)
 	CreateFile(ctx context.Context, file *types.CreateFileReq) (resp *types.CreateFileResp, err error)
 	SetFileAttr(ctx context.Context, file *types.SetFileAttrReq) (resp *types.SetFileAttrResp, err error)
 	CheckSegmentLeader(ctx context.Context, segment *types.CreateSegmentReq) (err error) 
@@ -29,4 +31,15 @@ type YigFsLayer interface {
 	UpdateFileSizeAndBlocksNumByCheck(ctx context.Context, file *types.GetFileInfoReq) (err error)
 	CheckFileLeader(ctx context.Context, file *types.GetLeaderReq) (err error)
 	RenameFile(ctx context.Context, file *types.RenameFileReq) (err error)
+	DeleteDir(ctx context.Context, dir *types.DeleteDirReq) (resp *types.FileInfo, err error)
+	CreateSymlink(ctx context.Context, link *types.CreateSymlinkReq) (resp *types.FileInfo, err error)
+	ReadSymlink(ctx context.Context, file *types.GetFileInfoReq) (target string, err error)
+	GetFsUsage(ctx context.Context, fs *types.GetFsUsageReq) (resp *types.GetFsUsageResp, err error)
+	SetXattr(ctx context.Context, xattr *types.SetXattrReq) (err error)
+	GetXattr(ctx context.Context, xattr *types.GetXattrReq) (value []byte, err error)
+	ListXattrs(ctx context.Context, file *types.GetFileInfoReq) (names []string, err error)
+	RemoveXattr(ctx context.Context, xattr *types.GetXattrReq) (err error)
+	AddOrphan(ctx context.Context, orphan *types.AddOrphanReq) (err error)
+	RemoveOrphan(ctx context.Context, orphan *types.OrphanReq) (err error)
+	ListOrphans(ctx context.Context, machine *types.ListOrphansReq) (inos []uint64, err error)
 }
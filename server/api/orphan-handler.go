@@ -0,0 +1,154 @@
+package api
+
+import (
+	"context"
+	"fmt"
+
+	"github.com/kataras/iris"
+	"github.com/google/uuid"
+	"github.com/hopkings2008/yigfs/server/types"
+	. "github.com/hopkings2008/yigfs/server/error"
+	"github.com/hopkings2008/yigfs/server/helper"
+)
+
+
+func (yigFs MetaAPIHandlers) AddOrphanHandler(ctx iris.Context) {
+	resp := &types.NonBodyResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("AddOrphanHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	orphanReq := &types.AddOrphanReq{}
+	if err := ctx.ReadJSON(&orphanReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read AddOrphanReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if orphanReq.BucketName == "" || orphanReq.ZoneId == "" || orphanReq.Machine == "" || orphanReq.Ino == 0 ||
+		orphanReq.ParentIno == 0 || orphanReq.FileName == "" {
+		helper.Logger.Error(reqContext, "Some AddOrphan required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if orphanReq.Region == "" {
+		orphanReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	orphanReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// detach the file and record the orphan
+	err := yigFs.YigFsAPI.AddOrphan(reqContext, orphanReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) RemoveOrphanHandler(ctx iris.Context) {
+	resp := &types.NonBodyResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("RemoveOrphanHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	orphanReq := &types.OrphanReq{}
+	if err := ctx.ReadJSON(&orphanReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read OrphanReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if orphanReq.BucketName == "" || orphanReq.ZoneId == "" || orphanReq.Machine == "" || orphanReq.Ino == 0 {
+		helper.Logger.Error(reqContext, "Some RemoveOrphan required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if orphanReq.Region == "" {
+		orphanReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	orphanReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// remove the orphan record
+	err := yigFs.YigFsAPI.RemoveOrphan(reqContext, orphanReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) ListOrphansHandler(ctx iris.Context) {
+	resp := &types.ListOrphansResp{
+		Inos: []uint64{},
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("ListOrphansHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	machineReq := &types.ListOrphansReq{}
+	if err := ctx.ReadJSON(&machineReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read ListOrphansReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if machineReq.BucketName == "" || machineReq.ZoneId == "" || machineReq.Machine == "" {
+		helper.Logger.Error(reqContext, "Some ListOrphans required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if machineReq.Region == "" {
+		machineReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	machineReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// list the orphans of the machine
+	inos, err := yigFs.YigFsAPI.ListOrphans(reqContext, machineReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.Inos = inos
+	ctx.JSON(resp)
+	return
+}
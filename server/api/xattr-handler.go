@@ -0,0 +1,207 @@
+package api
+
+import (
+	"context"
+	"fmt"
+
+	"github.com/kataras/iris"
+	"github.com/google/uuid"
+	"github.com/hopkings2008/yigfs/server/types"
+	. "github.com/hopkings2008/yigfs/server/error"
+	"github.com/hopkings2008/yigfs/server/helper"
+)
+
+
+func (yigFs MetaAPIHandlers) SetXattrHandler(ctx iris.Context) {
+	resp := &types.NonBodyResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("SetXattrHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	xattrReq := &types.SetXattrReq{}
+	if err := ctx.ReadJSON(&xattrReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read SetXattrReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if xattrReq.BucketName == "" || xattrReq.Ino == 0 || xattrReq.Name == "" {
+		helper.Logger.Error(reqContext, "Some SetXattr required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if xattrReq.Flags != 0 && xattrReq.Flags != types.XATTR_CREATE && xattrReq.Flags != types.XATTR_REPLACE {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Invalid SetXattr flags: %v", xattrReq.Flags))
+		resp.Result = GetErrInfo(ErrYigFsInvalidFlag)
+		ctx.JSON(resp)
+		return
+	}
+
+	if xattrReq.Region == "" {
+		xattrReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	xattrReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// set the xattr
+	err := yigFs.YigFsAPI.SetXattr(reqContext, xattrReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) GetXattrHandler(ctx iris.Context) {
+	resp := &types.GetXattrResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("GetXattrHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	xattrReq := &types.GetXattrReq{}
+	if err := ctx.ReadJSON(&xattrReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read GetXattrReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if xattrReq.BucketName == "" || xattrReq.Ino == 0 || xattrReq.Name == "" {
+		helper.Logger.Error(reqContext, "Some GetXattr required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if xattrReq.Region == "" {
+		xattrReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	xattrReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// get the xattr
+	value, err := yigFs.YigFsAPI.GetXattr(reqContext, xattrReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.Value = value
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) ListXattrsHandler(ctx iris.Context) {
+	resp := &types.ListXattrsResp{
+		Names: []string{},
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("ListXattrsHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	fileReq := &types.GetFileInfoReq{}
+	if err := ctx.ReadJSON(&fileReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read GetFileInfoReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if fileReq.BucketName == "" || fileReq.Ino == 0 {
+		helper.Logger.Error(reqContext, "Some ListXattrs required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if fileReq.Region == "" {
+		fileReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	fileReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// list the xattr names
+	names, err := yigFs.YigFsAPI.ListXattrs(reqContext, fileReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	resp.Names = names
+	ctx.JSON(resp)
+	return
+}
+
+func (yigFs MetaAPIHandlers) RemoveXattrHandler(ctx iris.Context) {
+	resp := &types.NonBodyResp{
+		Result: types.YigFsMetaError{},
+	}
+	defer GetSpendTime("RemoveXattrHandler")()
+
+	r := ctx.Request()
+	reqContext := r.Context()
+
+	// get req
+	xattrReq := &types.GetXattrReq{}
+	if err := ctx.ReadJSON(&xattrReq); err != nil {
+		helper.Logger.Error(reqContext, fmt.Sprintf("Failed to read GetXattrReq from body, err: %v", err))
+		resp.Result = GetErrInfo(ErrYigFsInvaildParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	// check request params
+	if xattrReq.BucketName == "" || xattrReq.Ino == 0 || xattrReq.Name == "" {
+		helper.Logger.Error(reqContext, "Some RemoveXattr required parameters are missing.")
+		resp.Result = GetErrInfo(ErrYigFsMissingRequiredParams)
+		ctx.JSON(resp)
+		return
+	}
+
+	if xattrReq.Region == "" {
+		xattrReq.Region = "cn-bj-1"
+	}
+
+	uuidStr := uuid.New()
+	xattrReq.Ctx = context.WithValue(reqContext, types.CTX_REQ_ID, uuidStr)
+
+	// remove the xattr
+	err := yigFs.YigFsAPI.RemoveXattr(reqContext, xattrReq)
+	if err != nil {
+		resp.Result = GetErrInfo(err)
+		ctx.JSON(resp)
+		return
+	}
+
+	resp.Result = GetErrInfo(NoYigFsErr)
+	ctx.JSON(resp)
+	return
+}
@@ -47,6 +47,10 @@ const (
 	ErrYigFsFailedCreateMessageConsumer
 	ErrYigFsFailedToSendMessage
 	ErrYigFsTheFileAlreadyRenamed
+	ErrYigFsDirNotEmpty
+	ErrYigFsNotDir
+	ErrYigFsNoSuchXattr
+	ErrYigFsXattrAlreadyExist
 )
 
 var ErrorCodeResponse = map[ApiErrorCode]ApiErrorStruct{
@@ -160,6 +164,26 @@ var ErrorCodeResponse = map[ApiErrorCode]ApiErrorStruct{
 		Description:    "The file already renamed.",
 		HttpStatusCode: 40020,
 	},
+	ErrYigFsDirNotEmpty: {
+		AwsErrorCode:   "ErrYigFsDirNotEmpty",
+		Description:    "The dir is not empty.",
+		HttpStatusCode: 40021,
+	},
+	ErrYigFsNotDir: {
+		AwsErrorCode:   "ErrYigFsNotDir",
+		Description:    "The file is not a dir.",
+		HttpStatusCode: 40022,
+	},
+	ErrYigFsNoSuchXattr: {
+		AwsErrorCode:   "ErrYigFsNoSuchXattr",
+		Description:    "The xattr does not exist.",
+		HttpStatusCode: 40023,
+	},
+	ErrYigFsXattrAlreadyExist: {
+		AwsErrorCode:   "ErrYigFsXattrAlreadyExist",
+		Description:    "The xattr already exists.",
+		HttpStatusCode: 40024,
+	},
 }
 
 func (e ApiErrorCode) AwsErrorCode() string {
@@ -48,6 +48,7 @@ CREATE TABLE `file_leader` (
   `ino` bigint(20) UNSIGNED DEFAULT 0,
   `generation` bigint(20) UNSIGNED DEFAULT 0,
   `leader` varchar(255) DEFAULT NULL,
+  `token` bigint(20) UNSIGNED DEFAULT 0,
   `ctime` datetime DEFAULT CURRENT_TIMESTAMP,
   `mtime` datetime DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
   `is_deleted` tinyint(1) DEFAULT 0,
@@ -123,4 +124,46 @@ CREATE TABLE `segment_blocks` (
   `is_deleted` tinyint(1) DEFAULT 0,
    UNIQUE KEY `rowkey` (`seg_id0`, `seg_id1`, `seg_start_addr`)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8 COLLATE=utf8_bin;
-/*!40101 SET character_set_client = @saved_cs_client */;
\ No newline at end of file
+/*!40101 SET character_set_client = @saved_cs_client */;
+DROP TABLE IF EXISTS `symlink`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!40101 SET character_set_client = utf8 */;
+CREATE TABLE `symlink` (
+  `region` varchar(255) DEFAULT "cn-bj-1",
+  `bucket_name` varchar(255) DEFAULT NULL,
+  `ino` bigint(20) UNSIGNED DEFAULT 0,
+  `target` varchar(4096) DEFAULT NULL,
+  `ctime` datetime DEFAULT CURRENT_TIMESTAMP,
+  `mtime` datetime DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+   UNIQUE KEY `rowkey` (`region`, `bucket_name`, `ino`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8 COLLATE=utf8_bin;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+DROP TABLE IF EXISTS `xattr`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!40101 SET character_set_client = utf8 */;
+CREATE TABLE `xattr` (
+  `region` varchar(255) DEFAULT "cn-bj-1",
+  `bucket_name` varchar(255) DEFAULT NULL,
+  `ino` bigint(20) UNSIGNED DEFAULT 0,
+  `name` varchar(255) DEFAULT NULL,
+  `value` mediumblob DEFAULT NULL,
+  `ctime` datetime DEFAULT CURRENT_TIMESTAMP,
+  `mtime` datetime DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+   UNIQUE KEY `rowkey` (`region`, `bucket_name`, `ino`, `name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8 COLLATE=utf8_bin;
+/*!40101 SET character_set_client = @saved_cs_client */;
+
+DROP TABLE IF EXISTS `orphan`;
+/*!40101 SET @saved_cs_client     = @@character_set_client */;
+/*!40101 SET character_set_client = utf8 */;
+CREATE TABLE `orphan` (
+  `zone_id` varchar(255) DEFAULT NULL,
+  `region` varchar(255) DEFAULT "cn-bj-1",
+  `bucket_name` varchar(255) DEFAULT NULL,
+  `machine` varchar(255) DEFAULT NULL,
+  `ino` bigint(20) UNSIGNED DEFAULT 0,
+  `ctime` datetime DEFAULT CURRENT_TIMESTAMP,
+   UNIQUE KEY `rowkey` (`zone_id`, `region`, `bucket_name`, `machine`, `ino`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8 COLLATE=utf8_bin;
+/*!40101 SET character_set_client = @saved_cs_client */;
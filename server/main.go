@@ -60,6 +60,10 @@ func main() {
 	app.Put("/v1/dir", apiHandlers.InitDirHandler)
 	// GetFileLeader
 	app.Get("/v1/file/leader", apiHandlers.GetFileLeaderHandler)
+	// TransferFileLeader
+	app.Put("/v1/file/leader", apiHandlers.TransferFileLeaderHandler)
+	// MigrateFileLeader
+	app.Put("/v1/file/leader/migrate", apiHandlers.MigrateFileLeaderHandler)
 	// CreateFile
 	app.Put("/v1/dir/file", apiHandlers.CreateFileHandler)
 	// SetFileAttr
@@ -78,6 +82,28 @@ func main() {
 	app.Post("/v1/file/remove", apiHandlers.DeleteFileHandler)
 	// RenameFile
 	app.Put("/v1/file/name", apiHandlers.RenameFileHandler)
+	// DeleteDir
+	app.Post("/v1/dir/remove", apiHandlers.DeleteDirHandler)
+	// CreateSymlink
+	app.Put("/v1/dir/symlink", apiHandlers.CreateSymlinkHandler)
+	// ReadSymlink
+	app.Get("/v1/file/symlink", apiHandlers.ReadSymlinkHandler)
+	// GetFsUsage
+	app.Get("/v1/fs/usage", apiHandlers.GetFsUsageHandler)
+	// SetXattr
+	app.Put("/v1/file/xattr", apiHandlers.SetXattrHandler)
+	// GetXattr
+	app.Get("/v1/file/xattr", apiHandlers.GetXattrHandler)
+	// ListXattrs
+	app.Get("/v1/file/xattrs", apiHandlers.ListXattrsHandler)
+	// RemoveXattr
+	app.Post("/v1/file/xattr/remove", apiHandlers.RemoveXattrHandler)
+	// AddOrphan
+	app.Put("/v1/file/orphan", apiHandlers.AddOrphanHandler)
+	// RemoveOrphan
+	app.Post("/v1/file/orphan/remove", apiHandlers.RemoveOrphanHandler)
+	// ListOrphans
+	app.Get("/v1/machine/orphans", apiHandlers.ListOrphansHandler)
 
 	port := ":" + helper.CONFIG.MetaServiceConfig.Port
 	isHTTP2 := false
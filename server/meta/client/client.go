@@ -24,6 +24,8 @@ type Client interface {
 	GetFileLeaderInfo(ctx context.Context, leader *types.GetLeaderReq) (resp *types.GetLeaderResp, err error)
 	// Create or update file leader
 	CreateOrUpdateFileLeader(ctx context.Context, leader *types.GetLeaderReq) (err error)
+	// change the file leader if the token is held by the current leader
+	ChangeFileLeader(ctx context.Context, leader *types.ChangeLeaderReq) (resp *types.LeaderInfo, isChanged bool, err error)
 	// Get one update machine
 	GetOneUpMachine(ctx context.Context, zone *types.GetLeaderReq) (leader string, err error)
 	// Get machine indo
@@ -74,5 +76,27 @@ type Client interface {
 	RemoveSegBlocks(ctx context.Context, segs []*types.CreateBlocksInfo, blocksNum int) (err error)
 	// rename the file
 	RenameFile(ctx context.Context, file *types.RenameFileReq) (err error)
+	// delete the empty dir
+	DeleteDir(ctx context.Context, dir *types.DeleteDirReq) (err error)
+	// create the symlink
+	CreateSymlink(ctx context.Context, link *types.CreateSymlinkReq) (err error)
+	// read the target of the symlink
+	ReadSymlink(ctx context.Context, file *types.GetFileInfoReq) (target string, err error)
+	// get the total size and the number of the files in the bucket
+	GetFsUsage(ctx context.Context, fs *types.GetFsUsageReq) (usedSize uint64, fileCount uint64, err error)
+	// set the xattr of the file
+	SetXattr(ctx context.Context, xattr *types.SetXattrReq) (err error)
+	// get the xattr of the file
+	GetXattr(ctx context.Context, xattr *types.GetXattrReq) (value []byte, err error)
+	// list the xattr names of the file
+	ListXattrs(ctx context.Context, file *types.GetFileInfoReq) (names []string, err error)
+	// remove the xattr of the file
+	RemoveXattr(ctx context.Context, xattr *types.GetXattrReq) (err error)
+	// detach the unlinked file which is still opened, and record it as the orphan of the machine
+	AddOrphan(ctx context.Context, orphan *types.AddOrphanReq) (err error)
+	// remove the orphan record of the machine
+	RemoveOrphan(ctx context.Context, orphan *types.OrphanReq) (err error)
+	// list the orphans of the machine
+	ListOrphans(ctx context.Context, machine *types.ListOrphansReq) (inos []uint64, err error)
 }
 
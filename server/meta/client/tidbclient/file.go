@@ -60,6 +60,21 @@ func checkFileExistedSql() (sqltext string) {
 	return sqltext
 }
 
+func LockDirSql() (sqltext string) {
+	sqltext = "select type from file where region=? and bucket_name=? and ino=? for update;"
+	return sqltext
+}
+
+func LockDirFileSql() (sqltext string) {
+	sqltext = "select ino, type from file where region=? and bucket_name=? and parent_ino=? and file_name=? for update;"
+	return sqltext
+}
+
+func checkDirEmptySql() (sqltext string) {
+	sqltext = "select 1 from file where region=? and bucket_name=? and parent_ino=? limit 1;"
+	return sqltext
+}
+
 func (t *TidbClient) ListDirFiles(ctx context.Context, dir *types.GetDirFilesReq) (dirFilesResp []*types.GetDirFileInfo, offset uint64, err error) {
 	var maxNum = 1000
 	args := make([]interface{}, 0)
@@ -292,6 +307,30 @@ func (t *TidbClient) CreateFile(ctx context.Context, file *types.CreateFileReq)
 
 	sqlTx, _ = tx.(*sql.Tx)
 
+	// lock the parent dir, so that it can't be removed until the file is created in it.
+	var parentType uint
+	row := sqlTx.QueryRow(LockDirSql(), file.Region, file.BucketName, file.ParentIno)
+	err = row.Scan(
+		&parentType)
+
+	if err == sql.ErrNoRows {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateFile: The parent dir is not existed, region: %v, bucket: %v, parent_ino: %v",
+			file.Region, file.BucketName, file.ParentIno))
+		err = ErrYigFsNoSuchFile
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateFile: Failed to lock the parent dir, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	if parentType != types.DIR_FILE {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateFile: The parent is not a dir, region: %v, bucket: %v, parent_ino: %v",
+			file.Region, file.BucketName, file.ParentIno))
+		err = ErrYigFsNotDir
+		return
+	}
+
 	now := time.Now().UTC().Format(types.TIME_LAYOUT_TIDB)
 
 	sqltext := "insert into file(region, bucket_name, parent_ino, file_name, size, type, atime, perm, uid, gid) values(?,?,?,?,?,?,?,?,?,?);"
@@ -307,7 +346,7 @@ func (t *TidbClient) CreateFile(ctx context.Context, file *types.CreateFileReq)
 	// get file ino
 	var ino uint64
 	sqltext = GetDirFileInoSql()
-	row := sqlTx.QueryRow(sqltext, file.Region, file.BucketName, file.ParentIno, file.FileName)
+	row = sqlTx.QueryRow(sqltext, file.Region, file.BucketName, file.ParentIno, file.FileName)
 	err = row.Scan(
 		&ino)
 
@@ -438,6 +477,23 @@ func(t *TidbClient) DeleteFile(ctx context.Context, file *types.DeleteFileReq) (
 		err = ErrYIgFsInternalErr
 		return
 	}
+
+	// the target of the symlink.
+	sqltext = DeleteSymlinkSql()
+	_, err = t.Client.Exec(sqltext, file.Region, file.BucketName, file.Ino)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to delete the symlink, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	sqltext = DeleteFileXattrsSql()
+	_, err = t.Client.Exec(sqltext, file.Region, file.BucketName, file.Ino)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to delete the file xattrs, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
 	
 	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to delete the file, region: %v, bucket: %v, ino: %v, generation: %v", 
 		file.Region, file.BucketName, file.Ino, file.Generation))
@@ -537,3 +593,108 @@ func(t *TidbClient) RenameFile(ctx context.Context, file *types.RenameFileReq) (
 
 	return ErrYigFsFileAlreadyExist
 }
+
+func(t *TidbClient) DeleteDir(ctx context.Context, dir *types.DeleteDirReq) (err error) {
+	var tx interface{}
+	var sqlTx *sql.Tx
+	tx, err = t.Client.Begin()
+	defer func() {
+		if err == nil {
+			err = sqlTx.Commit()
+		} else {
+			sqlTx.Rollback()
+		}
+	}()
+
+	sqlTx, _ = tx.(*sql.Tx)
+
+	// lock the dir, CreateFile locks the parent dir too, so no file can be created in it until it is removed.
+	var ino uint64
+	var fileType uint
+	row := sqlTx.QueryRow(LockDirFileSql(), dir.Region, dir.BucketName, dir.ParentIno, dir.FileName)
+	err = row.Scan(
+		&ino,
+		&fileType)
+
+	if err == sql.ErrNoRows {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: The dir is not existed, region: %v, bucket: %v, parent_ino: %v, name: %v",
+			dir.Region, dir.BucketName, dir.ParentIno, dir.FileName))
+		err = ErrYigFsNoSuchFile
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: Failed to lock the dir, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	if fileType != types.DIR_FILE {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: The file is not a dir, region: %v, bucket: %v, ino: %v", dir.Region, dir.BucketName, ino))
+		err = ErrYigFsNotDir
+		return
+	}
+
+	// check the dir is empty.
+	var r int
+	row = sqlTx.QueryRow(checkDirEmptySql(), dir.Region, dir.BucketName, ino)
+	err = row.Scan(
+		&r)
+
+	if err == nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: The dir is not empty, region: %v, bucket: %v, ino: %v", dir.Region, dir.BucketName, ino))
+		err = ErrYigFsDirNotEmpty
+		return
+	} else if err != sql.ErrNoRows {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: Failed to check the dir is empty or not, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	// delete the dir and its leaders.
+	sqltext := "delete from file where region=? and bucket_name=? and ino=?;"
+	_, err = sqlTx.Exec(sqltext, dir.Region, dir.BucketName, ino)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: Failed to delete the dir, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	sqltext = "delete from file_leader where region=? and bucket_name=? and ino=?;"
+	_, err = sqlTx.Exec(sqltext, dir.Region, dir.BucketName, ino)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: Failed to delete the dir leader, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	// update the times of the parent dir.
+	now := time.Now().UTC().Format(types.TIME_LAYOUT_TIDB)
+	sqltext = "update file set ctime=?, mtime=? where region=? and bucket_name=? and ino=?;"
+	_, err = sqlTx.Exec(sqltext, now, now, dir.Region, dir.BucketName, dir.ParentIno)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: Failed to update the parent dir, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to delete the dir, region: %v, bucket: %v, parent_ino: %v, name: %v, ino: %v",
+		dir.Region, dir.BucketName, dir.ParentIno, dir.FileName, ino))
+	return
+}
+
+func(t *TidbClient) GetFsUsage(ctx context.Context, fs *types.GetFsUsageReq) (usedSize uint64, fileCount uint64, err error) {
+	sqltext := "select ifnull(sum(size), 0), count(*) from file where region=? and bucket_name=?;"
+	row := t.Client.QueryRow(sqltext, fs.Region, fs.BucketName)
+	err = row.Scan(
+		&usedSize,
+		&fileCount)
+
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to get the fs usage, region: %v, bucket: %v, err: %v", fs.Region, fs.BucketName, err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to get the fs usage, region: %v, bucket: %v, used size: %v, file count: %v",
+		fs.Region, fs.BucketName, usedSize, fileCount))
+	return
+}
@@ -12,8 +12,9 @@ import (
 
 
 func CreateOrUpdateFileLeaderSql() (sqltext string) {
+	// the token is increased if the leader is changed, it is updated before the leader to compare with the old one.
 	sqltext = "insert into file_leader(zone_id, region, bucket_name, ino, generation, leader, is_deleted) values(?,?,?,?,?,?,?)" +
-		" on duplicate key update leader=values(leader), is_deleted=values(is_deleted);"
+		" on duplicate key update token=if(leader<=>values(leader), token, token+1), leader=values(leader), is_deleted=values(is_deleted);"
 	return sqltext
 }
 
@@ -27,10 +28,11 @@ func (t *TidbClient) GetFileLeaderInfo(ctx context.Context, leader *types.GetLea
 		LeaderInfo: &types.LeaderInfo{},
 	}
 
-	sqltext := "select leader from file_leader where zone_id=? and region=? and bucket_name=? and ino=? and generation=?;"
+	sqltext := "select leader, token from file_leader where zone_id=? and region=? and bucket_name=? and ino=? and generation=?;"
 	row := t.Client.QueryRow(sqltext, leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, leader.Generation)
 	err = row.Scan (
 		&resp.LeaderInfo.Leader,
+		&resp.LeaderInfo.Token,
 	)
 
 	if err == sql.ErrNoRows {
@@ -59,3 +61,60 @@ func (t *TidbClient) CreateOrUpdateFileLeader(ctx context.Context, leader *types
 	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to create file leader to tidb, sqltext: %v", sqltext))
 	return
 }
+
+func (t *TidbClient) ChangeFileLeader(ctx context.Context, leader *types.ChangeLeaderReq) (resp *types.LeaderInfo, isChanged bool, err error) {
+	resp = &types.LeaderInfo{
+		ZoneId: leader.ZoneId,
+	}
+
+	var tx interface{}
+	var sqlTx *sql.Tx
+	tx, err = t.Client.Begin()
+	defer func() {
+		if err == nil {
+			err = sqlTx.Commit()
+		} else {
+			sqlTx.Rollback()
+		}
+	}()
+
+	sqlTx, _ = tx.(*sql.Tx)
+
+	// lock the leader, so that only one of the machines holding the same token changes it.
+	sqltext := "select leader, token from file_leader where zone_id=? and region=? and bucket_name=? and ino=? and generation=? for update;"
+	row := sqlTx.QueryRow(sqltext, leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, leader.Generation)
+	err = row.Scan(
+		&resp.Leader,
+		&resp.Token)
+
+	if err == sql.ErrNoRows {
+		err = ErrYigFsNoSuchLeader
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("ChangeFileLeader: Failed to get the file leader, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	// the leader is changed by the others already, return the current one.
+	if resp.Token != leader.Token {
+		helper.Logger.Warn(ctx, fmt.Sprintf("ChangeFileLeader: The token is changed, zone: %v, region: %v, bucket: %v, ino: %v, leader: %v, token: %v, req token: %v",
+			leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, resp.Leader, resp.Token, leader.Token))
+		return
+	}
+
+	sqltext = "update file_leader set leader=?, token=token+1 where zone_id=? and region=? and bucket_name=? and ino=? and generation=?;"
+	_, err = sqlTx.Exec(sqltext, leader.Machine, leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, leader.Generation)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("ChangeFileLeader: Failed to update the file leader, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	resp.Leader = leader.Machine
+	resp.Token = leader.Token + 1
+	isChanged = true
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to change the file leader, zone: %v, region: %v, bucket: %v, ino: %v, leader: %v, token: %v",
+		leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, resp.Leader, resp.Token))
+	return
+}
@@ -0,0 +1,136 @@
+package tidbclient
+
+import (
+	"context"
+	"database/sql"
+	"fmt"
+	"time"
+
+	. "github.com/hopkings2008/yigfs/server/error"
+	"github.com/hopkings2008/yigfs/server/types"
+	"github.com/hopkings2008/yigfs/server/helper"
+)
+
+
+func AddOrphanSql() (sqltext string) {
+	sqltext = "insert into orphan(zone_id, region, bucket_name, machine, ino) values(?,?,?,?,?) on duplicate key update ino=values(ino);"
+	return sqltext
+}
+
+func DeleteOrphanSql() (sqltext string) {
+	sqltext = "delete from orphan where zone_id=? and region=? and bucket_name=? and machine=? and ino=?;"
+	return sqltext
+}
+
+func (t *TidbClient) AddOrphan(ctx context.Context, orphan *types.AddOrphanReq) (err error) {
+	var tx interface{}
+	var sqlTx *sql.Tx
+	tx, err = t.Client.Begin()
+	defer func() {
+		if err == nil {
+			err = sqlTx.Commit()
+		} else {
+			sqlTx.Rollback()
+		}
+	}()
+
+	sqlTx, _ = tx.(*sql.Tx)
+
+	now := time.Now().UTC().Format(types.TIME_LAYOUT_TIDB)
+
+	// detach the file from its parent dir, so that the name disappears but the ino is kept until it is deleted.
+	sqltext := "update file set parent_ino=0, ctime=? where region=? and bucket_name=? and ino=? and parent_ino=? and file_name=?;"
+	result, err := sqlTx.Exec(sqltext, now, orphan.Region, orphan.BucketName, orphan.Ino, orphan.ParentIno, orphan.FileName)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("AddOrphan: Failed to detach the file from the parent dir, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	affectRows, _ := result.RowsAffected()
+	if affectRows == 0 {
+		// the name is already removed, such as the target replaced by rename, check the file is still existed.
+		var f int
+		row := sqlTx.QueryRow(GetFileExistedSql(), orphan.Region, orphan.BucketName, orphan.Ino)
+		err = row.Scan(
+			&f)
+
+		if err == sql.ErrNoRows {
+			helper.Logger.Error(ctx, fmt.Sprintf("AddOrphan: The file is not existed, region: %v, bucket: %v, ino: %v",
+				orphan.Region, orphan.BucketName, orphan.Ino))
+			err = ErrYigFsNoSuchFile
+			return
+		} else if err != nil {
+			helper.Logger.Error(ctx, fmt.Sprintf("AddOrphan: Failed to check the file is existed or not, err: %v", err))
+			err = ErrYIgFsInternalErr
+			return
+		}
+	} else {
+		// update the times of the parent dir.
+		sqltext = "update file set ctime=?, mtime=? where region=? and bucket_name=? and ino=?;"
+		_, err = sqlTx.Exec(sqltext, now, now, orphan.Region, orphan.BucketName, orphan.ParentIno)
+		if err != nil {
+			helper.Logger.Error(ctx, fmt.Sprintf("AddOrphan: Failed to update the parent dir, err: %v", err))
+			err = ErrYIgFsInternalErr
+			return
+		}
+	}
+
+	_, err = sqlTx.Exec(AddOrphanSql(), orphan.ZoneId, orphan.Region, orphan.BucketName, orphan.Machine, orphan.Ino)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("AddOrphan: Failed to add the orphan to tidb, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to add the orphan, zone: %v, region: %v, bucket: %v, machine: %v, ino: %v",
+		orphan.ZoneId, orphan.Region, orphan.BucketName, orphan.Machine, orphan.Ino))
+	return
+}
+
+func (t *TidbClient) RemoveOrphan(ctx context.Context, orphan *types.OrphanReq) (err error) {
+	_, err = t.Client.Exec(DeleteOrphanSql(), orphan.ZoneId, orphan.Region, orphan.BucketName, orphan.Machine, orphan.Ino)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to remove the orphan, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to remove the orphan, zone: %v, region: %v, bucket: %v, machine: %v, ino: %v",
+		orphan.ZoneId, orphan.Region, orphan.BucketName, orphan.Machine, orphan.Ino))
+	return
+}
+
+func (t *TidbClient) ListOrphans(ctx context.Context, machine *types.ListOrphansReq) (inos []uint64, err error) {
+	inos = []uint64{}
+	sqltext := "select ino from orphan where zone_id=? and region=? and bucket_name=? and machine=? order by ino;"
+	rows, err := t.Client.Query(sqltext, machine.ZoneId, machine.Region, machine.BucketName, machine.Machine)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to query the orphans, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+	defer rows.Close()
+
+	for rows.Next() {
+		var ino uint64
+		err = rows.Scan(
+			&ino)
+		if err != nil {
+			helper.Logger.Error(ctx, fmt.Sprintf("Failed to list the orphans in row, err: %v", err))
+			err = ErrYIgFsInternalErr
+			return
+		}
+		inos = append(inos, ino)
+	}
+	err = rows.Err()
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to list the orphans in rows, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to list the orphans, zone: %v, region: %v, bucket: %v, machine: %v, number: %v",
+		machine.ZoneId, machine.Region, machine.BucketName, machine.Machine, len(inos)))
+	return
+}
@@ -0,0 +1,177 @@
+package tidbclient
+
+import (
+	"context"
+	"database/sql"
+	"fmt"
+	"time"
+
+	. "github.com/hopkings2008/yigfs/server/error"
+	"github.com/hopkings2008/yigfs/server/types"
+	"github.com/hopkings2008/yigfs/server/helper"
+)
+
+
+func CreateSymlinkSql() (sqltext string) {
+	sqltext = "insert into symlink(region, bucket_name, ino, target) values(?,?,?,?);"
+	return sqltext
+}
+
+func GetSymlinkSql() (sqltext string) {
+	sqltext = "select target from symlink where region=? and bucket_name=? and ino=?;"
+	return sqltext
+}
+
+func DeleteSymlinkSql() (sqltext string) {
+	sqltext = "delete from symlink where region=? and bucket_name=? and ino=?;"
+	return sqltext
+}
+
+func (t *TidbClient) CreateSymlink(ctx context.Context, link *types.CreateSymlinkReq) (err error) {
+	var tx interface{}
+	var sqlTx *sql.Tx
+	tx, err = t.Client.Begin()
+	defer func() {
+		if err == nil {
+			err = sqlTx.Commit()
+		} else {
+			sqlTx.Rollback()
+		}
+	}()
+
+	sqlTx, _ = tx.(*sql.Tx)
+
+	// lock the parent dir, so that it can't be removed until the symlink is created in it.
+	var parentType uint
+	row := sqlTx.QueryRow(LockDirSql(), link.Region, link.BucketName, link.ParentIno)
+	err = row.Scan(
+		&parentType)
+
+	if err == sql.ErrNoRows {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: The parent dir is not existed, region: %v, bucket: %v, parent_ino: %v",
+			link.Region, link.BucketName, link.ParentIno))
+		err = ErrYigFsNoSuchFile
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to lock the parent dir, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	if parentType != types.DIR_FILE {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: The parent is not a dir, region: %v, bucket: %v, parent_ino: %v",
+			link.Region, link.BucketName, link.ParentIno))
+		err = ErrYigFsNotDir
+		return
+	}
+
+	// check the name is not used in the parent dir.
+	var r int
+	row = sqlTx.QueryRow(checkFileExistedSql(), link.Region, link.BucketName, link.ParentIno, link.FileName)
+	err = row.Scan(
+		&r)
+
+	if err == nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: The file is already existed, region: %v, bucket: %v, parent_ino: %v, name: %v",
+			link.Region, link.BucketName, link.ParentIno, link.FileName))
+		err = ErrYigFsFileAlreadyExist
+		return
+	} else if err != sql.ErrNoRows {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to check the file is existed or not, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	now := time.Now().UTC().Format(types.TIME_LAYOUT_TIDB)
+
+	// the size of the symlink is the length of its target.
+	sqltext := "insert into file(region, bucket_name, parent_ino, file_name, size, type, atime, perm, uid, gid) values(?,?,?,?,?,?,?,?,?,?);"
+	args := []interface{}{link.Region, link.BucketName, link.ParentIno, link.FileName, len(link.Target),
+		link.Type, now, link.Perm, link.Uid, link.Gid}
+	_, err = sqlTx.Exec(sqltext, args...)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to create the symlink file to tidb, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	// get the symlink ino
+	var ino uint64
+	row = sqlTx.QueryRow(GetDirFileInoSql(), link.Region, link.BucketName, link.ParentIno, link.FileName)
+	err = row.Scan(
+		&ino)
+
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to get the symlink ino, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	_, err = sqlTx.Exec(CreateSymlinkSql(), link.Region, link.BucketName, ino, link.Target)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to create the symlink target to tidb, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	// create the leader, so that the symlink can be removed like the other files.
+	_, err = sqlTx.Exec(CreateOrUpdateFileLeaderSql(), link.ZoneId, link.Region, link.BucketName, ino, 0, link.Machine, types.NotDeleted)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to create the symlink leader to tidb, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	// update the times of the parent dir.
+	sqltext = "update file set ctime=?, mtime=? where region=? and bucket_name=? and ino=?;"
+	_, err = sqlTx.Exec(sqltext, now, now, link.Region, link.BucketName, link.ParentIno)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to update the parent dir, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to create the symlink, region: %v, bucket: %v, parent_ino: %v, name: %v, ino: %v",
+		link.Region, link.BucketName, link.ParentIno, link.FileName, ino))
+	return
+}
+
+func (t *TidbClient) ReadSymlink(ctx context.Context, file *types.GetFileInfoReq) (target string, err error) {
+	// check the file is a symlink.
+	var fileType uint
+	row := t.Client.QueryRow("select type from file where region=? and bucket_name=? and ino=?;", file.Region, file.BucketName, file.Ino)
+	err = row.Scan(
+		&fileType)
+
+	if err == sql.ErrNoRows {
+		err = ErrYigFsNoSuchFile
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("ReadSymlink: Failed to get the file type, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	if fileType != types.LINK_FILE {
+		helper.Logger.Error(ctx, fmt.Sprintf("ReadSymlink: The file is not a symlink, region: %v, bucket: %v, ino: %v, type: %v",
+			file.Region, file.BucketName, file.Ino, fileType))
+		err = ErrYigFsInvalidType
+		return
+	}
+
+	row = t.Client.QueryRow(GetSymlinkSql(), file.Region, file.BucketName, file.Ino)
+	err = row.Scan(
+		&target)
+
+	if err == sql.ErrNoRows {
+		err = ErrYigFsNoSuchFile
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("ReadSymlink: Failed to get the symlink target, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to read the symlink, region: %v, bucket: %v, ino: %v", file.Region, file.BucketName, file.Ino))
+	return
+}
@@ -0,0 +1,168 @@
+package tidbclient
+
+import (
+	"context"
+	"database/sql"
+	"fmt"
+
+	. "github.com/hopkings2008/yigfs/server/error"
+	"github.com/hopkings2008/yigfs/server/types"
+	"github.com/hopkings2008/yigfs/server/helper"
+)
+
+
+func LockXattrSql() (sqltext string) {
+	sqltext = "select 1 from xattr where region=? and bucket_name=? and ino=? and name=? for update;"
+	return sqltext
+}
+
+func DeleteFileXattrsSql() (sqltext string) {
+	sqltext = "delete from xattr where region=? and bucket_name=? and ino=?;"
+	return sqltext
+}
+
+func (t *TidbClient) SetXattr(ctx context.Context, xattr *types.SetXattrReq) (err error) {
+	var tx interface{}
+	var sqlTx *sql.Tx
+	tx, err = t.Client.Begin()
+	defer func() {
+		if err == nil {
+			err = sqlTx.Commit()
+		} else {
+			sqlTx.Rollback()
+		}
+	}()
+
+	sqlTx, _ = tx.(*sql.Tx)
+
+	// check the file is existed.
+	var f int
+	row := sqlTx.QueryRow(GetFileExistedSql(), xattr.Region, xattr.BucketName, xattr.Ino)
+	err = row.Scan(
+		&f)
+
+	if err == sql.ErrNoRows {
+		err = ErrYigFsNoSuchFile
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("SetXattr: Failed to check the file is existed or not, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	// lock the xattr, so that the check of the flags can't race with the others.
+	isExisted := true
+	row = sqlTx.QueryRow(LockXattrSql(), xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name)
+	err = row.Scan(
+		&f)
+
+	if err == sql.ErrNoRows {
+		isExisted = false
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("SetXattr: Failed to lock the xattr, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	if isExisted && xattr.Flags == types.XATTR_CREATE {
+		helper.Logger.Error(ctx, fmt.Sprintf("SetXattr: The xattr is already existed, region: %v, bucket: %v, ino: %v, name: %v",
+			xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name))
+		err = ErrYigFsXattrAlreadyExist
+		return
+	}
+
+	if !isExisted && xattr.Flags == types.XATTR_REPLACE {
+		helper.Logger.Error(ctx, fmt.Sprintf("SetXattr: The xattr is not existed, region: %v, bucket: %v, ino: %v, name: %v",
+			xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name))
+		err = ErrYigFsNoSuchXattr
+		return
+	}
+
+	sqltext := "insert into xattr(region, bucket_name, ino, name, value) values(?,?,?,?,?) on duplicate key update value=values(value);"
+	_, err = sqlTx.Exec(sqltext, xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name, xattr.Value)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("SetXattr: Failed to set the xattr, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to set the xattr, region: %v, bucket: %v, ino: %v, name: %v",
+		xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name))
+	return
+}
+
+func (t *TidbClient) GetXattr(ctx context.Context, xattr *types.GetXattrReq) (value []byte, err error) {
+	sqltext := "select value from xattr where region=? and bucket_name=? and ino=? and name=?;"
+	row := t.Client.QueryRow(sqltext, xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name)
+	err = row.Scan(
+		&value)
+
+	if err == sql.ErrNoRows {
+		err = ErrYigFsNoSuchXattr
+		return
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to get the xattr, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to get the xattr, region: %v, bucket: %v, ino: %v, name: %v",
+		xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name))
+	return
+}
+
+func (t *TidbClient) ListXattrs(ctx context.Context, file *types.GetFileInfoReq) (names []string, err error) {
+	names = []string{}
+	sqltext := "select name from xattr where region=? and bucket_name=? and ino=? order by name;"
+	rows, err := t.Client.Query(sqltext, file.Region, file.BucketName, file.Ino)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to query the xattrs, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+	defer rows.Close()
+
+	for rows.Next() {
+		var name string
+		err = rows.Scan(
+			&name)
+		if err != nil {
+			helper.Logger.Error(ctx, fmt.Sprintf("Failed to list the xattrs in row, err: %v", err))
+			err = ErrYIgFsInternalErr
+			return
+		}
+		names = append(names, name)
+	}
+	err = rows.Err()
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to list the xattrs in rows, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to list the xattrs, region: %v, bucket: %v, ino: %v, number: %v",
+		file.Region, file.BucketName, file.Ino, len(names)))
+	return
+}
+
+func (t *TidbClient) RemoveXattr(ctx context.Context, xattr *types.GetXattrReq) (err error) {
+	sqltext := "delete from xattr where region=? and bucket_name=? and ino=? and name=?;"
+	result, err := t.Client.Exec(sqltext, xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to remove the xattr, err: %v", err))
+		err = ErrYIgFsInternalErr
+		return
+	}
+
+	affectRows, _ := result.RowsAffected()
+	if affectRows == 0 {
+		helper.Logger.Error(ctx, fmt.Sprintf("The xattr is not existed, region: %v, bucket: %v, ino: %v, name: %v",
+			xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name))
+		err = ErrYigFsNoSuchXattr
+		return
+	}
+
+	helper.Logger.Info(ctx, fmt.Sprintf("Succeed to remove the xattr, region: %v, bucket: %v, ino: %v, name: %v",
+		xattr.Region, xattr.BucketName, xattr.Ino, xattr.Name))
+	return
+}
@@ -46,3 +46,11 @@ func(m *Meta) UpdateFileSizeAndBlocksNum(ctx context.Context, file *types.GetFil
 func(m *Meta) RenameFile(ctx context.Context, file *types.RenameFileReq) (err error) {
 	return m.Client.RenameFile(ctx, file)
 }
+
+func(m *Meta) DeleteDir(ctx context.Context, dir *types.DeleteDirReq) (err error) {
+	return m.Client.DeleteDir(ctx, dir)
+}
+
+func(m *Meta) GetFsUsage(ctx context.Context, fs *types.GetFsUsageReq) (usedSize uint64, fileCount uint64, err error) {
+	return m.Client.GetFsUsage(ctx, fs)
+}
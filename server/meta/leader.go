@@ -14,3 +14,7 @@ func(m *Meta) GetFileLeaderInfo(ctx context.Context, leader *types.GetLeaderReq)
 func(m *Meta) CreateOrUpdateFileLeader(ctx context.Context, leader *types.GetLeaderReq) (err error) {
 	return m.Client.CreateOrUpdateFileLeader(ctx, leader)
 }
+
+func(m *Meta) ChangeFileLeader(ctx context.Context, leader *types.ChangeLeaderReq) (resp *types.LeaderInfo, isChanged bool, err error) {
+	return m.Client.ChangeFileLeader(ctx, leader)
+}
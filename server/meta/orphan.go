@@ -0,0 +1,20 @@
+package meta
+
+import (
+	"context"
+
+	"github.com/hopkings2008/yigfs/server/types"
+)
+
+
+func(m *Meta) AddOrphan(ctx context.Context, orphan *types.AddOrphanReq) (err error) {
+	return m.Client.AddOrphan(ctx, orphan)
+}
+
+func(m *Meta) RemoveOrphan(ctx context.Context, orphan *types.OrphanReq) (err error) {
+	return m.Client.RemoveOrphan(ctx, orphan)
+}
+
+func(m *Meta) ListOrphans(ctx context.Context, machine *types.ListOrphansReq) (inos []uint64, err error) {
+	return m.Client.ListOrphans(ctx, machine)
+}
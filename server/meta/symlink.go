@@ -0,0 +1,16 @@
+package meta
+
+import (
+	"context"
+
+	"github.com/hopkings2008/yigfs/server/types"
+)
+
+
+func(m *Meta) CreateSymlink(ctx context.Context, link *types.CreateSymlinkReq) (err error) {
+	return m.Client.CreateSymlink(ctx, link)
+}
+
+func(m *Meta) ReadSymlink(ctx context.Context, file *types.GetFileInfoReq) (target string, err error) {
+	return m.Client.ReadSymlink(ctx, file)
+}
@@ -0,0 +1,24 @@
+package meta
+
+import (
+	"context"
+
+	"github.com/hopkings2008/yigfs/server/types"
+)
+
+
+func(m *Meta) SetXattr(ctx context.Context, xattr *types.SetXattrReq) (err error) {
+	return m.Client.SetXattr(ctx, xattr)
+}
+
+func(m *Meta) GetXattr(ctx context.Context, xattr *types.GetXattrReq) (value []byte, err error) {
+	return m.Client.GetXattr(ctx, xattr)
+}
+
+func(m *Meta) ListXattrs(ctx context.Context, file *types.GetFileInfoReq) (names []string, err error) {
+	return m.Client.ListXattrs(ctx, file)
+}
+
+func(m *Meta) RemoveXattr(ctx context.Context, xattr *types.GetXattrReq) (err error) {
+	return m.Client.RemoveXattr(ctx, xattr)
+}
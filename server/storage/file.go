@@ -203,3 +203,64 @@ func(yigFs *YigFsStorage) RenameFile(ctx context.Context, file *types.RenameFile
 	}
 	return
 }
+
+func(yigFs *YigFsStorage) DeleteDir(ctx context.Context, dir *types.DeleteDirReq) (resp *types.FileInfo, err error) {
+	err = yigFs.MetaStorage.Client.DeleteDir(ctx, dir)
+	if err != nil {
+		return
+	}
+
+	// return the parent dir whose ctime & mtime are updated.
+	parentReq := &types.GetFileInfoReq {
+		Region: dir.Region,
+		BucketName: dir.BucketName,
+		Ino: dir.ParentIno,
+	}
+
+	resp, err = yigFs.MetaStorage.Client.GetFileInfo(ctx, parentReq)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("DeleteDir: Failed to get parent dir info, region: %s, bucket: %s, ino: %d, err: %v",
+			parentReq.Region, parentReq.BucketName, parentReq.Ino, err))
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) CreateSymlink(ctx context.Context, link *types.CreateSymlinkReq) (resp *types.FileInfo, err error) {
+	err = yigFs.MetaStorage.Client.CreateSymlink(ctx, link)
+	if err != nil {
+		return
+	}
+
+	getFileReq := &types.GetDirFileInfoReq {
+		Region: link.Region,
+		BucketName: link.BucketName,
+		ParentIno: link.ParentIno,
+		FileName: link.FileName,
+	}
+
+	resp, err = yigFs.MetaStorage.Client.GetDirFileInfo(ctx, getFileReq)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("CreateSymlink: Failed to get symlink info, region: %s, bucket: %s, parent_ino: %d, filename: %s, err: %v",
+			getFileReq.Region, getFileReq.BucketName, getFileReq.ParentIno, getFileReq.FileName, err))
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) ReadSymlink(ctx context.Context, file *types.GetFileInfoReq) (target string, err error) {
+	target, err = yigFs.MetaStorage.Client.ReadSymlink(ctx, file)
+	if err != nil {
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) GetFsUsage(ctx context.Context, fs *types.GetFsUsageReq) (resp *types.GetFsUsageResp, err error) {
+	resp = &types.GetFsUsageResp{}
+	resp.UsedSize, resp.FileCount, err = yigFs.MetaStorage.Client.GetFsUsage(ctx, fs)
+	if err != nil {
+		return
+	}
+	return
+}
@@ -28,11 +28,12 @@ func GetMachineAndUpdateFileLeader(ctx context.Context, leader *types.GetLeaderR
 		return
 	}
 
-	resp = &types.GetLeaderResp {
-		LeaderInfo: &types.LeaderInfo {
-			ZoneId: leader.ZoneId,
-			Leader: machine,
-		},
+	// get the token increased by the update.
+	resp, err = yigFs.MetaStorage.Client.GetFileLeaderInfo(ctx, leader)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to get the updated leader, zone_id: %s, region: %s, bucket: %s, ino: %d, err: %v",
+			leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, err))
+		return
 	}
 
 	helper.Logger.Info(ctx, fmt.Sprintf("Get one up machine is: %s, zone_id is: %s", machine, leader.ZoneId))
@@ -160,6 +161,95 @@ func(yigFs *YigFsStorage) GetFileLeader(ctx context.Context, leader *types.GetLe
 	return
 }
 
+// check whether the machine is up in the zone.
+func isMachineUp(ctx context.Context, leader *types.ChangeLeaderReq, machine string, yigFs *YigFsStorage) (isUp bool, err error) {
+	zone := &types.GetLeaderReq {
+		ZoneId: leader.ZoneId,
+		Region: leader.Region,
+		BucketName: leader.BucketName,
+		Machine: machine,
+	}
+
+	getMachineInfoResp, err := yigFs.MetaStorage.Client.GetMachineInfo(ctx, zone)
+	if err == ErrYigFsNoSuchMachine {
+		return false, nil
+	} else if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("Failed to get machine info, zone_id: %s, region: %s, bucket: %s, machine: %s, err: %v",
+			leader.ZoneId, leader.Region, leader.BucketName, machine, err))
+		return
+	}
+
+	return getMachineInfoResp.Status == types.MachineUp, nil
+}
+
+func(yigFs *YigFsStorage) TransferFileLeader(ctx context.Context, leader *types.ChangeLeaderReq) (resp *types.GetLeaderResp, err error) {
+	current := &types.GetLeaderReq {
+		ZoneId: leader.ZoneId,
+		Region: leader.Region,
+		BucketName: leader.BucketName,
+		Ino: leader.Ino,
+		Generation: leader.Generation,
+	}
+
+	resp, err = yigFs.MetaStorage.Client.GetFileLeaderInfo(ctx, current)
+	if err != nil {
+		helper.Logger.Error(ctx, fmt.Sprintf("TransferFileLeader: Failed to get the file leader, zone_id: %s, region: %s, bucket: %s, ino: %d, err: %v",
+			leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, err))
+		return
+	}
+
+	// the file is taken over only if its leader is down, the current leader is returned otherwise.
+	isUp, err := isMachineUp(ctx, leader, resp.LeaderInfo.Leader, yigFs)
+	if err != nil {
+		return
+	}
+
+	if isUp {
+		helper.Logger.Warn(ctx, fmt.Sprintf("TransferFileLeader: The leader is still up, zone_id: %s, region: %s, bucket: %s, ino: %d, leader: %s",
+			leader.ZoneId, leader.Region, leader.BucketName, leader.Ino, resp.LeaderInfo.Leader))
+		return
+	}
+
+	leaderInfo, _, err := yigFs.MetaStorage.Client.ChangeFileLeader(ctx, leader)
+	if err != nil {
+		return
+	}
+
+	resp.LeaderInfo = leaderInfo
+	return
+}
+
+func(yigFs *YigFsStorage) MigrateFileLeader(ctx context.Context, leader *types.ChangeLeaderReq) (resp *types.GetLeaderResp, err error) {
+	// the new leader must be up to serve the file.
+	isUp, err := isMachineUp(ctx, leader, leader.Machine, yigFs)
+	if err != nil {
+		return
+	}
+
+	if !isUp {
+		helper.Logger.Error(ctx, fmt.Sprintf("MigrateFileLeader: The machine is not up, zone_id: %s, region: %s, bucket: %s, machine: %s",
+			leader.ZoneId, leader.Region, leader.BucketName, leader.Machine))
+		err = ErrYigFsLeaderStatusIsInvalid
+		return
+	}
+
+	leaderInfo, isChanged, err := yigFs.MetaStorage.Client.ChangeFileLeader(ctx, leader)
+	if err != nil {
+		return
+	}
+
+	// the token isn't held by the current leader, it may be taken over by the others.
+	if !isChanged {
+		err = ErrYigFsMachineNotMatchFileLeader
+		return
+	}
+
+	resp = &types.GetLeaderResp {
+		LeaderInfo: leaderInfo,
+	}
+	return
+}
+
 func(yigFs *YigFsStorage) CheckSegmentLeader(ctx context.Context, segment *types.CreateSegmentReq) (err error) {
 	// get segment leader
 	segLeader := &types.GetSegLeaderReq {
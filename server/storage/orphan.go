@@ -0,0 +1,32 @@
+package storage
+
+import (
+	"context"
+
+	"github.com/hopkings2008/yigfs/server/types"
+)
+
+
+func(yigFs *YigFsStorage) AddOrphan(ctx context.Context, orphan *types.AddOrphanReq) (err error) {
+	err = yigFs.MetaStorage.Client.AddOrphan(ctx, orphan)
+	if err != nil {
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) RemoveOrphan(ctx context.Context, orphan *types.OrphanReq) (err error) {
+	err = yigFs.MetaStorage.Client.RemoveOrphan(ctx, orphan)
+	if err != nil {
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) ListOrphans(ctx context.Context, machine *types.ListOrphansReq) (inos []uint64, err error) {
+	inos, err = yigFs.MetaStorage.Client.ListOrphans(ctx, machine)
+	if err != nil {
+		return
+	}
+	return
+}
@@ -0,0 +1,40 @@
+package storage
+
+import (
+	"context"
+
+	"github.com/hopkings2008/yigfs/server/types"
+)
+
+
+func(yigFs *YigFsStorage) SetXattr(ctx context.Context, xattr *types.SetXattrReq) (err error) {
+	err = yigFs.MetaStorage.Client.SetXattr(ctx, xattr)
+	if err != nil {
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) GetXattr(ctx context.Context, xattr *types.GetXattrReq) (value []byte, err error) {
+	value, err = yigFs.MetaStorage.Client.GetXattr(ctx, xattr)
+	if err != nil {
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) ListXattrs(ctx context.Context, file *types.GetFileInfoReq) (names []string, err error) {
+	names, err = yigFs.MetaStorage.Client.ListXattrs(ctx, file)
+	if err != nil {
+		return
+	}
+	return
+}
+
+func(yigFs *YigFsStorage) RemoveXattr(ctx context.Context, xattr *types.GetXattrReq) (err error) {
+	err = yigFs.MetaStorage.Client.RemoveXattr(ctx, xattr)
+	if err != nil {
+		return
+	}
+	return
+}
@@ -118,6 +118,56 @@ func GetFileLeader(getLeaderReq *types.GetLeaderReq) (getFileLeaderResp *types.G
 	return getFileLeaderResp, string(getFileLeaderInfo), nil
 }
 
+func TransferFileLeader(changeLeaderReq *types.ChangeLeaderReq) (changeLeaderResp *types.GetLeaderResp, result string, err error) {
+	changeLeaderResp = &types.GetLeaderResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/leader"
+
+	reqStr, err := json.Marshal(changeLeaderReq)
+	if err != nil {
+		return changeLeaderResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("PUT", newServer, sc, reqStr)
+	if err != nil {
+		return changeLeaderResp, "", err
+	}
+	defer resp.Close()
+
+	changeLeaderInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(changeLeaderInfo, &changeLeaderResp); err != nil {
+		return changeLeaderResp, "", err
+	}
+
+	return changeLeaderResp, string(changeLeaderInfo), nil
+}
+
+func MigrateFileLeader(changeLeaderReq *types.ChangeLeaderReq) (changeLeaderResp *types.GetLeaderResp, result string, err error) {
+	changeLeaderResp = &types.GetLeaderResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/leader/migrate"
+
+	reqStr, err := json.Marshal(changeLeaderReq)
+	if err != nil {
+		return changeLeaderResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("PUT", newServer, sc, reqStr)
+	if err != nil {
+		return changeLeaderResp, "", err
+	}
+	defer resp.Close()
+
+	changeLeaderInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(changeLeaderInfo, &changeLeaderResp); err != nil {
+		return changeLeaderResp, "", err
+	}
+
+	return changeLeaderResp, string(changeLeaderInfo), nil
+}
+
 func PutFile(createFileReq *types.CreateFileReq) (createFileResp *types.CreateFileResp, result string, err error) {
 	createFileResp = &types.CreateFileResp{}
 	sc := NewClient()
@@ -192,3 +242,278 @@ func RenameFile(renameFileReq *types.RenameFileReq) (renameFileResp *types.NonBo
 
 	return renameFileResp, string(renameFileInfo), nil
 }
+
+func DeleteDir(deleteDirReq *types.DeleteDirReq) (deleteDirResp *types.DeleteDirResp, result string, err error) {
+	deleteDirResp = &types.DeleteDirResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/dir/remove"
+
+	reqStr, err := json.Marshal(deleteDirReq)
+	if err != nil {
+		return deleteDirResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("POST", newServer, sc, reqStr)
+	if err != nil {
+		return deleteDirResp, "", err
+	}
+	defer resp.Close()
+
+	deleteDirInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(deleteDirInfo, &deleteDirResp); err != nil {
+		return deleteDirResp, "", err
+	}
+
+	return deleteDirResp, string(deleteDirInfo), nil
+}
+
+func CreateSymlink(createSymlinkReq *types.CreateSymlinkReq) (createSymlinkResp *types.CreateSymlinkResp, result string, err error) {
+	createSymlinkResp = &types.CreateSymlinkResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/dir/symlink"
+
+	reqStr, err := json.Marshal(createSymlinkReq)
+	if err != nil {
+		return createSymlinkResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("PUT", newServer, sc, reqStr)
+	if err != nil {
+		return createSymlinkResp, "", err
+	}
+	defer resp.Close()
+
+	createSymlinkInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(createSymlinkInfo, &createSymlinkResp); err != nil {
+		return createSymlinkResp, "", err
+	}
+
+	return createSymlinkResp, string(createSymlinkInfo), nil
+}
+
+func ReadSymlink(readSymlinkReq *types.GetFileInfoReq) (readSymlinkResp *types.ReadSymlinkResp, result string, err error) {
+	readSymlinkResp = &types.ReadSymlinkResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/symlink"
+
+	reqStr, err := json.Marshal(readSymlinkReq)
+	if err != nil {
+		return readSymlinkResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("GET", newServer, sc, reqStr)
+	if err != nil {
+		return readSymlinkResp, "", err
+	}
+	defer resp.Close()
+
+	readSymlinkInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(readSymlinkInfo, &readSymlinkResp); err != nil {
+		return readSymlinkResp, "", err
+	}
+
+	return readSymlinkResp, string(readSymlinkInfo), nil
+}
+
+func GetFsUsage(getFsUsageReq *types.GetFsUsageReq) (getFsUsageResp *types.GetFsUsageResp, result string, err error) {
+	getFsUsageResp = &types.GetFsUsageResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/fs/usage"
+
+	reqStr, err := json.Marshal(getFsUsageReq)
+	if err != nil {
+		return getFsUsageResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("GET", newServer, sc, reqStr)
+	if err != nil {
+		return getFsUsageResp, "", err
+	}
+	defer resp.Close()
+
+	getFsUsageInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(getFsUsageInfo, &getFsUsageResp); err != nil {
+		return getFsUsageResp, "", err
+	}
+
+	return getFsUsageResp, string(getFsUsageInfo), nil
+}
+
+func SetXattr(setXattrReq *types.SetXattrReq) (setXattrResp *types.NonBodyResp, result string, err error) {
+	setXattrResp = &types.NonBodyResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/xattr"
+
+	reqStr, err := json.Marshal(setXattrReq)
+	if err != nil {
+		return setXattrResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("PUT", newServer, sc, reqStr)
+	if err != nil {
+		return setXattrResp, "", err
+	}
+	defer resp.Close()
+
+	setXattrInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(setXattrInfo, &setXattrResp); err != nil {
+		return setXattrResp, "", err
+	}
+
+	return setXattrResp, string(setXattrInfo), nil
+}
+
+func GetXattr(getXattrReq *types.GetXattrReq) (getXattrResp *types.GetXattrResp, result string, err error) {
+	getXattrResp = &types.GetXattrResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/xattr"
+
+	reqStr, err := json.Marshal(getXattrReq)
+	if err != nil {
+		return getXattrResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("GET", newServer, sc, reqStr)
+	if err != nil {
+		return getXattrResp, "", err
+	}
+	defer resp.Close()
+
+	getXattrInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(getXattrInfo, &getXattrResp); err != nil {
+		return getXattrResp, "", err
+	}
+
+	return getXattrResp, string(getXattrInfo), nil
+}
+
+func ListXattrs(listXattrsReq *types.GetFileInfoReq) (listXattrsResp *types.ListXattrsResp, result string, err error) {
+	listXattrsResp = &types.ListXattrsResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/xattrs"
+
+	reqStr, err := json.Marshal(listXattrsReq)
+	if err != nil {
+		return listXattrsResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("GET", newServer, sc, reqStr)
+	if err != nil {
+		return listXattrsResp, "", err
+	}
+	defer resp.Close()
+
+	listXattrsInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(listXattrsInfo, &listXattrsResp); err != nil {
+		return listXattrsResp, "", err
+	}
+
+	return listXattrsResp, string(listXattrsInfo), nil
+}
+
+func RemoveXattr(removeXattrReq *types.GetXattrReq) (removeXattrResp *types.NonBodyResp, result string, err error) {
+	removeXattrResp = &types.NonBodyResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/xattr/remove"
+
+	reqStr, err := json.Marshal(removeXattrReq)
+	if err != nil {
+		return removeXattrResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("POST", newServer, sc, reqStr)
+	if err != nil {
+		return removeXattrResp, "", err
+	}
+	defer resp.Close()
+
+	removeXattrInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(removeXattrInfo, &removeXattrResp); err != nil {
+		return removeXattrResp, "", err
+	}
+
+	return removeXattrResp, string(removeXattrInfo), nil
+}
+
+func AddOrphan(addOrphanReq *types.AddOrphanReq) (addOrphanResp *types.NonBodyResp, result string, err error) {
+	addOrphanResp = &types.NonBodyResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/orphan"
+
+	reqStr, err := json.Marshal(addOrphanReq)
+	if err != nil {
+		return addOrphanResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("PUT", newServer, sc, reqStr)
+	if err != nil {
+		return addOrphanResp, "", err
+	}
+	defer resp.Close()
+
+	addOrphanInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(addOrphanInfo, &addOrphanResp); err != nil {
+		return addOrphanResp, "", err
+	}
+
+	return addOrphanResp, string(addOrphanInfo), nil
+}
+
+func RemoveOrphan(removeOrphanReq *types.OrphanReq) (removeOrphanResp *types.NonBodyResp, result string, err error) {
+	removeOrphanResp = &types.NonBodyResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/file/orphan/remove"
+
+	reqStr, err := json.Marshal(removeOrphanReq)
+	if err != nil {
+		return removeOrphanResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("POST", newServer, sc, reqStr)
+	if err != nil {
+		return removeOrphanResp, "", err
+	}
+	defer resp.Close()
+
+	removeOrphanInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(removeOrphanInfo, &removeOrphanResp); err != nil {
+		return removeOrphanResp, "", err
+	}
+
+	return removeOrphanResp, string(removeOrphanInfo), nil
+}
+
+func ListOrphans(listOrphansReq *types.ListOrphansReq) (listOrphansResp *types.ListOrphansResp, result string, err error) {
+	listOrphansResp = &types.ListOrphansResp{}
+	sc := NewClient()
+	newServer := Endpoint + "/v1/machine/orphans"
+
+	reqStr, err := json.Marshal(listOrphansReq)
+	if err != nil {
+		return listOrphansResp, "", err
+	}
+
+	resp, err := SendHttpToYigFs("GET", newServer, sc, reqStr)
+	if err != nil {
+		return listOrphansResp, "", err
+	}
+	defer resp.Close()
+
+	listOrphansInfo, _ := ioutil.ReadAll(resp)
+
+	if err = json.Unmarshal(listOrphansInfo, &listOrphansResp); err != nil {
+		return listOrphansResp, "", err
+	}
+
+	return listOrphansResp, string(listOrphansInfo), nil
+}
@@ -702,4 +702,549 @@ func Test_HeartBeat(t *testing.T) {
 	r.Nil(err)
 	r.Equal(heartBeatResp.Result.ErrCode, 0)
 	t.Logf("Succeed to test heart beat, resp: %s", heartBeatInfo)
-}
\ No newline at end of file
+}
+
+func Test_DeleteDir(t *testing.T) {
+	r := require.New(t)
+	// create the dir and a file in it
+	createDirReq := &types.CreateFileReq{
+		ZoneId:     ZoneId,
+		Region:     Region,
+		BucketName: BucketName,
+		ParentIno:  FileParentIno,
+		FileName:   RmDirName,
+		Size:       CreateFileSize,
+		Type:       types.DIR_FILE,
+		Perm:       types.DIR_PERM,
+		Machine:    Machine,
+	}
+
+	createDirResp, createDirInfo, err := PutFile(createDirReq)
+	r.Nil(err)
+	r.Equal(createDirResp.Result.ErrCode, 0)
+	t.Logf("Succeed to create the dir, resp: %s", createDirInfo)
+
+	createFileReq := &types.CreateFileReq{
+		ZoneId:     ZoneId,
+		Region:     Region,
+		BucketName: BucketName,
+		ParentIno:  createDirResp.File.Ino,
+		FileName:   FileName,
+		Size:       CreateFileSize,
+		Type:       types.COMMON_FILE,
+		Perm:       types.FILE_PERM,
+		Machine:    Machine,
+	}
+
+	createFileResp, createFileInfo, err := PutFile(createFileReq)
+	r.Nil(err)
+	r.Equal(createFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to create the file in the dir, resp: %s", createFileInfo)
+
+	// the dir is not empty
+	deleteDirReq := &types.DeleteDirReq {
+		Region: Region,
+		BucketName: BucketName,
+		ZoneId: ZoneId,
+		Machine: Machine,
+		ParentIno: FileParentIno,
+		FileName: RmDirName,
+	}
+
+	deleteDirResp, deleteDirInfo, err := DeleteDir(deleteDirReq)
+	r.Nil(err)
+	r.Equal(deleteDirResp.Result.ErrCode, 40021)
+	t.Logf("Succeed to resp err, for the dir is not empty, resp: %s", deleteDirInfo)
+
+	// the file is not a dir
+	deleteFileAsDirReq := &types.DeleteDirReq {
+		Region: Region,
+		BucketName: BucketName,
+		ZoneId: ZoneId,
+		Machine: Machine,
+		ParentIno: createDirResp.File.Ino,
+		FileName: FileName,
+	}
+
+	deleteDirResp, deleteDirInfo, err = DeleteDir(deleteFileAsDirReq)
+	r.Nil(err)
+	r.Equal(deleteDirResp.Result.ErrCode, 40022)
+	t.Logf("Succeed to resp err, for the file is not a dir, resp: %s", deleteDirInfo)
+
+	// delete the file, then the dir
+	deleteFileReq := &types.DeleteFileReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: createFileResp.File.Ino,
+		ZoneId: ZoneId,
+		Machine: Machine,
+	}
+
+	deleteFileResp, deleteFileRespInfo, err := DeleteFile(deleteFileReq)
+	r.Nil(err)
+	r.Equal(deleteFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to delete the file in the dir, resp: %s", deleteFileRespInfo)
+
+	deleteDirResp, deleteDirInfo, err = DeleteDir(deleteDirReq)
+	r.Nil(err)
+	r.Equal(deleteDirResp.Result.ErrCode, 0)
+	r.Equal(deleteDirResp.File.Ino, FileParentIno)
+	t.Logf("Succeed to delete the dir, resp: %s", deleteDirInfo)
+
+	// get the removed dir
+	getDirReq := &types.GetDirFileInfoReq {
+		Region: Region,
+		BucketName: BucketName,
+		ParentIno: FileParentIno,
+		FileName: RmDirName,
+	}
+
+	getDirResp, getDirInfo, err := GetDirFileAttr(getDirReq)
+	r.Nil(err)
+	r.Equal(getDirResp.Result.ErrCode, 40002)
+	t.Logf("Succeed to get the removed dir, resp: %s", getDirInfo)
+}
+
+func Test_Symlink(t *testing.T) {
+	r := require.New(t)
+	createSymlinkReq := &types.CreateSymlinkReq{
+		ZoneId:     ZoneId,
+		Machine:    Machine,
+		Region:     Region,
+		BucketName: BucketName,
+		ParentIno:  FileParentIno,
+		FileName:   LinkName,
+		Type:       types.LINK_FILE,
+		Perm:       types.LINK_PERM,
+		Target:     FileName,
+	}
+
+	createSymlinkResp, createSymlinkInfo, err := CreateSymlink(createSymlinkReq)
+	r.Nil(err)
+	r.Equal(createSymlinkResp.Result.ErrCode, 0)
+	r.Equal(createSymlinkResp.File.Type, uint(types.LINK_FILE))
+	r.Equal(createSymlinkResp.File.Size, uint64(len(FileName)))
+	t.Logf("Succeed to create the symlink, resp: %s", createSymlinkInfo)
+	linkIno := createSymlinkResp.File.Ino
+
+	// the name is already used
+	createSymlinkResp, createSymlinkInfo, err = CreateSymlink(createSymlinkReq)
+	r.Nil(err)
+	r.Equal(createSymlinkResp.Result.ErrCode, 40011)
+	t.Logf("Succeed to resp err, for the symlink is already existed, resp: %s", createSymlinkInfo)
+
+	// read the target
+	readSymlinkReq := &types.GetFileInfoReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: linkIno,
+	}
+
+	readSymlinkResp, readSymlinkInfo, err := ReadSymlink(readSymlinkReq)
+	r.Nil(err)
+	r.Equal(readSymlinkResp.Result.ErrCode, 0)
+	r.Equal(readSymlinkResp.Target, FileName)
+	t.Logf("Succeed to read the symlink, resp: %s", readSymlinkInfo)
+
+	// the dir is not a symlink
+	readDirReq := &types.GetFileInfoReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: FileParentIno,
+	}
+
+	readSymlinkResp, readSymlinkInfo, err = ReadSymlink(readDirReq)
+	r.Nil(err)
+	r.Equal(readSymlinkResp.Result.ErrCode, 40006)
+	t.Logf("Succeed to resp err, for the dir is not a symlink, resp: %s", readSymlinkInfo)
+
+	// remove the symlink
+	deleteFileReq := &types.DeleteFileReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: linkIno,
+		ZoneId: ZoneId,
+		Machine: Machine,
+	}
+
+	deleteFileResp, deleteFileRespInfo, err := DeleteFile(deleteFileReq)
+	r.Nil(err)
+	r.Equal(deleteFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to delete the symlink, resp: %s", deleteFileRespInfo)
+
+	readSymlinkResp, readSymlinkInfo, err = ReadSymlink(readSymlinkReq)
+	r.Nil(err)
+	r.Equal(readSymlinkResp.Result.ErrCode, 40002)
+	t.Logf("Succeed to read the removed symlink, resp: %s", readSymlinkInfo)
+}
+
+func Test_GetFsUsage(t *testing.T) {
+	r := require.New(t)
+	getFsUsageReq := &types.GetFsUsageReq {
+		Region: Region,
+		BucketName: BucketName,
+	}
+
+	getFsUsageResp, getFsUsageInfo, err := GetFsUsage(getFsUsageReq)
+	r.Nil(err)
+	r.Equal(getFsUsageResp.Result.ErrCode, 0)
+	t.Logf("Succeed to get fs usage, resp: %s", getFsUsageInfo)
+
+	// the new file is counted
+	createFileReq := &types.CreateFileReq{
+		ZoneId:     ZoneId,
+		Region:     Region,
+		BucketName: BucketName,
+		ParentIno:  FileParentIno,
+		FileName:   UsageFileName,
+		Size:       Size,
+		Type:       types.COMMON_FILE,
+		Perm:       types.FILE_PERM,
+		Machine:    Machine,
+	}
+
+	createFileResp, createFileInfo, err := PutFile(createFileReq)
+	r.Nil(err)
+	r.Equal(createFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to create new file, resp: %s", createFileInfo)
+
+	newUsageResp, newUsageInfo, err := GetFsUsage(getFsUsageReq)
+	r.Nil(err)
+	r.Equal(newUsageResp.Result.ErrCode, 0)
+	r.Equal(newUsageResp.FileCount, getFsUsageResp.FileCount + 1)
+	r.Equal(newUsageResp.UsedSize, getFsUsageResp.UsedSize + Size)
+	t.Logf("Succeed to get fs usage with the new file, resp: %s", newUsageInfo)
+
+	deleteFileReq := &types.DeleteFileReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: createFileResp.File.Ino,
+		ZoneId: ZoneId,
+		Machine: Machine,
+	}
+
+	deleteFileResp, deleteFileRespInfo, err := DeleteFile(deleteFileReq)
+	r.Nil(err)
+	r.Equal(deleteFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to delete the file, resp: %s", deleteFileRespInfo)
+}
+
+func Test_Xattr(t *testing.T) {
+	r := require.New(t)
+	createFileReq := &types.CreateFileReq{
+		ZoneId:     ZoneId,
+		Region:     Region,
+		BucketName: BucketName,
+		ParentIno:  FileParentIno,
+		FileName:   XattrFileName,
+		Size:       CreateFileSize,
+		Type:       types.COMMON_FILE,
+		Perm:       types.FILE_PERM,
+		Machine:    Machine,
+	}
+
+	createFileResp, createFileInfo, err := PutFile(createFileReq)
+	r.Nil(err)
+	r.Equal(createFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to create new file, resp: %s", createFileInfo)
+	ino := createFileResp.File.Ino
+
+	// the xattr is not existed
+	getXattrReq := &types.GetXattrReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		Name: XattrName,
+	}
+
+	getXattrResp, getXattrInfo, err := GetXattr(getXattrReq)
+	r.Nil(err)
+	r.Equal(getXattrResp.Result.ErrCode, 40023)
+	t.Logf("Succeed to resp err, for the xattr is not existed, resp: %s", getXattrInfo)
+
+	setXattrReq := &types.SetXattrReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		Name: XattrName,
+		Value: []byte(XattrValue),
+		Flags: types.XATTR_REPLACE,
+	}
+
+	setXattrResp, setXattrInfo, err := SetXattr(setXattrReq)
+	r.Nil(err)
+	r.Equal(setXattrResp.Result.ErrCode, 40023)
+	t.Logf("Succeed to resp err, for the replaced xattr is not existed, resp: %s", setXattrInfo)
+
+	setXattrReq.Flags = types.XATTR_CREATE
+	setXattrResp, setXattrInfo, err = SetXattr(setXattrReq)
+	r.Nil(err)
+	r.Equal(setXattrResp.Result.ErrCode, 0)
+	t.Logf("Succeed to set xattr, resp: %s", setXattrInfo)
+
+	setXattrResp, setXattrInfo, err = SetXattr(setXattrReq)
+	r.Nil(err)
+	r.Equal(setXattrResp.Result.ErrCode, 40024)
+	t.Logf("Succeed to resp err, for the created xattr is already existed, resp: %s", setXattrInfo)
+
+	getXattrResp, getXattrInfo, err = GetXattr(getXattrReq)
+	r.Nil(err)
+	r.Equal(getXattrResp.Result.ErrCode, 0)
+	r.Equal(string(getXattrResp.Value), XattrValue)
+	t.Logf("Succeed to get xattr, resp: %s", getXattrInfo)
+
+	// list the xattrs
+	listXattrsReq := &types.GetFileInfoReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+	}
+
+	listXattrsResp, listXattrsInfo, err := ListXattrs(listXattrsReq)
+	r.Nil(err)
+	r.Equal(listXattrsResp.Result.ErrCode, 0)
+	r.Equal(listXattrsResp.Names, []string{XattrName})
+	t.Logf("Succeed to list xattrs, resp: %s", listXattrsInfo)
+
+	// remove the xattr
+	removeXattrResp, removeXattrInfo, err := RemoveXattr(getXattrReq)
+	r.Nil(err)
+	r.Equal(removeXattrResp.Result.ErrCode, 0)
+	t.Logf("Succeed to remove xattr, resp: %s", removeXattrInfo)
+
+	removeXattrResp, removeXattrInfo, err = RemoveXattr(getXattrReq)
+	r.Nil(err)
+	r.Equal(removeXattrResp.Result.ErrCode, 40023)
+	t.Logf("Succeed to resp err, for the removed xattr is not existed, resp: %s", removeXattrInfo)
+
+	deleteFileReq := &types.DeleteFileReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		ZoneId: ZoneId,
+		Machine: Machine,
+	}
+
+	deleteFileResp, deleteFileRespInfo, err := DeleteFile(deleteFileReq)
+	r.Nil(err)
+	r.Equal(deleteFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to delete the file, resp: %s", deleteFileRespInfo)
+}
+
+
+func Test_Orphan(t *testing.T) {
+	r := require.New(t)
+	createFileReq := &types.CreateFileReq{
+		ZoneId:     ZoneId,
+		Region:     Region,
+		BucketName: BucketName,
+		ParentIno:  FileParentIno,
+		FileName:   OrphanFileName,
+		Size:       CreateFileSize,
+		Type:       types.COMMON_FILE,
+		Perm:       types.FILE_PERM,
+		Machine:    Machine,
+	}
+
+	createFileResp, createFileInfo, err := PutFile(createFileReq)
+	r.Nil(err)
+	r.Equal(createFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to create new file, resp: %s", createFileInfo)
+	ino := createFileResp.File.Ino
+
+	addOrphanReq := &types.AddOrphanReq {
+		ZoneId: ZoneId,
+		Machine: Machine,
+		Region: Region,
+		BucketName: BucketName,
+		ParentIno: FileParentIno,
+		FileName: OrphanFileName,
+		Ino: ino,
+	}
+
+	addOrphanResp, addOrphanInfo, err := AddOrphan(addOrphanReq)
+	r.Nil(err)
+	r.Equal(addOrphanResp.Result.ErrCode, 0)
+	t.Logf("Succeed to add orphan, resp: %s", addOrphanInfo)
+
+	// adding the detached orphan again is ok.
+	addOrphanResp, addOrphanInfo, err = AddOrphan(addOrphanReq)
+	r.Nil(err)
+	r.Equal(addOrphanResp.Result.ErrCode, 0)
+	t.Logf("Succeed to add orphan again, resp: %s", addOrphanInfo)
+
+	// the name is detached from the parent dir.
+
+	file := &types.GetDirFileInfoReq{
+		Region: Region,
+		BucketName: BucketName,
+		ParentIno: FileParentIno,
+		FileName: OrphanFileName,
+	}
+
+	getDirFileResp, getDirFileInfo, err := GetDirFileAttr(file)
+	r.Nil(err)
+	r.Equal(getDirFileResp.Result.ErrCode, 40002)
+	t.Logf("Succeed to resp err, for the name of the orphan is removed, resp: %s", getDirFileInfo)
+
+	// but the ino is kept.
+	getFileReq := &types.GetFileInfoReq{
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+	}
+
+	getFileResp, getFileInfo, err := GetFileAttr(getFileReq)
+	r.Nil(err)
+	r.Equal(getFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to get the attr of the orphan, resp: %s", getFileInfo)
+
+	listOrphansReq := &types.ListOrphansReq {
+		ZoneId: ZoneId,
+		Machine: Machine,
+		Region: Region,
+		BucketName: BucketName,
+	}
+
+	listOrphansResp, listOrphansInfo, err := ListOrphans(listOrphansReq)
+	r.Nil(err)
+	r.Equal(listOrphansResp.Result.ErrCode, 0)
+	r.Contains(listOrphansResp.Inos, ino)
+	t.Logf("Succeed to list orphans, resp: %s", listOrphansInfo)
+
+	deleteFileReq := &types.DeleteFileReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		ZoneId: ZoneId,
+		Machine: Machine,
+	}
+
+	deleteFileResp, deleteFileRespInfo, err := DeleteFile(deleteFileReq)
+	r.Nil(err)
+	r.Equal(deleteFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to delete the orphan, resp: %s", deleteFileRespInfo)
+
+	removeOrphanReq := &types.OrphanReq {
+		ZoneId: ZoneId,
+		Machine: Machine,
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+	}
+
+	removeOrphanResp, removeOrphanInfo, err := RemoveOrphan(removeOrphanReq)
+	r.Nil(err)
+	r.Equal(removeOrphanResp.Result.ErrCode, 0)
+	t.Logf("Succeed to remove orphan, resp: %s", removeOrphanInfo)
+
+	listOrphansResp, listOrphansInfo, err = ListOrphans(listOrphansReq)
+	r.Nil(err)
+	r.Equal(listOrphansResp.Result.ErrCode, 0)
+	r.NotContains(listOrphansResp.Inos, ino)
+	t.Logf("Succeed to list orphans, resp: %s", listOrphansInfo)
+}
+
+func Test_MigrateLeader(t *testing.T) {
+	r := require.New(t)
+	createFileReq := &types.CreateFileReq{
+		ZoneId:     ZoneId,
+		Region:     Region,
+		BucketName: BucketName,
+		ParentIno:  FileParentIno,
+		FileName:   MigrateFileName,
+		Size:       CreateFileSize,
+		Type:       types.COMMON_FILE,
+		Perm:       types.FILE_PERM,
+		Machine:    Machine,
+	}
+
+	createFileResp, createFileInfo, err := PutFile(createFileReq)
+	r.Nil(err)
+	r.Equal(createFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to create new file, resp: %s", createFileInfo)
+	ino := createFileResp.File.Ino
+
+	getLeaderReq := &types.GetLeaderReq {
+		ZoneId: ZoneId,
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		Machine: Machine,
+	}
+
+	getLeaderResp, getLeaderInfo, err := GetFileLeader(getLeaderReq)
+	r.Nil(err)
+	r.Equal(getLeaderResp.Result.ErrCode, 0)
+	r.Equal(getLeaderResp.LeaderInfo.Leader, Machine)
+	t.Logf("Succeed to get file leader, resp: %s", getLeaderInfo)
+	token := getLeaderResp.LeaderInfo.Token
+
+	// the stale token is rejected.
+	migrateReq := &types.ChangeLeaderReq {
+		ZoneId: ZoneId,
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		Machine: Machine2,
+		Token: token + 1,
+	}
+
+	migrateResp, migrateInfo, err := MigrateFileLeader(migrateReq)
+	r.Nil(err)
+	r.Equal(migrateResp.Result.ErrCode, 40015)
+	t.Logf("Succeed to resp err, for the token is stale, resp: %s", migrateInfo)
+
+	// the machine which is not up cannot be the leader.
+	migrateReq.Token = token
+	migrateReq.Machine = UnknownMachine
+	migrateResp, migrateInfo, err = MigrateFileLeader(migrateReq)
+	r.Nil(err)
+	r.Equal(migrateResp.Result.ErrCode, 40016)
+	t.Logf("Succeed to resp err, for the machine is not up, resp: %s", migrateInfo)
+
+	migrateReq.Machine = Machine2
+	migrateResp, migrateInfo, err = MigrateFileLeader(migrateReq)
+	r.Nil(err)
+	r.Equal(migrateResp.Result.ErrCode, 0)
+	r.Equal(migrateResp.LeaderInfo.Leader, Machine2)
+	r.Equal(migrateResp.LeaderInfo.Token, token + 1)
+	t.Logf("Succeed to migrate file leader, resp: %s", migrateInfo)
+
+	getLeaderResp, getLeaderInfo, err = GetFileLeader(getLeaderReq)
+	r.Nil(err)
+	r.Equal(getLeaderResp.Result.ErrCode, 0)
+	r.Equal(getLeaderResp.LeaderInfo.Leader, Machine2)
+	r.Equal(getLeaderResp.LeaderInfo.Token, token + 1)
+	t.Logf("Succeed to get the migrated file leader, resp: %s", getLeaderInfo)
+
+	// the leader is up, so it is not taken over by the transfer.
+	transferReq := &types.ChangeLeaderReq {
+		ZoneId: ZoneId,
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		Machine: Machine,
+		Token: token + 1,
+	}
+
+	transferResp, transferInfo, err := TransferFileLeader(transferReq)
+	r.Nil(err)
+	r.Equal(transferResp.Result.ErrCode, 0)
+	r.Equal(transferResp.LeaderInfo.Leader, Machine2)
+	r.Equal(transferResp.LeaderInfo.Token, token + 1)
+	t.Logf("Succeed to keep the up leader, resp: %s", transferInfo)
+
+	deleteFileReq := &types.DeleteFileReq {
+		Region: Region,
+		BucketName: BucketName,
+		Ino: ino,
+		ZoneId: ZoneId,
+		Machine: Machine2,
+	}
+
+	deleteFileResp, deleteFileRespInfo, err := DeleteFile(deleteFileReq)
+	r.Nil(err)
+	r.Equal(deleteFileResp.Result.ErrCode, 0)
+	t.Logf("Succeed to delete the migrated file, resp: %s", deleteFileRespInfo)
+}
@@ -40,6 +40,15 @@ const (
 	LatestedOffset = 256
 	NewFileName = "test.txt2"
 	DirName = "testDir"
+	RmDirName = "testRmDir"
+	LinkName = "testLink"
+	UsageFileName = "testUsage.txt"
+	XattrFileName = "testXattr.txt"
+	XattrName = "user.test"
+	XattrValue = "test value"
+	OrphanFileName = "testOrphan.txt"
+	MigrateFileName = "testMigrate.txt"
+	UnknownMachine = "172.20.13.200"
 )
 
 func SendHttpToYigFs(method string, newServer string, client *http.Client, reqStr []byte) (result io.ReadCloser, err error) {
@@ -17,11 +17,20 @@ const (
 const (
 	COMMON_FILE = iota + 1
 	DIR_FILE
+	LINK_FILE
 )
 
 const (
 	FILE_PERM = 644
 	DIR_PERM = 755
+	// the perm of the symlink is always 0777.
+	LINK_PERM = 0777
+)
+
+// the flags of setxattr, 0 means create or replace.
+const (
+	XATTR_CREATE = iota + 1
+	XATTR_REPLACE
 )
 
 const (
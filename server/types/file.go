@@ -163,3 +163,55 @@ type RenameFileReq struct {
 	NewParentIno *uint64 `json:"new_parent"`
 	NewFileName string `json:"new_name"`
 }
+
+type DeleteDirReq struct {
+	Ctx context.Context `json:"-"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+	ZoneId string `json:"zone"`
+	Machine string `json:"machine"`
+	ParentIno uint64 `json:"parent_ino"`
+	FileName string `json:"file_name"`
+}
+
+type DeleteDirResp struct {
+	Result YigFsMetaError `json:"result"`
+	File *FileInfo `json:"file"`
+}
+
+type CreateSymlinkReq struct {
+	Ctx context.Context `json:"-"`
+	ZoneId string `json:"zone"`
+	Machine string `json:"machine"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+	ParentIno uint64 `json:"parent_ino"`
+	FileName string `json:"file_name"`
+	Type uint `json:"type"`
+	Perm uint32 `json:"perm"`
+	Uid uint32 `json:"uid"`
+	Gid uint32 `json:"gid"`
+	Target string `json:"target"`
+}
+
+type CreateSymlinkResp struct {
+	Result YigFsMetaError `json:"result"`
+	File *FileInfo `json:"file"`
+}
+
+type ReadSymlinkResp struct {
+	Result YigFsMetaError `json:"result"`
+	Target string `json:"target"`
+}
+
+type GetFsUsageReq struct {
+	Ctx context.Context `json:"-"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+}
+
+type GetFsUsageResp struct {
+	Result YigFsMetaError `json:"result"`
+	UsedSize uint64 `json:"used_size"`
+	FileCount uint64 `json:"file_count"`
+}
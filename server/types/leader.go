@@ -22,6 +22,20 @@ type GetLeaderResp struct {
 type LeaderInfo struct {
 	ZoneId string `json:"zone"`
 	Leader string `json:"leader"`
+	// the fencing token of the leader, it is increased each time the leader is changed.
+	Token uint64 `json:"token"`
+}
+
+// transfer or migrate the file leader to machine, it is changed only if token is held by the current leader.
+type ChangeLeaderReq struct {
+	Ctx context.Context `json:"-"`
+	ZoneId string `json:"zone"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+	Ino uint64 `json:"ino"`
+	Generation uint64 `json:"generation"`
+	Machine string `json:"machine"`
+	Token uint64 `json:"token"`
 }
 
 type GetSegLeaderReq struct {
@@ -0,0 +1,38 @@
+package types
+
+import (
+	"context"
+)
+
+type AddOrphanReq struct {
+	Ctx context.Context `json:"-"`
+	ZoneId string `json:"zone"`
+	Machine string `json:"machine"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+	ParentIno uint64 `json:"parent_ino"`
+	FileName string `json:"file_name"`
+	Ino uint64 `json:"ino"`
+}
+
+type OrphanReq struct {
+	Ctx context.Context `json:"-"`
+	ZoneId string `json:"zone"`
+	Machine string `json:"machine"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+	Ino uint64 `json:"ino"`
+}
+
+type ListOrphansReq struct {
+	Ctx context.Context `json:"-"`
+	ZoneId string `json:"zone"`
+	Machine string `json:"machine"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+}
+
+type ListOrphansResp struct {
+	Result YigFsMetaError `json:"result"`
+	Inos []uint64 `json:"inos"`
+}
@@ -0,0 +1,33 @@
+package types
+
+import (
+	"context"
+)
+
+type SetXattrReq struct {
+	Ctx context.Context `json:"-"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+	Ino uint64 `json:"ino"`
+	Name string `json:"name"`
+	Value []byte `json:"value"`
+	Flags int `json:"flags"`
+}
+
+type GetXattrReq struct {
+	Ctx context.Context `json:"-"`
+	Region string `json:"region"`
+	BucketName string `json:"bucket"`
+	Ino uint64 `json:"ino"`
+	Name string `json:"name"`
+}
+
+type GetXattrResp struct {
+	Result YigFsMetaError `json:"result"`
+	Value []byte `json:"value"`
+}
+
+type ListXattrsResp struct {
+	Result YigFsMetaError `json:"result"`
+	Names []string `json:"names"`
+}